@@ -0,0 +1,80 @@
+//! Probe the compiler for features the crate can take advantage of.
+//!
+//! Each probe compiles a tiny `no_std` library; if it builds, the matching cfg is set.
+//! The cfgs can still be set manually through `RUSTFLAGS` instead.
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+fn probe(name: &str, code: &str) -> bool {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR not set"));
+    let crate_name = format!("thin_dst_probe_{}", name);
+    let src = out_dir.join(format!("{}.rs", crate_name));
+    if fs::write(&src, code).is_err() {
+        return false;
+    }
+
+    let mut cmd = Command::new(rustc);
+    cmd.args(["--crate-type=lib", "--emit=metadata", "--edition=2018"])
+        .arg("--crate-name")
+        .arg(&crate_name)
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(&src)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(target) = env::var_os("TARGET") {
+        cmd.arg("--target").arg(target);
+    }
+    cmd.status().map(|status| status.success()).unwrap_or(false)
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // https://github.com/rust-lang/rust/issues/36925
+    if probe(
+        "slice_from_raw_parts",
+        "#![no_std] pub fn f(p: *const u8) -> *const [u8] { core::ptr::slice_from_raw_parts(p, 0) }",
+    ) {
+        println!("cargo:rustc-cfg=slice_from_raw_parts");
+    }
+
+    // https://github.com/rust-lang/rust/issues/55724
+    if probe(
+        "alloc_layout_extra",
+        "#![no_std] use core::alloc::Layout;
+        pub fn f() -> Layout {
+            let (layout, _) = Layout::new::<u8>().extend(Layout::array::<u16>(1).unwrap()).unwrap();
+            layout.pad_to_align()
+        }",
+    ) {
+        println!("cargo:rustc-cfg=alloc_layout_extra");
+    }
+
+    if probe(
+        "inline_asm",
+        "#![no_std] pub fn f() { unsafe { core::arch::asm!(\"\") } }",
+    ) {
+        println!("cargo:rustc-cfg=inline_asm");
+    }
+
+    // The `nightly` feature only takes effect on a compiler that accepts the features,
+    // so that enabling it (e.g. with `--all-features`) keeps stable builds working.
+    // Only `may_dangle` is probed; see the feature's comment in Cargo.toml
+    // for why `TrustedLen`, `allocator_api` and extern types are left out.
+    if env::var_os("CARGO_FEATURE_NIGHTLY").is_some()
+        && probe(
+            "nightly",
+            "#![no_std] #![feature(dropck_eyepatch)]
+            pub struct S<T>(T);
+            unsafe impl<#[may_dangle] T> Drop for S<T> { fn drop(&mut self) {} }",
+        )
+    {
+        println!("cargo:rustc-cfg=thin_dst_nightly");
+    }
+}
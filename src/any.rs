@@ -0,0 +1,115 @@
+use {
+    crate::{align_up, ErasedPtr, Len, ThinData, ThinRef},
+    core::{
+        any::{Any, TypeId},
+        mem,
+        ops::{Deref, DerefMut},
+    },
+};
+
+/// A head tagged with its type, so that it can be downcast from an erased pointer.
+///
+/// Nodes of a heterogeneous graph can share a slice type but have different heads.
+/// With each head wrapped in `HeadAny`, the graph can store its nodes as
+/// [`ErasedPtr`]s and recover the concrete head type with [`ThinRef::downcast`].
+///
+/// The head type must not need more alignment than [`TypeId`], such that the
+/// tag is at the same offset for every head; this is checked at compile time.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// struct Add;
+/// struct Const(i64);
+///
+/// let nodes = [
+///     ThinBox::erase(ThinBox::new(HeadAny::new(Add), vec![1usize, 2])),
+///     ThinBox::erase(ThinBox::new(HeadAny::new(Const(7)), Vec::<usize>::new())),
+/// ];
+/// let konst = unsafe { ThinRef::<HeadAny<Const>, usize>::downcast(nodes[1]) }.unwrap();
+/// assert_eq!(konst.head.0, 7);
+/// assert!(unsafe { ThinRef::<HeadAny<Const>, usize>::downcast(nodes[0]) }.is_none());
+/// # unsafe {
+/// #     drop(ThinBox::<HeadAny<Add>, usize>::from_erased(nodes[0]));
+/// #     drop(ThinBox::<HeadAny<Const>, usize>::from_erased(nodes[1]));
+/// # }
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HeadAny<Head> {
+    type_id: TypeId,
+    /// The wrapped head.
+    pub head: Head,
+}
+
+/// The offset of the type tag of a `HeadAny` head in a `ThinData`.
+const TAG_OFFSET: usize = align_up(mem::size_of::<Len>(), mem::align_of::<TypeId>());
+
+impl<Head: 'static> HeadAny<Head> {
+    const TAG_AT_FIXED_OFFSET: () = assert!(
+        mem::align_of::<Head>() <= mem::align_of::<TypeId>(),
+        "HeadAny head is more aligned than TypeId",
+    );
+
+    /// Tag a head with its type.
+    pub fn new(head: Head) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::TAG_AT_FIXED_OFFSET;
+        HeadAny {
+            type_id: TypeId::of::<Head>(),
+            head,
+        }
+    }
+
+    /// Borrow the head as `dyn Any`.
+    pub fn as_any(&self) -> &dyn Any {
+        &self.head
+    }
+}
+
+impl<Head> HeadAny<Head> {
+    /// The `TypeId` of the head.
+    pub fn head_type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Unwrap the head.
+    pub fn into_inner(self) -> Head {
+        self.head
+    }
+}
+
+impl<Head> Deref for HeadAny<Head> {
+    type Target = Head;
+    fn deref(&self) -> &Head {
+        &self.head
+    }
+}
+
+impl<Head> DerefMut for HeadAny<Head> {
+    fn deref_mut(&mut self) -> &mut Head {
+        &mut self.head
+    }
+}
+
+impl<'a, Head: 'static, SliceItem> ThinRef<'a, HeadAny<Head>, SliceItem> {
+    /// Borrow the `ThinData` behind an erased pointer, if its head is a `HeadAny<Head>`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a `ThinData<HeadAny<H>, SliceItem>`, for some `H`,
+    /// which lives and isn't mutated for `'a`.
+    pub unsafe fn downcast(ptr: ErasedPtr) -> Option<Self> {
+        debug_assert_eq!(
+            ThinData::<HeadAny<Head>, SliceItem>::HEAD_OFFSET,
+            TAG_OFFSET
+        );
+        let type_id = ptr.as_ptr().add(TAG_OFFSET).cast::<TypeId>().read();
+        if type_id == TypeId::of::<Head>() {
+            Some(ThinRef::from_erased(ptr))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,538 @@
+use {
+    crate::{
+        jagged::Row,
+        polyfill::{extend_layout, layout_array, make_slice, make_slice_mut, pad_layout_to_align},
+        ThinBox, ThinData, ThinJagged,
+    },
+    alloc::{
+        alloc::{alloc, dealloc, handle_alloc_error, Layout, LayoutError},
+        vec::Vec,
+    },
+    core::{
+        fmt::{self, Debug},
+        hash,
+        marker::PhantomData,
+        mem,
+        ptr::{self, NonNull},
+        sync::atomic::{self, AtomicUsize, Ordering},
+    },
+};
+
+/// A generational index into a [`ThinArena`].
+///
+/// The generation is bumped every time a slot is reused,
+/// so a stale id is detected rather than resolving to an unrelated node.
+/// A slot is retired once its generation runs out, rather than wrapping around.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ThinId {
+    /// The slot index in the arena.
+    pub index: u32,
+    /// The generation of the slot when this id was handed out.
+    pub generation: u32,
+}
+
+/// A head that embeds the [`ThinId`] it was allocated at in a [`ThinArena`].
+///
+/// Storing the id in the head means the arena doesn't need to track
+/// the generation of occupied slots separately, and that any `&ThinData`
+/// reached through other means can recover its own key.
+pub trait HeadWithId {
+    /// The id this head was created with.
+    fn id(&self) -> ThinId;
+}
+
+/// A typed [`ThinId`], which can only be resolved in an arena of the matching type.
+pub struct ThinKey<Head, SliceItem> {
+    id: ThinId,
+    marker: PhantomData<fn() -> ThinBox<Head, SliceItem>>,
+}
+
+impl<Head, SliceItem> ThinKey<Head, SliceItem> {
+    /// The untyped id of this key.
+    pub fn id(self) -> ThinId {
+        self.id
+    }
+}
+
+impl<Head, SliceItem> Copy for ThinKey<Head, SliceItem> {}
+impl<Head, SliceItem> Clone for ThinKey<Head, SliceItem> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<Head, SliceItem> Eq for ThinKey<Head, SliceItem> {}
+impl<Head, SliceItem> PartialEq for ThinKey<Head, SliceItem> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+impl<Head, SliceItem> hash::Hash for ThinKey<Head, SliceItem> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+impl<Head, SliceItem> Debug for ThinKey<Head, SliceItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ThinKey").field(&self.id).finish()
+    }
+}
+
+enum Slot<Head, SliceItem> {
+    Occupied(ThinBox<Head, SliceItem>),
+    Vacant {
+        generation: u32,
+        next_free: Option<u32>,
+    },
+}
+
+/// A generational arena of `ThinBox`es, addressed by [`ThinKey`].
+///
+/// Each node's head records its own id (see [`HeadWithId`]),
+/// which is checked against the key on every lookup.
+pub struct ThinArena<Head, SliceItem> {
+    slots: Vec<Slot<Head, SliceItem>>,
+    free: Option<u32>,
+    len: usize,
+}
+
+impl<Head, SliceItem> Default for ThinArena<Head, SliceItem> {
+    fn default() -> Self {
+        ThinArena {
+            slots: Vec::new(),
+            free: None,
+            len: 0,
+        }
+    }
+}
+
+impl<Head: HeadWithId, SliceItem> ThinArena<Head, SliceItem> {
+    /// Create a new, empty arena.
+    pub fn new() -> Self {
+        ThinArena::default()
+    }
+
+    /// The number of nodes in the arena.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Does the arena contain no nodes?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The key of a node in this arena, recovered from its head.
+    pub fn key_of(node: &ThinData<Head, SliceItem>) -> ThinKey<Head, SliceItem> {
+        ThinKey {
+            id: node.head.id(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Insert a new node, passing the id it will live at to the head constructor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length,
+    /// if the constructed head does not report the id it was given,
+    /// or if the arena already holds `u32::MAX` slots.
+    pub fn insert_with<F, I>(&mut self, make_head: F, slice: I) -> ThinKey<Head, SliceItem>
+    where
+        F: FnOnce(ThinId) -> Head,
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let id = match self.free {
+            Some(index) => match self.slots[index as usize] {
+                Slot::Vacant { generation, .. } => ThinId { index, generation },
+                Slot::Occupied(_) => unreachable!("free list points to occupied slot"),
+            },
+            None => {
+                assert!(self.slots.len() < u32::MAX as usize, "ThinArena is full");
+                ThinId {
+                    index: self.slots.len() as u32,
+                    generation: 0,
+                }
+            }
+        };
+
+        let node = ThinBox::new(make_head(id), slice);
+        assert_eq!(node.head.id(), id, "head did not record its given id");
+
+        if let Some(index) = self.free {
+            let slot = mem::replace(&mut self.slots[index as usize], Slot::Occupied(node));
+            if let Slot::Vacant { next_free, .. } = slot {
+                self.free = next_free;
+            }
+        } else {
+            self.slots.push(Slot::Occupied(node));
+        }
+        self.len += 1;
+
+        ThinKey {
+            id,
+            marker: PhantomData,
+        }
+    }
+
+    fn occupied(&self, key: ThinKey<Head, SliceItem>) -> Option<&ThinBox<Head, SliceItem>> {
+        match self.slots.get(key.id.index as usize)? {
+            Slot::Occupied(node) if node.head.id() == key.id => Some(node),
+            _ => None,
+        }
+    }
+
+    /// Resolve a key, returning `None` if it is stale.
+    pub fn get(&self, key: ThinKey<Head, SliceItem>) -> Option<&ThinData<Head, SliceItem>> {
+        self.occupied(key).map(|node| &**node)
+    }
+
+    /// Resolve a key mutably, returning `None` if it is stale.
+    ///
+    /// Note that changing the id reported by the head
+    /// will make the node unreachable by its key.
+    pub fn get_mut(
+        &mut self,
+        key: ThinKey<Head, SliceItem>,
+    ) -> Option<&mut ThinData<Head, SliceItem>> {
+        self.occupied(key)?;
+        match &mut self.slots[key.id.index as usize] {
+            Slot::Occupied(node) => Some(&mut **node),
+            Slot::Vacant { .. } => None,
+        }
+    }
+
+    /// Remove a node from the arena, returning `None` if the key is stale.
+    ///
+    /// The slot's generation is bumped, so the key will not resolve again.
+    /// A slot whose generation would wrap around is retired instead,
+    /// and never reused, so that no stale key can ever match it again.
+    pub fn remove(&mut self, key: ThinKey<Head, SliceItem>) -> Option<ThinBox<Head, SliceItem>> {
+        self.occupied(key)?;
+        let generation = key.id.generation.checked_add(1);
+        let vacant = Slot::Vacant {
+            generation: generation.unwrap_or(u32::MAX),
+            next_free: if generation.is_some() {
+                self.free
+            } else {
+                None
+            },
+        };
+        match mem::replace(&mut self.slots[key.id.index as usize], vacant) {
+            Slot::Occupied(node) => {
+                if generation.is_some() {
+                    self.free = Some(key.id.index);
+                }
+                self.len -= 1;
+                Some(node)
+            }
+            Slot::Vacant { .. } => unreachable!(),
+        }
+    }
+
+    /// Iterate over all nodes in the arena with their keys.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (ThinKey<Head, SliceItem>, &ThinData<Head, SliceItem>)> {
+        self.slots.iter().filter_map(|slot| match slot {
+            Slot::Occupied(node) => Some((Self::key_of(node), &**node)),
+            Slot::Vacant { .. } => None,
+        })
+    }
+}
+
+impl<Head: HeadWithId, SliceItem> ThinArena<Head, SliceItem> {
+    /// Compact the arena into an immutable, shareable snapshot.
+    ///
+    /// The nodes are moved into one shared allocation, in slot order,
+    /// and freed. That allocation also holds the row of each slot,
+    /// so keys into the arena keep resolving in the snapshot
+    /// without chasing another pointer.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// struct Node(ThinId, &'static str);
+    /// impl HeadWithId for Node {
+    ///     fn id(&self) -> ThinId {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let mut arena = ThinArena::new();
+    /// let a = arena.insert_with(|id| Node(id, "a"), vec![1, 2]);
+    /// let b = arena.insert_with(|id| Node(id, "b"), vec![3]);
+    /// arena.remove(a);
+    ///
+    /// let frozen = arena.freeze();
+    /// let shared = frozen.clone();
+    /// assert!(shared.get(a).is_none());
+    /// let (node, slice) = shared.get(b).unwrap();
+    /// assert_eq!((node.1, slice), ("b", &[3][..]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot would exceed `isize::MAX` bytes,
+    /// or the combined slice length would overflow `usize`.
+    pub fn freeze(self) -> FrozenArena<Head, SliceItem> {
+        let items = self.slots.iter().fold(0, |items: usize, slot| match slot {
+            // Zero-sized items don't bound the total through the layout.
+            Slot::Occupied(node) => items
+                .checked_add(node.slice.len())
+                .unwrap_or_else(|| panic!("oversize frozen arena: too many slice items")),
+            Slot::Vacant { .. } => items,
+        });
+        let (layout, _) = FrozenArena::<Head, SliceItem>::layout(self.slots.len(), self.len, items);
+        let raw =
+            unsafe { NonNull::new(alloc(layout)).unwrap_or_else(|| handle_alloc_error(layout)) };
+        let this = FrozenArena::<Head, SliceItem> {
+            raw: raw.cast::<FrozenHeader>(),
+            marker: PhantomData,
+        };
+        unsafe {
+            ptr::write(
+                this.raw.as_ptr(),
+                FrozenHeader {
+                    strong: AtomicUsize::new(1),
+                    slots: self.slots.len(),
+                    rows: self.len,
+                    items,
+                },
+            );
+        }
+        let (index, rows, slices) = this.parts();
+        let (mut row, mut end) = (0, 0);
+        // Nothing in here can panic, so the nodes are never left half-moved.
+        for (i, slot) in self.slots.into_iter().enumerate() {
+            unsafe {
+                match slot {
+                    Slot::Occupied(node) => {
+                        // There are fewer rows than slots, so this is below `VACANT_ROW`.
+                        ptr::write(index.add(i), row as u32);
+                        let moved = Row::move_from(node, slices, end);
+                        end = moved.end;
+                        ptr::write(rows.add(row), moved);
+                        row += 1;
+                    }
+                    Slot::Vacant { .. } => ptr::write(index.add(i), VACANT_ROW),
+                }
+            }
+        }
+        this
+    }
+}
+
+/// The row of a vacant slot in a [`FrozenArena`].
+const VACANT_ROW: u32 = u32::MAX;
+
+/// The most clones of a [`FrozenArena`] that can be alive at once, as for `Arc`.
+const MAX_REFCOUNT: usize = isize::MAX as usize;
+
+#[repr(C)]
+struct FrozenHeader {
+    strong: AtomicUsize,
+    slots: usize,
+    rows: usize,
+    items: usize,
+}
+
+/// An immutable snapshot of a [`ThinArena`], shared by reference counting.
+///
+/// Created by [`ThinArena::freeze`]. Cloning it is cheap.
+///
+/// The snapshot is a single allocation holding the reference count and the
+/// number of slots, rows and items, then the row of each slot, then each
+/// row's head and end offset, then every row's slice items back to back.
+/// This can't be a `ThinArc`, whose head and slice item are each one sized
+/// type, so the snapshot keeps its own count, with the same semantics.
+pub struct FrozenArena<Head, SliceItem> {
+    raw: NonNull<FrozenHeader>,
+    marker: PhantomData<ThinJagged<Head, SliceItem>>,
+}
+
+unsafe impl<Head: Send + Sync, SliceItem: Send + Sync> Send for FrozenArena<Head, SliceItem> {}
+unsafe impl<Head: Send + Sync, SliceItem: Send + Sync> Sync for FrozenArena<Head, SliceItem> {}
+
+impl<Head, SliceItem> FrozenArena<Head, SliceItem> {
+    fn try_layout(
+        slots: usize,
+        rows: usize,
+        items: usize,
+    ) -> Result<(Layout, [usize; 3]), LayoutError> {
+        let header = Layout::new::<FrozenHeader>();
+        let (layout, index_offset) = extend_layout(&header, layout_array::<u32>(slots)?)?;
+        let (layout, rows_offset) = extend_layout(&layout, layout_array::<Row<Head>>(rows)?)?;
+        let (layout, items_offset) = extend_layout(&layout, layout_array::<SliceItem>(items)?)?;
+        Ok((
+            pad_layout_to_align(&layout),
+            [index_offset, rows_offset, items_offset],
+        ))
+    }
+
+    fn layout(slots: usize, rows: usize, items: usize) -> (Layout, [usize; 3]) {
+        Self::try_layout(slots, rows, items)
+            .unwrap_or_else(|e| panic!("oversize frozen arena: {}", e))
+    }
+
+    fn header(&self) -> &FrozenHeader {
+        unsafe { self.raw.as_ref() }
+    }
+
+    fn parts(&self) -> (*mut u32, *mut Row<Head>, *mut SliceItem) {
+        let header = self.header();
+        let (_, [index_offset, rows_offset, items_offset]) =
+            Self::layout(header.slots, header.rows, header.items);
+        let raw = self.raw.as_ptr().cast::<u8>();
+        unsafe {
+            (
+                raw.add(index_offset).cast(),
+                raw.add(rows_offset).cast(),
+                raw.add(items_offset).cast(),
+            )
+        }
+    }
+
+    fn row(&self, index: usize) -> Option<(&Head, &[SliceItem])> {
+        let (_, rows, slices) = self.parts();
+        let rows = unsafe { &*make_slice(rows, self.header().rows) };
+        let row = rows.get(index)?;
+        let start = if index == 0 { 0 } else { rows[index - 1].end };
+        let slice = unsafe { &*make_slice(slices.add(start), row.end - start) };
+        Some((&row.head, slice))
+    }
+}
+
+impl<Head: HeadWithId, SliceItem> FrozenArena<Head, SliceItem> {
+    /// The number of nodes in the snapshot.
+    pub fn len(&self) -> usize {
+        self.header().rows
+    }
+
+    /// Does the snapshot contain no nodes?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Resolve a key, returning `None` if it was stale when the arena was frozen.
+    pub fn get(&self, key: ThinKey<Head, SliceItem>) -> Option<(&Head, &[SliceItem])> {
+        let (index, _, _) = self.parts();
+        let index = unsafe { &*make_slice(index, self.header().slots) };
+        let &row = index.get(key.id.index as usize)?;
+        let (head, slice) = self.row(row as usize)?;
+        if head.id() == key.id {
+            Some((head, slice))
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over all nodes in the snapshot with their keys, in slot order.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (ThinKey<Head, SliceItem>, &Head, &[SliceItem])> + '_ {
+        (0..self.len()).map(move |row| match self.row(row) {
+            Some((head, slice)) => {
+                let key = ThinKey {
+                    id: head.id(),
+                    marker: PhantomData,
+                };
+                (key, head, slice)
+            }
+            None => unreachable!(),
+        })
+    }
+}
+
+impl<Head, SliceItem> Clone for FrozenArena<Head, SliceItem> {
+    fn clone(&self) -> Self {
+        // Like `Arc`, a new reference only needs the count to be kept alive.
+        let old = self.header().strong.fetch_add(1, Ordering::Relaxed);
+        if old > MAX_REFCOUNT {
+            abort_refcount_overflow();
+        }
+        FrozenArena {
+            raw: self.raw,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Abort the process, as `Arc` does when its count overflows.
+///
+/// There's no `std::process::abort` without `std`, but a panic while unwinding aborts.
+#[cold]
+fn abort_refcount_overflow() -> ! {
+    struct Abort;
+    impl Drop for Abort {
+        fn drop(&mut self) {
+            panic!("FrozenArena reference count overflow");
+        }
+    }
+    let _abort = Abort;
+    panic!("FrozenArena reference count overflow");
+}
+
+impl<Head, SliceItem> Drop for FrozenArena<Head, SliceItem> {
+    fn drop(&mut self) {
+        if self.header().strong.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+        // Synchronize with every other clone's release of the count,
+        // so their uses of the contents happen before dropping them.
+        atomic::fence(Ordering::Acquire);
+
+        /// Frees the allocation, even if a head or item panics while dropping.
+        struct Dealloc(*mut u8, Layout);
+        impl Drop for Dealloc {
+            fn drop(&mut self) {
+                unsafe { dealloc(self.0, self.1) };
+            }
+        }
+
+        /// Drops the slice items after the rows, even if a head panics.
+        struct DropItems<SliceItem>(*mut [SliceItem]);
+        impl<SliceItem> Drop for DropItems<SliceItem> {
+            fn drop(&mut self) {
+                unsafe { ptr::drop_in_place(self.0) };
+            }
+        }
+
+        let &FrozenHeader {
+            slots, rows, items, ..
+        } = self.header();
+        let (layout, _) = Self::layout(slots, rows, items);
+        let (_, row_ptr, slice_ptr) = self.parts();
+        let _dealloc = Dealloc(self.raw.as_ptr().cast(), layout);
+        let _items;
+        unsafe {
+            _items = DropItems(make_slice_mut(slice_ptr, items));
+            ptr::drop_in_place(make_slice_mut(row_ptr, rows));
+        }
+    }
+}
+
+impl<Head, SliceItem> Debug for FrozenArena<Head, SliceItem>
+where
+    Head: Debug,
+    SliceItem: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.header().rows).filter_map(|row| self.row(row)))
+            .finish()
+    }
+}
+
+impl<Head, SliceItem> Debug for ThinArena<Head, SliceItem>
+where
+    ThinData<Head, SliceItem>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.slots.iter().filter_map(|slot| match slot {
+                Slot::Occupied(node) => Some(&**node),
+                Slot::Vacant { .. } => None,
+            }))
+            .finish()
+    }
+}
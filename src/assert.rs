@@ -0,0 +1,54 @@
+/// Assert at compile time that types are thin, nullable pointers.
+///
+/// For each type, this checks that it has the size and alignment of a pointer,
+/// so it can stand in for one (e.g. in a `#[repr(transparent)]` wrapper or
+/// across FFI), and that `Option` of it is no bigger, so the null niche is kept.
+/// This catches a wrapper around a thin pointer accidentally growing a field.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// #[repr(transparent)]
+/// struct Node(ThinArc<u32, Node>);
+///
+/// assert_thin!(Node, ThinBox<String, u8>, ThinRef<'static, (), ()>);
+/// ```
+///
+/// ```rust,compile_fail
+/// # use thin_dst::*;
+/// struct Tagged(ThinBox<(), u8>, bool);
+///
+/// assert_thin!(Tagged);
+/// ```
+#[macro_export]
+macro_rules! assert_thin {
+    ($($ty:ty),+ $(,)?) => {$(
+        const _: () = {
+            use ::core::mem::{align_of, size_of};
+            assert!(
+                size_of::<$ty>() == size_of::<*const ()>(),
+                concat!("`", stringify!($ty), "` is not pointer-sized"),
+            );
+            assert!(
+                align_of::<$ty>() == align_of::<*const ()>(),
+                concat!("`", stringify!($ty), "` is not pointer-aligned"),
+            );
+            assert!(
+                size_of::<::core::option::Option<$ty>>() == size_of::<$ty>(),
+                concat!("`Option<", stringify!($ty), ">` has no niche"),
+            );
+        };
+    )+};
+}
+
+assert_thin!(
+    crate::ErasedPtr,
+    crate::ThinBox<(), ()>,
+    crate::ThinArc<(), ()>,
+    crate::ThinWeak<(), ()>,
+    crate::ThinRc<(), ()>,
+    crate::ThinRef<'static, (), ()>,
+    crate::ThinRefMut<'static, (), ()>,
+    crate::ThinPtr<(), ()>,
+);
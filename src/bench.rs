@@ -0,0 +1,133 @@
+//! Deterministic synthetic workloads for benchmarks.
+//!
+//! The generators here build large trees and buffers of a configurable shape
+//! from a seed, so benchmarks downstream (and performance reports in issues)
+//! can share a workload by sharing its shape. Everything is built up front
+//! and returned by value, so it can be set up outside of the measured code
+//! and passed through `black_box`.
+//!
+//! ```rust
+//! use thin_dst::bench::{BufferShape, TreeShape};
+//!
+//! let shape = TreeShape { depth: 4, fanout: 3, seed: 1 };
+//! let tree = shape.build();
+//! assert_eq!(tree.slice.len(), 3);
+//! assert_eq!(shape.build(), tree);
+//!
+//! let buffers = BufferShape { count: 10, min_len: 0, max_len: 64, seed: 1 }.build();
+//! assert_eq!(buffers.len(), 10);
+//! ```
+
+use {
+    crate::{ThinArc, ThinBox},
+    alloc::vec::Vec,
+};
+
+/// A node of a tree built by [`TreeShape::build`].
+///
+/// The head is a pseudo-random payload, and the slice holds the children.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BenchNode(pub ThinArc<u64, BenchNode>);
+
+/// The shape of a complete tree of [`BenchNode`]s.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TreeShape {
+    /// The number of levels, including the root and the leaves.
+    /// Zero is treated as one.
+    pub depth: usize,
+    /// The number of children of each node above the leaves.
+    pub fanout: usize,
+    /// The seed the payloads are generated from.
+    pub seed: u64,
+}
+
+impl TreeShape {
+    /// The number of nodes in the tree, or `None` if it overflows `usize`.
+    pub fn nodes(&self) -> Option<usize> {
+        let mut level = 1usize;
+        let mut nodes = 1usize;
+        for _ in 1..self.depth {
+            level = level.checked_mul(self.fanout)?;
+            nodes = nodes.checked_add(level)?;
+        }
+        Some(nodes)
+    }
+
+    /// Build the tree, returning its root.
+    ///
+    /// The tree is built a level at a time from the leaves up,
+    /// so this doesn't recurse however deep the tree is
+    /// (though dropping it does, once per level).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of nodes overflows `usize`.
+    pub fn build(&self) -> ThinArc<u64, BenchNode> {
+        assert!(self.nodes().is_some(), "tree has too many nodes");
+        let mut rng = SplitMix64(self.seed);
+        let leaves = (0..self.fanout.pow(self.depth.saturating_sub(1) as u32))
+            .map(|_| BenchNode(ThinArc::new(rng.next(), None)))
+            .collect::<Vec<_>>();
+        let mut level = leaves;
+        for _ in 1..self.depth {
+            let mut children = level.into_iter();
+            level = (0..children.len() / self.fanout.max(1))
+                .map(|_| {
+                    let node = ThinArc::new(
+                        rng.next(),
+                        children.by_ref().take(self.fanout).collect::<Vec<_>>(),
+                    );
+                    BenchNode(node)
+                })
+                .collect();
+        }
+        match level.pop() {
+            Some(BenchNode(root)) => root,
+            None => ThinArc::new(rng.next(), None),
+        }
+    }
+}
+
+/// The shape of a batch of byte buffers with pseudo-random lengths and contents.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BufferShape {
+    /// The number of buffers.
+    pub count: usize,
+    /// The shortest length of a buffer.
+    pub min_len: usize,
+    /// The longest length of a buffer. Values below `min_len` are treated as `min_len`.
+    pub max_len: usize,
+    /// The seed the lengths and contents are generated from.
+    pub seed: u64,
+}
+
+impl BufferShape {
+    /// Build the buffers, each with its index as the head.
+    pub fn build(&self) -> Vec<ThinBox<u64, u8>> {
+        let mut rng = SplitMix64(self.seed);
+        let spread = self.max_len.saturating_sub(self.min_len) as u64;
+        (0..self.count as u64)
+            .map(|index| {
+                let len = match spread.checked_add(1) {
+                    Some(choices) => self.min_len + (rng.next() % choices) as usize,
+                    None => self.min_len + rng.next() as usize,
+                };
+                ThinBox::new(index, (0..len).map(|_| rng.next() as u8))
+            })
+            .collect()
+    }
+}
+
+/// A small, fast, and stable pseudo-random generator,
+/// so the workloads don't change between releases.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
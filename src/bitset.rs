@@ -0,0 +1,166 @@
+use {
+    crate::ThinBox,
+    core::{
+        cmp,
+        fmt::{self, Debug},
+        iter::FusedIterator,
+        mem,
+    },
+};
+
+const WORD_BITS: usize = mem::size_of::<usize>() * 8;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct BitsHead<Head> {
+    len: usize,
+    head: Head,
+}
+
+/// A fixed-size set of bits with a head, stored as a slice of words in a [`ThinBox`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let mut flags = ThinBitSet::new("flags", 100);
+/// flags.set(3, true);
+/// flags.set(70, true);
+/// assert!(flags.get(70));
+/// assert_eq!(flags.iter_ones().collect::<Vec<_>>(), [3, 70]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ThinBitSet<Head> {
+    raw: ThinBox<BitsHead<Head>, usize>,
+}
+
+impl<Head> ThinBitSet<Head> {
+    /// Create a new bitset of `len` bits, all unset.
+    pub fn new(head: Head, len: usize) -> Self {
+        let words = len / WORD_BITS + cmp::min(len % WORD_BITS, 1);
+        ThinBitSet {
+            raw: ThinBox::new(BitsHead { len, head }, (0..words).map(|_| 0)),
+        }
+    }
+
+    /// The number of bits in the set.
+    pub fn len(&self) -> usize {
+        self.raw.head.len
+    }
+
+    /// Is the set zero bits long?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Borrow the head.
+    pub fn head(&self) -> &Head {
+        &self.raw.head.head
+    }
+
+    /// Mutably borrow the head.
+    pub fn head_mut(&mut self) -> &mut Head {
+        &mut self.raw.head.head
+    }
+
+    /// The underlying words. Bit `i` is bit `i % usize::BITS` of word `i / usize::BITS`.
+    ///
+    /// Bits past `len` in the last word are always unset.
+    pub fn words(&self) -> &[usize] {
+        &self.raw.slice
+    }
+
+    fn check(&self, index: usize) {
+        assert!(
+            index < self.len(),
+            "bit index {} out of range for bitset of length {}",
+            index,
+            self.len()
+        );
+    }
+
+    /// Get the bit at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn get(&self, index: usize) -> bool {
+        self.check(index);
+        self.raw.slice[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0
+    }
+
+    /// Set the bit at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn set(&mut self, index: usize, value: bool) {
+        self.check(index);
+        let word = &mut self.raw.slice[index / WORD_BITS];
+        let mask = 1 << (index % WORD_BITS);
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// The number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words()
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Iterate over the indices of the set bits, in increasing order.
+    pub fn iter_ones(&self) -> IterOnes<'_> {
+        IterOnes {
+            words: self.words(),
+            base: 0,
+            current: self.words().first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl<Head: Debug> Debug for ThinBitSet<Head> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinBitSet")
+            .field("head", self.head())
+            .field("len", &self.len())
+            .field("ones", &DebugOnes(self.iter_ones()))
+            .finish()
+    }
+}
+
+struct DebugOnes<'a>(IterOnes<'a>);
+
+impl Debug for DebugOnes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.clone()).finish()
+    }
+}
+
+/// An iterator over the set bits of a [`ThinBitSet`]. See [`ThinBitSet::iter_ones`].
+#[derive(Debug, Clone)]
+pub struct IterOnes<'a> {
+    words: &'a [usize],
+    base: usize,
+    current: usize,
+}
+
+impl Iterator for IterOnes<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.words = self.words.get(1..).filter(|rest| !rest.is_empty())?;
+            self.base += WORD_BITS;
+            self.current = self.words[0];
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.base + bit)
+    }
+}
+
+impl FusedIterator for IterOnes<'_> {}
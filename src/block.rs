@@ -0,0 +1,269 @@
+#[cfg(feature = "std")]
+use std::io;
+use {
+    crate::{
+        codec::{self, Decode, DecodeError},
+        ErasedPtr, InProgress, ThinBox, ThinData, ThinRef,
+    },
+    alloc::{
+        alloc::{alloc, dealloc, handle_alloc_error, Layout},
+        vec::Vec,
+    },
+    core::{
+        fmt::{self, Debug},
+        marker::PhantomData,
+        ptr::{self, NonNull},
+    },
+};
+
+/// The size of the chunks records are allocated from, unless a record needs more.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// An append-only arena packing many `ThinData` records into a few large allocations.
+///
+/// Unlike a [`ThinArena`](crate::ThinArena), whose nodes are individually
+/// allocated `ThinBox`es, records here are bump-allocated from shared chunks,
+/// and can't be removed individually. This makes it cheap to load a whole file
+/// of records at once with [`ThinBlockArena::decode_all`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::{*, codec::*};
+/// let mut file = Vec::new();
+/// for i in 0..100u32 {
+///     ThinBox::new(i, 0..i as u8).encode(&mut file).unwrap();
+/// }
+///
+/// let index = ThinBlockArena::<u32, u8>::decode_all(&file).unwrap();
+/// assert_eq!(index.len(), 100);
+/// let record = index.get(42).unwrap();
+/// assert_eq!((record.head, record.slice.len()), (42, 42));
+/// ```
+pub struct ThinBlockArena<Head, SliceItem> {
+    /// Each chunk's allocation and layout.
+    chunks: Vec<(NonNull<u8>, Layout)>,
+    /// The number of bytes used in the last chunk.
+    used: usize,
+    records: Vec<ErasedPtr>,
+    marker: PhantomData<ThinBox<Head, SliceItem>>,
+}
+
+unsafe impl<Head: Send, SliceItem: Send> Send for ThinBlockArena<Head, SliceItem> {}
+unsafe impl<Head: Sync, SliceItem: Sync> Sync for ThinBlockArena<Head, SliceItem> {}
+
+impl<Head, SliceItem> Default for ThinBlockArena<Head, SliceItem> {
+    fn default() -> Self {
+        ThinBlockArena {
+            chunks: Vec::new(),
+            used: 0,
+            records: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Head, SliceItem> ThinBlockArena<Head, SliceItem> {
+    /// Create a new, empty arena.
+    pub fn new() -> Self {
+        ThinBlockArena::default()
+    }
+
+    /// The number of records in the arena.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether the arena has no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Borrow the record at `index`, in the order they were added.
+    pub fn get(&self, index: usize) -> Option<ThinRef<'_, Head, SliceItem>> {
+        let raw = *self.records.get(index)?;
+        Some(unsafe { ThinRef::from_erased(raw) })
+    }
+
+    /// Iterate over the records, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = ThinRef<'_, Head, SliceItem>> + '_ {
+        self.records
+            .iter()
+            .map(|&raw| unsafe { ThinRef::from_erased(raw) })
+    }
+
+    /// Add a record to the end of the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    pub fn push<I>(&mut self, head: Head, slice: I) -> ThinRef<'_, Head, SliceItem>
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let items = slice.into_iter();
+        let len = items.len();
+        let (layout, _) = InProgress::<Head, SliceItem>::layout(len);
+        let ptr = self.bump(layout);
+        let raw = unsafe { InProgress::in_place(len, ptr) }.fill_in_place(head, items);
+        self.records.push(raw);
+        unsafe { ThinRef::from_erased(raw) }
+    }
+
+    /// Create an arena from pairs of head and slice.
+    ///
+    /// Every record's layout is computed before any is written,
+    /// so all of them are placed in a single allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let arena = ThinBlockArena::new_many((0..1000u32).map(|i| (i, 0..i % 7)));
+    /// assert_eq!(arena.len(), 1000);
+    /// assert_eq!(arena.get(9).unwrap().slice, [0, 1]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if any slice iterator incorrectly reports its length.
+    pub fn new_many<I, S>(nodes: I) -> Self
+    where
+        I: IntoIterator<Item = (Head, S)>,
+        S: IntoIterator<Item = SliceItem>,
+        S::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let nodes: Vec<(Head, S::IntoIter)> = nodes
+            .into_iter()
+            .map(|(head, slice)| (head, slice.into_iter()))
+            .collect();
+        let (first, _) = InProgress::<Head, SliceItem>::layout(0);
+        let size = nodes.iter().fold(0, |size: usize, (_, items)| {
+            let (layout, _) = InProgress::<Head, SliceItem>::layout(items.len());
+            size.checked_add(layout.size())
+                .unwrap_or_else(|| panic!("oversize chunk"))
+        });
+
+        let mut this = ThinBlockArena::new();
+        this.records.reserve(nodes.len());
+        if size != 0 {
+            this.new_chunk(size, first.align());
+        }
+        for (head, items) in nodes {
+            this.push(head, items);
+        }
+        this
+    }
+
+    /// Start a new chunk of at least `size` bytes.
+    fn new_chunk(&mut self, size: usize, align: usize) {
+        let chunk_layout = Layout::from_size_align(size, align)
+            .unwrap_or_else(|e| panic!("oversize chunk: {}", e));
+        let chunk = NonNull::new(unsafe { alloc(chunk_layout) })
+            .unwrap_or_else(|| handle_alloc_error(chunk_layout));
+        self.chunks.push((chunk, chunk_layout));
+        self.used = 0;
+    }
+
+    /// Reserve room for a record of the given layout, starting a new chunk if needed.
+    fn bump(&mut self, layout: Layout) -> NonNull<u8> {
+        // Every record has the same alignment and a size padded to it,
+        // so records packed from the start of a chunk stay aligned.
+        let fits = match self.chunks.last() {
+            Some(&(_, chunk_layout)) => chunk_layout.size() - self.used >= layout.size(),
+            None => false,
+        };
+        if !fits {
+            self.new_chunk(layout.size().max(CHUNK_SIZE), layout.align());
+        }
+        let &(chunk, _) = self.chunks.last().unwrap();
+        let ptr = unsafe { NonNull::new_unchecked(chunk.as_ptr().add(self.used)) };
+        self.used += layout.size();
+        ptr
+    }
+}
+
+impl<Head: Decode, SliceItem: Decode> ThinBlockArena<Head, SliceItem> {
+    /// Decode every record in `input` into a new arena.
+    ///
+    /// The records are encoded back to back, as by [`Encode`](crate::codec::Encode).
+    pub fn decode_all(input: &[u8]) -> Result<Self, DecodeError> {
+        let mut this = ThinBlockArena::new();
+        let mut input = input;
+        while !input.is_empty() {
+            this.decode_one(&mut input)?;
+        }
+        Ok(this)
+    }
+
+    /// Decode one record from the front of `input` into the arena,
+    /// advancing `input` past it.
+    ///
+    /// A record claiming more (non-zero-sized) slice items than there are bytes
+    /// left is rejected, so a corrupt length can't cause a huge allocation.
+    /// Zero-sized slice items are limited to [`ZST_LEN_LIMIT`](codec::ZST_LEN_LIMIT).
+    pub fn decode_one(
+        &mut self,
+        input: &mut &[u8],
+    ) -> Result<ThinRef<'_, Head, SliceItem>, DecodeError> {
+        let len = usize::decode(input)?;
+        codec::check_len::<SliceItem>(len, input, codec::default_len_limit::<SliceItem>())?;
+        let head = Head::decode(input)?;
+        let (layout, _) =
+            ThinBox::<Head, SliceItem>::layout(len).map_err(|_| DecodeError::Invalid)?;
+
+        // If decoding fails, the reserved space is simply left unused.
+        let ptr = self.bump(layout);
+        let mut record = unsafe { InProgress::<Head, SliceItem>::in_place(len, ptr) };
+        record.write_head(head);
+        for _ in 0..len {
+            unsafe { record.push(SliceItem::decode(input)?) };
+        }
+        let raw = unsafe { record.into_erased() };
+        self.records.push(raw);
+        Ok(unsafe { ThinRef::from_erased(raw) })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Head: Decode, SliceItem: Decode> ThinBlockArena<Head, SliceItem> {
+    /// Read every record from `reader` into a new arena.
+    ///
+    /// The codec decodes from byte slices, so the whole stream is read
+    /// into memory first, then decoded as by [`ThinBlockArena::decode_all`].
+    pub fn read_all<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut input = Vec::new();
+        reader.read_to_end(&mut input)?;
+        ThinBlockArena::decode_all(&input).map_err(|e| {
+            let kind = match e {
+                DecodeError::UnexpectedEnd => io::ErrorKind::UnexpectedEof,
+                _ => io::ErrorKind::InvalidData,
+            };
+            io::Error::new(kind, e)
+        })
+    }
+}
+
+impl<Head, SliceItem> Drop for ThinBlockArena<Head, SliceItem> {
+    fn drop(&mut self) {
+        unsafe {
+            for &raw in &self.records {
+                ptr::drop_in_place(ThinData::<Head, SliceItem>::fatten_mut(raw).as_ptr());
+            }
+            for &(chunk, layout) in &self.chunks {
+                dealloc(chunk.as_ptr(), layout);
+            }
+        }
+    }
+}
+
+impl<Head, SliceItem> Debug for ThinBlockArena<Head, SliceItem>
+where
+    Head: Debug,
+    SliceItem: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,40 @@
+use {
+    crate::{InProgress, ThinBox},
+    ::bytes::Buf,
+};
+
+impl<Head> ThinBox<Head, u8> {
+    /// Create a new boxed `ThinData` with the given head and
+    /// all the bytes remaining in a [`Buf`].
+    ///
+    /// The bytes are copied a whole chunk at a time,
+    /// so chained buffers are copied with one copy per buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer runs out of chunks before `remaining` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// use bytes::Buf;
+    /// let frame = (&b"hel"[..]).chain(&b"lo"[..]);
+    /// let boxed = ThinBox::from_buf("frame", frame);
+    /// assert_eq!(boxed.slice, *b"hello");
+    /// ```
+    pub fn from_buf<B: Buf>(head: Head, mut buf: B) -> Self {
+        let mut this = InProgress::new(buf.remaining());
+        while this.written_len < this.len {
+            let chunk = buf.chunk();
+            assert!(
+                !chunk.is_empty(),
+                "Buf ran out of chunks before `remaining` bytes"
+            );
+            let n = chunk.len().min(this.len - this.written_len);
+            this.push_slice(&chunk[..n]);
+            buf.advance(n);
+        }
+        unsafe { this.finish(head) }
+    }
+}
@@ -0,0 +1,116 @@
+use {
+    crate::{InProgress, ThinBox},
+    core::{
+        fmt::{self, Debug},
+        mem::MaybeUninit,
+    },
+};
+
+/// A `ThinBox` under construction, whose slice is initialized gradually.
+///
+/// This works like the spare capacity of a `Vec`: items are written through
+/// [`spare_capacity_mut`](Self::spare_capacity_mut), then marked initialized
+/// with [`set_len`](Self::set_len). The builder tracks the initialized length,
+/// so [`assume_init_prefix`](Self::assume_init_prefix) can safely turn it into
+/// a `ThinBox` of just the initialized items. If the builder is dropped
+/// instead, the initialized items and the head are dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let mut builder = ThinBox::with_capacity("squares", 8);
+/// for (i, slot) in builder.spare_capacity_mut()[..5].iter_mut().enumerate() {
+///     slot.write(i * i);
+/// }
+/// unsafe { builder.set_len(5) };
+/// let boxed = builder.assume_init_prefix();
+/// assert_eq!(boxed.slice, [0, 1, 4, 9, 16]);
+/// ```
+pub struct ThinBoxBuilder<Head, SliceItem> {
+    raw: InProgress<Head, SliceItem>,
+}
+
+impl<Head, SliceItem> ThinBox<Head, SliceItem> {
+    /// Start building a `ThinBox` with room for `capacity` slice items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation would exceed `isize::MAX` bytes.
+    pub fn with_capacity(head: Head, capacity: usize) -> ThinBoxBuilder<Head, SliceItem> {
+        let mut raw = InProgress::new(capacity);
+        raw.write_head(head);
+        ThinBoxBuilder { raw }
+    }
+}
+
+impl<Head, SliceItem> ThinBoxBuilder<Head, SliceItem> {
+    /// The number of initialized slice items.
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Whether no slice items are initialized.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of slice items there is room for.
+    pub fn capacity(&self) -> usize {
+        self.raw.len
+    }
+
+    /// The initialized slice items.
+    pub fn as_slice(&self) -> &[SliceItem] {
+        self.raw.written()
+    }
+
+    /// The slots after the initialized slice items.
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<SliceItem>] {
+        let raw = &mut self.raw;
+        unsafe {
+            let spare = raw
+                .raw
+                .as_ptr()
+                .add(raw.slice_offset)
+                .cast::<MaybeUninit<SliceItem>>()
+                .add(raw.written_len);
+            &mut *crate::make_slice_mut(spare, raw.len - raw.written_len)
+        }
+    }
+
+    /// Set the number of initialized slice items.
+    ///
+    /// Like [`Vec::set_len`], this doesn't drop or initialize anything:
+    /// shrinking the length leaks the items past it.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be at most [`capacity`](Self::capacity).
+    /// - The items at `old_len..new_len` must be initialized.
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
+        self.raw.written_len = new_len;
+    }
+
+    /// Finish building, keeping only the initialized slice items.
+    ///
+    /// The allocation is shrunk to fit if any capacity is left unused.
+    pub fn assume_init_prefix(self) -> ThinBox<Head, SliceItem> {
+        let mut raw = self.raw;
+        raw.shrink_to_written();
+        unsafe { raw.into_box() }
+    }
+}
+
+impl<Head, SliceItem> Debug for ThinBoxBuilder<Head, SliceItem>
+where
+    SliceItem: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinBoxBuilder")
+            .field("initialized", &self.as_slice())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
@@ -0,0 +1,139 @@
+use {
+    crate::ThinData,
+    core::{
+        fmt::{self, Debug},
+        hash::{Hash, Hasher},
+        mem,
+        ops::Deref,
+        ptr, slice,
+    },
+};
+
+/// Types whose equality is equality of their bytes.
+///
+/// # Safety
+///
+/// The type must have no padding or other uninitialized bytes,
+/// and two values must be equal by `PartialEq` exactly when their bytes are equal.
+/// Floats don't qualify, as `0.0 == -0.0` and `NaN != NaN`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// #[derive(Copy, Clone, PartialEq, Eq)]
+/// #[repr(C)]
+/// struct Rgba([u8; 4]);
+///
+/// // SAFETY: `Rgba` is four bytes with no padding, compared field by field.
+/// unsafe impl BytewiseEq for Rgba {}
+/// ```
+pub unsafe trait BytewiseEq: Copy + Eq {}
+
+macro_rules! bytewise_eq {
+    ($($ty:ty),* $(,)?) => {$(
+        unsafe impl BytewiseEq for $ty {}
+    )*};
+}
+
+bytewise_eq!(
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    bool,
+    char,
+    ()
+);
+
+unsafe impl<T: BytewiseEq, const N: usize> BytewiseEq for [T; N] {}
+
+impl<Head, SliceItem: BytewiseEq> ThinData<Head, SliceItem> {
+    /// View the slice as its raw bytes.
+    pub fn slice_bytes(&self) -> &[u8] {
+        let len = mem::size_of_val(&self.slice);
+        unsafe { slice::from_raw_parts(self.slice.as_ptr().cast(), len) }
+    }
+}
+
+/// A thin pointer compared and hashed by the bytes of its slice.
+///
+/// Comparing or hashing a `ThinData` goes through the slice items one at a time.
+/// The standard library already compares and hashes slices of primitive integers
+/// as bytes, but can't do the same for user types, such as a `#[derive]`d
+/// newtype around an integer. For slice items that are [`BytewiseEq`],
+/// `Bytewise` compares the slices with one `memcmp` and feeds them to the
+/// hasher in a single write, which makes large numeric nodes much cheaper to
+/// use as map keys. The head is still compared and hashed as usual.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// # use std::collections::HashSet;
+/// #[derive(Copy, Clone, PartialEq, Eq, Hash)]
+/// struct Id(u32);
+/// unsafe impl BytewiseEq for Id {}
+///
+/// let mut interned = HashSet::new();
+/// interned.insert(Bytewise(ThinArc::new("ids", (0..1000).map(Id))));
+/// assert!(interned.contains(&Bytewise(ThinArc::new("ids", (0..1000).map(Id)))));
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct Bytewise<P>(pub P);
+
+impl<P> Deref for Bytewise<P> {
+    type Target = P;
+    fn deref(&self) -> &P {
+        &self.0
+    }
+}
+
+impl<P, Head, SliceItem> PartialEq for Bytewise<P>
+where
+    P: Deref<Target = ThinData<Head, SliceItem>>,
+    Head: PartialEq,
+    SliceItem: BytewiseEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        let (this, other) = (&*self.0, &*other.0);
+        ptr::eq(this, other)
+            || (this.head == other.head && this.slice_bytes() == other.slice_bytes())
+    }
+}
+
+impl<P, Head, SliceItem> Eq for Bytewise<P>
+where
+    P: Deref<Target = ThinData<Head, SliceItem>>,
+    Head: Eq,
+    SliceItem: BytewiseEq,
+{
+}
+
+impl<P, Head, SliceItem> Hash for Bytewise<P>
+where
+    P: Deref<Target = ThinData<Head, SliceItem>>,
+    Head: Hash,
+    SliceItem: BytewiseEq,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.head.hash(state);
+        // Like `[u8]`, prefix the length so that adjacent fields can't collide.
+        state.write_usize(self.0.slice.len());
+        state.write(self.0.slice_bytes());
+    }
+}
+
+impl<P: Debug> Debug for Bytewise<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Bytewise").field(&self.0).finish()
+    }
+}
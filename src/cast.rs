@@ -0,0 +1,114 @@
+use {
+    crate::{ErasedPtr, Len, ThinBox, ThinData},
+    core::{mem, ptr, slice},
+};
+
+/// Plain old data: types which can be reinterpreted from and as any bytes.
+///
+/// # Safety
+///
+/// The type must have no padding or other uninitialized bytes,
+/// and every bit pattern must be a valid value of it.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! pod {
+    ($($ty:ty),* $(,)?) => {$(
+        unsafe impl Pod for $ty {}
+    )*};
+}
+
+pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// The length of a slice of `len` items of `T` viewed as items of `U`,
+/// if it is exactly as many bytes.
+fn cast_len<T, U>(len: usize) -> Option<usize> {
+    let size = mem::size_of::<U>();
+    if size == 0 {
+        return None;
+    }
+    let bytes = len * mem::size_of::<T>();
+    let cast_len = bytes / size;
+    if cast_len * size == bytes {
+        Some(cast_len)
+    } else {
+        None
+    }
+}
+
+impl<Head, SliceItem: Pod> ThinData<Head, SliceItem> {
+    /// View the slice as a slice of another plain old data type.
+    ///
+    /// Returns `None` if the slice isn't aligned for `U`,
+    /// or isn't a whole number of `U`s long.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let words = ThinBox::new((), vec![0x0102_0304_u32; 2]);
+    /// assert_eq!(words.cast_slice::<u8>().unwrap().len(), 8);
+    /// assert_eq!(words.cast_slice::<[u32; 2]>(), Some(&[[0x0102_0304; 2]][..]));
+    /// assert_eq!(words.cast_slice::<[u8; 3]>(), None);
+    /// ```
+    pub fn cast_slice<U: Pod>(&self) -> Option<&[U]> {
+        let len = cast_len::<SliceItem, U>(self.slice.len())?;
+        let ptr = self.slice.as_ptr().cast::<U>();
+        if ptr.align_offset(mem::align_of::<U>()) != 0 {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+
+    /// View the slice as a mutable slice of another plain old data type.
+    ///
+    /// See [`ThinData::cast_slice`].
+    pub fn cast_slice_mut<U: Pod>(&mut self) -> Option<&mut [U]> {
+        let len = cast_len::<SliceItem, U>(self.slice.len())?;
+        let ptr = self.slice.as_mut_ptr().cast::<U>();
+        if ptr.align_offset(mem::align_of::<U>()) != 0 {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts_mut(ptr, len) })
+    }
+}
+
+impl<Head, SliceItem: Pod> ThinBox<Head, SliceItem> {
+    /// Reinterpret the slice as a slice of another plain old data type, in place.
+    ///
+    /// The length is updated to the number of `U`s in the slice.
+    /// The box is returned unchanged if the slice isn't a whole number of `U`s long,
+    /// or if the allocation doesn't have the layout of a `ThinBox<Head, U>`
+    /// (e.g. as `U` needs more alignment than `SliceItem`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let words = ThinBox::new("frame", vec![1u32, 2]);
+    /// let bytes = ThinBox::cast_item_slice::<u8>(words).unwrap();
+    /// assert_eq!(bytes.slice.len(), 8);
+    /// let words = ThinBox::cast_item_slice::<u32>(bytes).unwrap();
+    /// assert_eq!(words.slice, [1, 2]);
+    /// ```
+    pub fn cast_item_slice<U: Pod>(this: Self) -> Result<ThinBox<Head, U>, Self> {
+        let len = this.slice.len();
+        let cast_len = match cast_len::<SliceItem, U>(len) {
+            Some(cast_len) => cast_len,
+            None => return Err(this),
+        };
+        match (
+            ThinBox::<Head, SliceItem>::layout(len),
+            ThinBox::<Head, U>::layout(cast_len),
+        ) {
+            (Ok(layout), Ok(cast_layout)) if layout == cast_layout => {}
+            _ => return Err(this),
+        }
+        let raw: ErasedPtr = ThinBox::erase(this);
+        unsafe {
+            ptr::write(ThinData::<Head, U>::len(raw).as_ptr(), Len::new(cast_len));
+            Ok(ThinBox::from_erased(raw))
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use {
+    crate::{priv_in_pub::Erased, ErasedPtr, ThinArc},
+    core::{
+        fmt::{self, Debug},
+        marker::PhantomData,
+        mem::ManuallyDrop,
+        ptr::{self, NonNull},
+        sync::atomic::{AtomicPtr, Ordering},
+    },
+};
+
+fn encode<Head, SliceItem>(value: Option<ThinArc<Head, SliceItem>>) -> *mut Erased {
+    value.map_or(ptr::null_mut(), |arc| ThinArc::erase(arc).as_ptr())
+}
+
+fn encode_erased(ptr: Option<ErasedPtr>) -> *mut Erased {
+    ptr.map_or(ptr::null_mut(), NonNull::as_ptr)
+}
+
+/// An atomic `Option<ThinArc>`, for read-mostly values that are replaced
+/// as a whole, such as configuration.
+///
+/// Every operation takes the memory ordering to use, so readers can pick the
+/// cheapest ordering that is correct for them. This matters on weak-memory
+/// targets (e.g. ARM or POWER), where an `Acquire` load needs a barrier
+/// that a `Relaxed` one doesn't; on `x86_64`, both compile to the same load.
+/// Unlike `VersionedThinArcCell`,
+/// this is a single `AtomicPtr` and is available on every target.
+///
+/// The cell owns the `ThinArc` stored in it. Loads only return the erased
+/// pointer; reading through it is only sound while something else keeps it
+/// alive, such as the reclaimer behind [`ThinArc::retire`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use {std::sync::atomic::Ordering, thin_dst::*};
+/// let cell = ThinArcCell::new(Some(ThinArc::new("v1", vec![1])));
+/// let seen = cell.load(Ordering::Relaxed);
+///
+/// let old = cell.swap(Some(ThinArc::new("v2", vec![2])), Ordering::AcqRel);
+/// assert_eq!(old.map(|arc| arc.head), Some("v1"));
+///
+/// // A relaxed load is enough to notice that the value changed...
+/// assert_ne!(cell.load(Ordering::Relaxed), seen);
+/// // ...but reading the new value needs an acquire load.
+/// let now = cell.load(Ordering::Acquire).unwrap();
+/// assert_eq!(unsafe { ThinRef::<&str, i32>::from_erased(now) }.slice, [2]);
+/// ```
+pub struct ThinArcCell<Head, SliceItem> {
+    ptr: AtomicPtr<Erased>,
+    marker: PhantomData<ThinArc<Head, SliceItem>>,
+}
+
+unsafe impl<Head, SliceItem> Send for ThinArcCell<Head, SliceItem> where
+    ThinArc<Head, SliceItem>: Send
+{
+}
+unsafe impl<Head, SliceItem> Sync for ThinArcCell<Head, SliceItem> where
+    ThinArc<Head, SliceItem>: Send + Sync
+{
+}
+
+impl<Head, SliceItem> ThinArcCell<Head, SliceItem> {
+    /// Create a new cell holding `value`.
+    pub fn new(value: Option<ThinArc<Head, SliceItem>>) -> Self {
+        ThinArcCell {
+            ptr: AtomicPtr::new(encode(value)),
+            marker: PhantomData,
+        }
+    }
+
+    /// Load the current pointer, with the given memory ordering.
+    ///
+    /// - `Acquire` (or `SeqCst`) makes the pointee's contents visible, as they
+    ///   were when the pointer was stored, so the snapshot can be dereferenced
+    ///   (while kept alive elsewhere).
+    /// - `Relaxed` is enough to compare the pointer's identity, e.g. to see if
+    ///   a configuration changed, but the pointee must not be read through it.
+    ///   Rust has no `Consume` ordering, so this is the cheapest snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is `Release` or `AcqRel`.
+    pub fn load(&self, order: Ordering) -> Option<ErasedPtr> {
+        NonNull::new(self.ptr.load(order))
+    }
+
+    /// Store `value`, returning the `ThinArc` that was stored before.
+    ///
+    /// Use `Release` (or stronger) so that `Acquire` loads see the new value's contents.
+    pub fn swap(
+        &self,
+        value: Option<ThinArc<Head, SliceItem>>,
+        order: Ordering,
+    ) -> Option<ThinArc<Head, SliceItem>> {
+        let old = self.ptr.swap(encode(value), order);
+        NonNull::new(old).map(|ptr| unsafe { ThinArc::from_erased(ptr) })
+    }
+
+    /// Store `new` if the cell still holds `current`.
+    ///
+    /// On success, returns the `ThinArc` that was stored. On failure, returns
+    /// `new` back along with the pointer the cell holds instead.
+    /// The orderings are as for [`AtomicPtr::compare_exchange`].
+    #[allow(clippy::type_complexity)]
+    pub fn compare_exchange(
+        &self,
+        current: Option<ErasedPtr>,
+        new: Option<ThinArc<Head, SliceItem>>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<
+        Option<ThinArc<Head, SliceItem>>,
+        (Option<ThinArc<Head, SliceItem>>, Option<ErasedPtr>),
+    > {
+        let new = ManuallyDrop::new(new);
+        let new_ptr = encode_erased(new.as_ref().map(|arc| arc.raw));
+        match self
+            .ptr
+            .compare_exchange(encode_erased(current), new_ptr, success, failure)
+        {
+            // The cell now owns `new`, and handed over ownership of the old pointer.
+            Ok(old) => Ok(NonNull::new(old).map(|ptr| unsafe { ThinArc::from_erased(ptr) })),
+            Err(now) => Err((ManuallyDrop::into_inner(new), NonNull::new(now))),
+        }
+    }
+
+    /// Take the stored `ThinArc` out of the cell.
+    pub fn into_inner(self) -> Option<ThinArc<Head, SliceItem>> {
+        let mut this = ManuallyDrop::new(self);
+        NonNull::new(*this.ptr.get_mut()).map(|ptr| unsafe { ThinArc::from_erased(ptr) })
+    }
+}
+
+impl<Head, SliceItem> Drop for ThinArcCell<Head, SliceItem> {
+    fn drop(&mut self) {
+        if let Some(ptr) = NonNull::new(*self.ptr.get_mut()) {
+            drop(unsafe { ThinArc::<Head, SliceItem>::from_erased(ptr) });
+        }
+    }
+}
+
+impl<Head, SliceItem> Debug for ThinArcCell<Head, SliceItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinArcCell")
+            .field("current", &self.load(Ordering::SeqCst))
+            .finish()
+    }
+}
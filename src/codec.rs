@@ -0,0 +1,344 @@
+//! A minimal binary encoding for `ThinData`, usable without `std::io`.
+//!
+//! The encoding of a `ThinData` is its slice length as a little-endian `u64`,
+//! followed by the encoded head, followed by each encoded slice item.
+//! Primitive integers are encoded as fixed-width little-endian,
+//! with `usize` and `isize` widened to 64 bits so that all platforms agree.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use thin_dst::{*, codec::*};
+//! let node = ThinBox::new(7u16, vec![1u8, 2, 3]);
+//! let mut buf = Vec::new();
+//! node.encode(&mut buf).unwrap();
+//! assert_eq!(buf, [3, 0, 0, 0, 0, 0, 0, 0, 7, 0, 1, 2, 3]);
+//!
+//! let decoded = ThinBox::<u16, u8>::decode(&mut &buf[..]).unwrap();
+//! assert_eq!(decoded, node);
+//! ```
+
+use {
+    crate::{ThinArc, ThinBox, ThinData, ThinRc},
+    alloc::vec::Vec,
+    core::{
+        convert::TryFrom,
+        fmt::{self, Display},
+        hash::Hasher,
+        mem,
+    },
+};
+
+/// A destination for encoded bytes.
+pub trait Sink {
+    /// Write all of `bytes` to the sink.
+    fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError>;
+}
+
+impl Sink for Vec<u8> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+/// Writing to a byte slice advances the slice past the written bytes.
+impl Sink for &'_ mut [u8] {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+        if bytes.len() > self.len() {
+            return Err(EncodeError::BufferFull);
+        }
+        let (head, tail) = mem::take(self).split_at_mut(bytes.len());
+        head.copy_from_slice(bytes);
+        *self = tail;
+        Ok(())
+    }
+}
+
+/// An error encoding a value.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// The output buffer is too small.
+    BufferFull,
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::BufferFull => f.write_str("output buffer is full"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {}
+
+/// An error decoding a value.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The input ended before the value did.
+    UnexpectedEnd,
+    /// The input does not encode a valid value.
+    Invalid,
+    /// The input claims more slice items than the decoding limit allows.
+    TooLong,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => f.write_str("unexpected end of input"),
+            DecodeError::Invalid => f.write_str("invalid encoding"),
+            DecodeError::TooLong => f.write_str("slice length exceeds the decoding limit"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// A type which can be encoded into bytes.
+pub trait Encode {
+    /// Encode `self` into the sink.
+    fn encode<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<(), EncodeError>;
+}
+
+/// A type which can be decoded from bytes.
+pub trait Decode: Sized {
+    /// Decode a value from the front of `input`, advancing it past the decoded bytes.
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError>;
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if input.len() < len {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    let (head, tail) = input.split_at(len);
+    *input = tail;
+    Ok(head)
+}
+
+macro_rules! codec_int {
+    ($($ty:ty),* $(,)?) => {$(
+        impl Encode for $ty {
+            fn encode<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<(), EncodeError> {
+                sink.write(&self.to_le_bytes())
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                let mut bytes = [0; mem::size_of::<$ty>()];
+                bytes.copy_from_slice(take(input, mem::size_of::<$ty>())?);
+                Ok(<$ty>::from_le_bytes(bytes))
+            }
+        }
+    )*};
+}
+
+codec_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl Encode for usize {
+    fn encode<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<(), EncodeError> {
+        (*self as u64).encode(sink)
+    }
+}
+
+impl Decode for usize {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        usize::try_from(u64::decode(input)?).map_err(|_| DecodeError::Invalid)
+    }
+}
+
+impl Encode for isize {
+    fn encode<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<(), EncodeError> {
+        (*self as i64).encode(sink)
+    }
+}
+
+impl Decode for isize {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        isize::try_from(i64::decode(input)?).map_err(|_| DecodeError::Invalid)
+    }
+}
+
+impl Encode for bool {
+    fn encode<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<(), EncodeError> {
+        (*self as u8).encode(sink)
+    }
+}
+
+impl Decode for bool {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::Invalid),
+        }
+    }
+}
+
+impl Encode for () {
+    fn encode<S: Sink + ?Sized>(&self, _: &mut S) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl Decode for () {
+    fn decode(_: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(())
+    }
+}
+
+impl<Head, SliceItem> Encode for ThinData<Head, SliceItem>
+where
+    Head: Encode,
+    SliceItem: Encode,
+{
+    fn encode<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<(), EncodeError> {
+        self.slice.len().encode(sink)?;
+        self.head.encode(sink)?;
+        self.slice.iter().try_for_each(|item| item.encode(sink))
+    }
+}
+
+/// Feeds encoded bytes to a hasher.
+struct HashSink<'a, H: ?Sized>(&'a mut H);
+
+impl<H: Hasher + ?Sized> Sink for HashSink<'_, H> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+        self.0.write(bytes);
+        Ok(())
+    }
+}
+
+impl<Head, SliceItem> ThinData<Head, SliceItem>
+where
+    Head: Encode,
+    SliceItem: Encode,
+{
+    /// Feed the encoding of this into `hasher`.
+    ///
+    /// Unlike the `Hash` impl, which hashes native-endian, platform-sized
+    /// integers, this hashes the same bytes on every platform. Paired with a
+    /// hasher that is itself portable (e.g. one with fixed keys), this gives
+    /// hashes suitable for caches shared between machines.
+    ///
+    /// ```rust
+    /// # use thin_dst::{*, codec::*};
+    /// # use std::hash::Hasher;
+    /// #[derive(Default)]
+    /// struct Bytes(Vec<u8>);
+    /// impl Hasher for Bytes {
+    ///     fn write(&mut self, bytes: &[u8]) { self.0.extend_from_slice(bytes) }
+    ///     fn finish(&self) -> u64 { 0 }
+    /// }
+    ///
+    /// let node = ThinBox::new(7u16, vec![1usize, 2]);
+    /// let mut hasher = Bytes::default();
+    /// node.portable_hash(&mut hasher);
+    /// let mut encoded = Vec::new();
+    /// node.encode(&mut encoded).unwrap();
+    /// assert_eq!(hasher.0, encoded);
+    /// ```
+    pub fn portable_hash<H: Hasher + ?Sized>(&self, hasher: &mut H) {
+        match self.encode(&mut HashSink(hasher)) {
+            Ok(()) => {}
+            Err(_) => unreachable!("hashing is infallible"),
+        }
+    }
+}
+
+/// The most zero-sized slice items [`Decode`] accepts for one `ThinData`.
+///
+/// Zero-sized items usually decode from no input at all, so unlike other items,
+/// the length of the input doesn't bound how many of them a corrupt length
+/// can ask for. Use `decode_with_limit` on the thin pointers to pick another limit.
+pub const ZST_LEN_LIMIT: usize = 1 << 16;
+
+/// The default limit on the number of slice items, as used by [`Decode`].
+pub(crate) fn default_len_limit<SliceItem>() -> usize {
+    if mem::size_of::<SliceItem>() == 0 {
+        ZST_LEN_LIMIT
+    } else {
+        usize::MAX
+    }
+}
+
+/// Check a decoded slice length before decoding any of its items.
+///
+/// Items that aren't zero-sized are assumed to each take at least one byte,
+/// so a length longer than the remaining input is rejected up front.
+pub(crate) fn check_len<SliceItem>(
+    len: usize,
+    input: &[u8],
+    limit: usize,
+) -> Result<(), DecodeError> {
+    if len > limit {
+        Err(DecodeError::TooLong)
+    } else if mem::size_of::<SliceItem>() != 0 && len > input.len() {
+        Err(DecodeError::UnexpectedEnd)
+    } else {
+        Ok(())
+    }
+}
+
+/// Decode the parts of a `ThinData` without trusting the stored length for preallocation.
+fn decode_parts<Head, SliceItem>(
+    input: &mut &[u8],
+    limit: usize,
+) -> Result<(Head, Vec<SliceItem>), DecodeError>
+where
+    Head: Decode,
+    SliceItem: Decode,
+{
+    let len = usize::decode(input)?;
+    check_len::<SliceItem>(len, input, limit)?;
+    let head = Head::decode(input)?;
+    let mut slice = Vec::new();
+    for _ in 0..len {
+        slice.push(SliceItem::decode(input)?);
+    }
+    Ok((head, slice))
+}
+
+macro_rules! codec_thin {
+    ($($thin:ident),* $(,)?) => {$(
+        impl<Head, SliceItem> Encode for $thin<Head, SliceItem>
+        where
+            Head: Encode,
+            SliceItem: Encode,
+        {
+            fn encode<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<(), EncodeError> {
+                (**self).encode(sink)
+            }
+        }
+
+        impl<Head, SliceItem> Decode for $thin<Head, SliceItem>
+        where
+            Head: Decode,
+            SliceItem: Decode,
+        {
+            /// Zero-sized slice items are limited to [`ZST_LEN_LIMIT`].
+            fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                Self::decode_with_limit(input, default_len_limit::<SliceItem>())
+            }
+        }
+
+        impl<Head, SliceItem> $thin<Head, SliceItem>
+        where
+            Head: Decode,
+            SliceItem: Decode,
+        {
+            /// Decode a value, rejecting one with more than `limit` slice items.
+            pub fn decode_with_limit(input: &mut &[u8], limit: usize) -> Result<Self, DecodeError> {
+                let (head, slice) = decode_parts(input, limit)?;
+                Ok($thin::new(head, slice))
+            }
+        }
+    )*};
+}
+
+codec_thin!(ThinBox, ThinArc, ThinRc);
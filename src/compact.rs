@@ -0,0 +1,105 @@
+use {
+    crate::ThinData,
+    core::fmt::{self, Debug, Display},
+};
+
+/// A one-line formatter for a [`ThinData`] that elides most of the slice.
+///
+/// Created by [`ThinData::display_compact`]. Prints the head, the slice length,
+/// and the first few slice items followed by an ellipsis if any were left out.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let boxed = ThinBox::new("head", 0..1_000_000);
+/// assert_eq!(
+///     boxed.display_compact().to_string(),
+///     r#"ThinData { head: "head", len: 1000000, slice: [0, 1, 2, 3, 4, 5, 6, 7, ..] }"#,
+/// );
+/// assert_eq!(
+///     boxed.display_compact().items(2).to_string(),
+///     r#"ThinData { head: "head", len: 1000000, slice: [0, 1, ..] }"#,
+/// );
+/// ```
+pub struct CompactDisplay<'a, Head, SliceItem> {
+    data: &'a ThinData<Head, SliceItem>,
+    items: usize,
+}
+
+impl<'a, Head, SliceItem> CompactDisplay<'a, Head, SliceItem> {
+    /// The number of slice items shown by default.
+    pub const DEFAULT_ITEMS: usize = 8;
+
+    /// Set the number of leading slice items to show.
+    pub fn items(self, items: usize) -> Self {
+        CompactDisplay { items, ..self }
+    }
+}
+
+impl<Head, SliceItem> ThinData<Head, SliceItem> {
+    /// Format this on one line, showing only the first few slice items.
+    ///
+    /// The derived `Debug` prints the entire slice, which is unwieldy for logs.
+    /// See [`CompactDisplay`].
+    pub fn display_compact(&self) -> CompactDisplay<'_, Head, SliceItem> {
+        CompactDisplay {
+            data: self,
+            items: CompactDisplay::<Head, SliceItem>::DEFAULT_ITEMS,
+        }
+    }
+}
+
+struct Elided;
+
+impl Debug for Elided {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("..")
+    }
+}
+
+struct Prefix<'a, SliceItem> {
+    slice: &'a [SliceItem],
+    items: usize,
+}
+
+impl<SliceItem: Debug> Debug for Prefix<'_, SliceItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        list.entries(self.slice.iter().take(self.items));
+        if self.slice.len() > self.items {
+            list.entry(&Elided);
+        }
+        list.finish()
+    }
+}
+
+impl<Head: Debug, SliceItem: Debug> Display for CompactDisplay<'_, Head, SliceItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Deliberately not forwarding `{:#}`; this is meant to fit on one line.
+        write!(
+            f,
+            "ThinData {{ head: {:?}, len: {}, slice: {:?} }}",
+            self.data.head,
+            self.data.slice.len(),
+            Prefix {
+                slice: &self.data.slice,
+                items: self.items,
+            },
+        )
+    }
+}
+
+impl<Head: Debug, SliceItem: Debug> Debug for CompactDisplay<'_, Head, SliceItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl<Head, SliceItem> Clone for CompactDisplay<'_, Head, SliceItem> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Head, SliceItem> Copy for CompactDisplay<'_, Head, SliceItem> {}
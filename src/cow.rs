@@ -0,0 +1,126 @@
+use {
+    crate::{ThinArc, ThinData, ThinRef},
+    core::{
+        fmt::{self, Debug},
+        ops::Deref,
+    },
+};
+
+/// A `ThinData` which is either borrowed from a memory map, or owned.
+///
+/// Read-mostly structures can point straight into a mapped file with
+/// [`ThinRef`]s, and only pay for a copy when something needs to change:
+/// [`to_mut`](Self::to_mut) copies the mapped data into a new [`ThinArc`]
+/// the first time it is called, and mutates in place from then on.
+///
+/// Cloning is cheap in both cases. If an owned `ThinArc` is shared
+/// between clones when it is mutated, it is copied again, like `Arc::make_mut`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// // Stand-in for data living in a memory map.
+/// let mapped = ThinBox::new(1u32, vec![10u64, 20]);
+///
+/// let mut entry = ThinMapCow::from(ThinRef::from(&*mapped));
+/// assert!(entry.is_mapped());
+/// entry.to_mut().slice[0] = 11;
+/// assert!(!entry.is_mapped());
+/// assert_eq!(entry.slice, [11, 20]);
+/// assert_eq!(mapped.slice, [10, 20]);
+/// ```
+pub enum ThinMapCow<'a, Head, SliceItem> {
+    /// Borrowed data, such as a view into a memory map.
+    Mapped(ThinRef<'a, Head, SliceItem>),
+    /// Owned data, copied out of the map when it was first mutated.
+    Owned(ThinArc<Head, SliceItem>),
+}
+
+impl<'a, Head, SliceItem> ThinMapCow<'a, Head, SliceItem> {
+    /// Whether the data is still borrowed.
+    pub fn is_mapped(&self) -> bool {
+        match self {
+            ThinMapCow::Mapped(_) => true,
+            ThinMapCow::Owned(_) => false,
+        }
+    }
+
+    /// Mutably borrow the data, copying it first if it is borrowed
+    /// or shared with a clone.
+    pub fn to_mut(&mut self) -> &mut ThinData<Head, SliceItem>
+    where
+        Head: Clone,
+        SliceItem: Clone,
+    {
+        let unique = match self {
+            ThinMapCow::Mapped(_) => false,
+            ThinMapCow::Owned(arc) => ThinArc::get_mut(arc).is_some(),
+        };
+        if !unique {
+            let data: &ThinData<Head, SliceItem> = self;
+            let copy = ThinArc::new(data.head.clone(), data.slice.iter().cloned());
+            *self = ThinMapCow::Owned(copy);
+        }
+        match self {
+            ThinMapCow::Owned(arc) => match ThinArc::get_mut(arc) {
+                Some(data) => data,
+                None => unreachable!(),
+            },
+            ThinMapCow::Mapped(_) => unreachable!(),
+        }
+    }
+
+    /// Take ownership of the data, copying it if it is borrowed.
+    pub fn into_owned(self) -> ThinArc<Head, SliceItem>
+    where
+        Head: Clone,
+        SliceItem: Clone,
+    {
+        match self {
+            ThinMapCow::Mapped(data) => ThinArc::new(data.head.clone(), data.slice.iter().cloned()),
+            ThinMapCow::Owned(arc) => arc,
+        }
+    }
+}
+
+impl<'a, Head, SliceItem> Deref for ThinMapCow<'a, Head, SliceItem> {
+    type Target = ThinData<Head, SliceItem>;
+    fn deref(&self) -> &Self::Target {
+        match self {
+            ThinMapCow::Mapped(data) => data,
+            ThinMapCow::Owned(arc) => arc,
+        }
+    }
+}
+
+impl<'a, Head, SliceItem> Clone for ThinMapCow<'a, Head, SliceItem> {
+    fn clone(&self) -> Self {
+        match self {
+            ThinMapCow::Mapped(data) => ThinMapCow::Mapped(*data),
+            ThinMapCow::Owned(arc) => ThinMapCow::Owned(arc.clone()),
+        }
+    }
+}
+
+impl<'a, Head, SliceItem> From<ThinRef<'a, Head, SliceItem>> for ThinMapCow<'a, Head, SliceItem> {
+    fn from(data: ThinRef<'a, Head, SliceItem>) -> Self {
+        ThinMapCow::Mapped(data)
+    }
+}
+
+impl<'a, Head, SliceItem> From<ThinArc<Head, SliceItem>> for ThinMapCow<'a, Head, SliceItem> {
+    fn from(arc: ThinArc<Head, SliceItem>) -> Self {
+        ThinMapCow::Owned(arc)
+    }
+}
+
+impl<'a, Head, SliceItem> Debug for ThinMapCow<'a, Head, SliceItem>
+where
+    Head: Debug,
+    SliceItem: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        ThinData::fmt(self, f)
+    }
+}
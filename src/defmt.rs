@@ -0,0 +1,35 @@
+use {
+    crate::{ThinArc, ThinBox, ThinData, ThinRc, ThinRef, ThinRefMut},
+    defmt::{Format, Formatter},
+};
+
+impl<Head, SliceItem> Format for ThinData<Head, SliceItem>
+where
+    Head: Format,
+    SliceItem: Format,
+{
+    fn format(&self, f: Formatter<'_>) {
+        defmt::write!(
+            f,
+            "ThinData {{ head: {}, slice: {} }}",
+            self.head,
+            &self.slice
+        )
+    }
+}
+
+macro_rules! defmt_holder {
+    ($($thin:ident$(<$a:lifetime>)?),* $(,)?) => {$(
+        impl<$($a,)? Head, SliceItem> Format for $thin<$($a,)? Head, SliceItem>
+        where
+            Head: Format,
+            SliceItem: Format,
+        {
+            fn format(&self, f: Formatter<'_>) {
+                ThinData::format(self, f)
+            }
+        }
+    )*};
+}
+
+defmt_holder!(ThinBox, ThinArc, ThinRc, ThinRef<'a>, ThinRefMut<'a>);
@@ -0,0 +1,104 @@
+//! Helpers for comparing trees of shared thin nodes.
+//!
+//! See also [`ThinArc::shallow_eq`](crate::ThinArc::shallow_eq).
+
+use core::{
+    hash::{Hash, Hasher},
+    ops::{Deref, DerefMut},
+};
+
+/// A node head paired with a hash of the entire subtree, computed once at construction.
+///
+/// Comparing two `SubtreeHash`es compares the cached hashes before the heads,
+/// so unequal subtrees are usually told apart without visiting their children,
+/// and hashing one only writes the cached hash.
+///
+/// The head must not be mutated in a way that changes its hash,
+/// or the cached hash will no longer describe it.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::{*, diff::*};
+/// # use std::collections::hash_map::DefaultHasher;
+/// #[derive(Clone, PartialEq)]
+/// struct Node(ThinArc<SubtreeHash<u32>, Node>);
+///
+/// fn node(value: u32, children: Vec<Node>) -> Node {
+///     let head = SubtreeHash::new::<DefaultHasher, _, _>(value, &children, |child| {
+///         child.0.head.subtree_hash()
+///     });
+///     Node(ThinArc::new(head, children))
+/// }
+///
+/// let a = node(0, vec![node(1, vec![]), node(2, vec![])]);
+/// let b = node(0, vec![node(1, vec![]), node(3, vec![])]);
+/// assert_ne!(a.0.head.subtree_hash(), b.0.head.subtree_hash());
+/// assert!(a != b);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SubtreeHash<Head> {
+    hash: u64,
+    head: Head,
+}
+
+impl<Head: Hash> SubtreeHash<Head> {
+    /// Hash the head together with the subtree hashes of its children.
+    ///
+    /// `child_hash` should return the cached subtree hash of each child.
+    pub fn new<H, SliceItem, F>(head: Head, children: &[SliceItem], mut child_hash: F) -> Self
+    where
+        H: Hasher + Default,
+        F: FnMut(&SliceItem) -> u64,
+    {
+        let mut hasher = H::default();
+        head.hash(&mut hasher);
+        hasher.write_usize(children.len());
+        for child in children {
+            hasher.write_u64(child_hash(child));
+        }
+        SubtreeHash {
+            hash: hasher.finish(),
+            head,
+        }
+    }
+}
+
+impl<Head> SubtreeHash<Head> {
+    /// The cached hash of the subtree.
+    pub fn subtree_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Discard the cached hash.
+    pub fn into_inner(this: Self) -> Head {
+        this.head
+    }
+}
+
+impl<Head> Deref for SubtreeHash<Head> {
+    type Target = Head;
+    fn deref(&self) -> &Head {
+        &self.head
+    }
+}
+
+impl<Head> DerefMut for SubtreeHash<Head> {
+    fn deref_mut(&mut self) -> &mut Head {
+        &mut self.head
+    }
+}
+
+impl<Head: PartialEq> PartialEq for SubtreeHash<Head> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.head == other.head
+    }
+}
+
+impl<Head: Eq> Eq for SubtreeHash<Head> {}
+
+impl<Head> Hash for SubtreeHash<Head> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash)
+    }
+}
@@ -0,0 +1,237 @@
+use {
+    crate::{ErasedPtr, ThinPointer, ThinRef},
+    alloc::vec::{self, Vec},
+    core::{
+        fmt::{self, Debug},
+        iter::FromIterator,
+        marker::PhantomData,
+        mem::ManuallyDrop,
+    },
+};
+
+/// A vector of thin pointers of one type, stored as erased pointers.
+///
+/// Erased pointers are one word each, with no type to get wrong,
+/// which makes them the natural element of a large table of thin pointers.
+/// This keeps the type of the pointers in its own type parameter instead,
+/// restoring each pointer to drop it, and handing out typed views.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let mut table = ErasedVec::new();
+/// table.push(ThinArc::new("a", vec![1]));
+/// table.push(ThinArc::new("b", vec![2, 3]));
+///
+/// assert_eq!(table.len(), 2);
+/// assert_eq!(table.get(1).unwrap().slice, [2, 3]);
+/// let heads: Vec<_> = table.iter().map(|node| node.head).collect();
+/// assert_eq!(heads, ["a", "b"]);
+/// ```
+pub struct ErasedVec<P: ThinPointer> {
+    ptrs: Vec<ErasedPtr>,
+    marker: PhantomData<P>,
+}
+
+// SAFETY: this owns its pointers, like a Vec<P> would.
+unsafe impl<P: ThinPointer + Send> Send for ErasedVec<P> {}
+unsafe impl<P: ThinPointer + Sync> Sync for ErasedVec<P> {}
+
+impl<P: ThinPointer> Default for ErasedVec<P> {
+    fn default() -> Self {
+        ErasedVec {
+            ptrs: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P: ThinPointer> ErasedVec<P> {
+    /// Create a new, empty vector.
+    pub fn new() -> Self {
+        ErasedVec::default()
+    }
+
+    /// Create a new, empty vector with room for `capacity` pointers.
+    pub fn with_capacity(capacity: usize) -> Self {
+        ErasedVec {
+            ptrs: Vec::with_capacity(capacity),
+            marker: PhantomData,
+        }
+    }
+
+    /// The number of pointers in the vector.
+    pub fn len(&self) -> usize {
+        self.ptrs.len()
+    }
+
+    /// Whether the vector is empty.
+    pub fn is_empty(&self) -> bool {
+        self.ptrs.is_empty()
+    }
+
+    /// Append a pointer to the vector.
+    pub fn push(&mut self, ptr: P) {
+        self.ptrs.push(P::erase(ptr));
+    }
+
+    /// Remove the last pointer from the vector.
+    pub fn pop(&mut self) -> Option<P> {
+        let ptr = self.ptrs.pop()?;
+        Some(unsafe { P::from_erased(ptr) })
+    }
+
+    /// Remove the pointer at `index`, replacing it with the last one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> P {
+        unsafe { P::from_erased(self.ptrs.swap_remove(index)) }
+    }
+
+    /// Borrow the pointee at `index`.
+    pub fn get(&self, index: usize) -> Option<ThinRef<'_, P::Head, P::Item>> {
+        self.as_refs().get(index).copied()
+    }
+
+    /// Iterate over the pointees.
+    pub fn iter(&self) -> impl Iterator<Item = ThinRef<'_, P::Head, P::Item>> + '_ {
+        self.as_refs().iter().copied()
+    }
+
+    /// View the vector as a slice of thin references.
+    pub fn as_refs(&self) -> &[ThinRef<'_, P::Head, P::Item>] {
+        unsafe { ThinRef::from_erased_slice(&self.ptrs) }
+    }
+
+    /// View the vector as a slice of erased pointers.
+    ///
+    /// The pointers remain owned by the vector.
+    pub fn as_erased(&self) -> &[ErasedPtr] {
+        &self.ptrs
+    }
+
+    /// Take the erased pointers out of the vector.
+    ///
+    /// To avoid a memory leak, each pointer must be converted back using
+    /// [`P::from_erased`](ThinPointer::from_erased).
+    pub fn into_erased(this: Self) -> Vec<ErasedPtr> {
+        let mut this = ManuallyDrop::new(this);
+        core::mem::take(&mut this.ptrs)
+    }
+
+    /// Take ownership of a vector of erased pointers.
+    ///
+    /// # Safety
+    ///
+    /// Each pointer must logically own a valid instance of `P`.
+    pub unsafe fn from_erased(ptrs: Vec<ErasedPtr>) -> Self {
+        ErasedVec {
+            ptrs,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P: ThinPointer> Drop for ErasedVec<P> {
+    fn drop(&mut self) {
+        for ptr in self.ptrs.drain(..) {
+            drop(unsafe { P::from_erased(ptr) });
+        }
+    }
+}
+
+impl<P: ThinPointer + Clone> Clone for ErasedVec<P> {
+    fn clone(&self) -> Self {
+        let mut clone = ErasedVec::with_capacity(self.len());
+        for &ptr in &self.ptrs {
+            let ptr = ManuallyDrop::new(unsafe { P::from_erased(ptr) });
+            clone.push(P::clone(&ptr));
+        }
+        clone
+    }
+}
+
+impl<P: ThinPointer> Extend<P> for ErasedVec<P> {
+    fn extend<I: IntoIterator<Item = P>>(&mut self, iter: I) {
+        self.ptrs.extend(iter.into_iter().map(P::erase));
+    }
+}
+
+impl<P: ThinPointer> FromIterator<P> for ErasedVec<P> {
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        let mut this = ErasedVec::new();
+        this.extend(iter);
+        this
+    }
+}
+
+impl<P: ThinPointer> IntoIterator for ErasedVec<P> {
+    type Item = P;
+    type IntoIter = ErasedVecIntoIter<P>;
+
+    fn into_iter(self) -> ErasedVecIntoIter<P> {
+        ErasedVecIntoIter {
+            ptrs: ErasedVec::into_erased(self).into_iter(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P: ThinPointer> Debug for ErasedVec<P>
+where
+    P::Head: Debug,
+    P::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An owning iterator over an [`ErasedVec`].
+pub struct ErasedVecIntoIter<P: ThinPointer> {
+    ptrs: vec::IntoIter<ErasedPtr>,
+    marker: PhantomData<P>,
+}
+
+// SAFETY: this owns its pointers, like a vec::IntoIter<P> would.
+unsafe impl<P: ThinPointer + Send> Send for ErasedVecIntoIter<P> {}
+unsafe impl<P: ThinPointer + Sync> Sync for ErasedVecIntoIter<P> {}
+
+impl<P: ThinPointer> Iterator for ErasedVecIntoIter<P> {
+    type Item = P;
+
+    fn next(&mut self) -> Option<P> {
+        let ptr = self.ptrs.next()?;
+        Some(unsafe { P::from_erased(ptr) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ptrs.size_hint()
+    }
+}
+
+impl<P: ThinPointer> DoubleEndedIterator for ErasedVecIntoIter<P> {
+    fn next_back(&mut self) -> Option<P> {
+        let ptr = self.ptrs.next_back()?;
+        Some(unsafe { P::from_erased(ptr) })
+    }
+}
+
+impl<P: ThinPointer> ExactSizeIterator for ErasedVecIntoIter<P> {}
+
+impl<P: ThinPointer> Drop for ErasedVecIntoIter<P> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+impl<P: ThinPointer> Debug for ErasedVecIntoIter<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedVecIntoIter")
+            .field("remaining", &self.ptrs.len())
+            .finish()
+    }
+}
@@ -0,0 +1,163 @@
+use {
+    crate::{Len, ThinData, ThinRef},
+    core::{
+        fmt::{self, Debug},
+        marker::PhantomData,
+        ptr::NonNull,
+    },
+};
+
+/// A custom slice-holding type whose slice length is known at compile time.
+///
+/// This has the same layout as a [`ThinData`] with a slice of length `N`,
+/// including the inline length, so a `&ThinFixed` can be used as a `&ThinData`.
+///
+/// # Stability
+///
+/// As with `ThinData`, the offsets of the public fields are _not public_.
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ThinFixed<Head, SliceItem, const N: usize> {
+    // NB: this MUST have the same layout as the prefix of ThinData<Head, SliceItem>.
+    // SAFETY: must be N
+    len: Len,
+    /// The sized portion of this type.
+    pub head: Head,
+    /// The slice portion of this type.
+    pub slice: [SliceItem; N],
+}
+
+impl<Head, SliceItem, const N: usize> ThinFixed<Head, SliceItem, N> {
+    /// Create a new `ThinFixed` with the given head and slice.
+    pub const fn new(head: Head, slice: [SliceItem; N]) -> Self {
+        ThinFixed {
+            len: Len::new(N),
+            head,
+            slice,
+        }
+    }
+
+    /// Split this into its head and slice.
+    pub fn into_parts(self) -> (Head, [SliceItem; N]) {
+        (self.head, self.slice)
+    }
+}
+
+impl<Head, SliceItem, const N: usize> Debug for ThinFixed<Head, SliceItem, N>
+where
+    Head: Debug,
+    SliceItem: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinFixed")
+            .field("head", &self.head)
+            .field("slice", &self.slice)
+            .finish()
+    }
+}
+
+impl<'a, Head, SliceItem, const N: usize> From<&'a ThinFixed<Head, SliceItem, N>>
+    for &'a ThinData<Head, SliceItem>
+{
+    fn from(this: &'a ThinFixed<Head, SliceItem, N>) -> Self {
+        unsafe { &*ThinData::fatten_const(NonNull::from(this).cast()).as_ptr() }
+    }
+}
+
+impl<'a, Head, SliceItem, const N: usize> From<&'a mut ThinFixed<Head, SliceItem, N>>
+    for &'a mut ThinData<Head, SliceItem>
+{
+    fn from(this: &'a mut ThinFixed<Head, SliceItem, N>) -> Self {
+        unsafe { &mut *ThinData::fatten_mut(NonNull::from(this).cast()).as_ptr() }
+    }
+}
+
+impl<Head, SliceItem, const N: usize> AsRef<ThinData<Head, SliceItem>>
+    for ThinFixed<Head, SliceItem, N>
+{
+    fn as_ref(&self) -> &ThinData<Head, SliceItem> {
+        self.into()
+    }
+}
+
+impl<Head, SliceItem, const N: usize> AsMut<ThinData<Head, SliceItem>>
+    for ThinFixed<Head, SliceItem, N>
+{
+    fn as_mut(&mut self) -> &mut ThinData<Head, SliceItem> {
+        self.into()
+    }
+}
+
+impl<Head, SliceItem> ThinData<Head, SliceItem> {
+    /// View this as a `ThinFixed`, if the slice has length `N`.
+    pub fn try_as_fixed<const N: usize>(&self) -> Option<&ThinFixed<Head, SliceItem, N>> {
+        if self.slice.len() == N {
+            Some(unsafe { &*(self as *const Self as *const ThinFixed<Head, SliceItem, N>) })
+        } else {
+            None
+        }
+    }
+
+    /// View this as a mutable `ThinFixed`, if the slice has length `N`.
+    pub fn try_as_fixed_mut<const N: usize>(
+        &mut self,
+    ) -> Option<&mut ThinFixed<Head, SliceItem, N>> {
+        if self.slice.len() == N {
+            Some(unsafe { &mut *(self as *mut Self as *mut ThinFixed<Head, SliceItem, N>) })
+        } else {
+            None
+        }
+    }
+}
+
+impl<Head, SliceItem> ThinRef<'static, Head, SliceItem> {
+    /// Borrow a static `ThinFixed` as a `ThinRef`.
+    ///
+    /// Unlike converting through `&ThinData`, this is a `const fn`,
+    /// so it can be used in `static` initializers.
+    /// See also [`thin_static!`](crate::thin_static), which declares both at once.
+    pub const fn from_static<const N: usize>(data: &'static ThinFixed<Head, SliceItem, N>) -> Self {
+        ThinRef {
+            raw: unsafe {
+                NonNull::new_unchecked(
+                    data as *const ThinFixed<Head, SliceItem, N>
+                        as *mut ThinFixed<Head, SliceItem, N>,
+                )
+            }
+            .cast(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Declare a `static` [`ThinRef`] to data embedded in the binary.
+///
+/// The data is stored in a hidden static [`ThinFixed`],
+/// whose length is taken from the number of slice items given.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// thin_static! {
+///     pub static PRIMES: ThinRef<&str, u32> = ("primes", [2, 3, 5, 7]);
+/// }
+///
+/// static TABLE: [ThinRef<&str, u32>; 1] = [PRIMES];
+/// assert_eq!(TABLE[0].head, "primes");
+/// assert_eq!(TABLE[0].slice, [2, 3, 5, 7]);
+/// ```
+#[macro_export]
+macro_rules! thin_static {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis static $name:ident: ThinRef<$head:ty, $item:ty> = ($h:expr, [$($x:expr),* $(,)?]);
+    )*) => {$(
+        $(#[$attr])*
+        $vis static $name: $crate::ThinRef<'static, $head, $item> = {
+            const N: usize = <[&str]>::len(&[$(stringify!($x)),*]);
+            static DATA: $crate::ThinFixed<$head, $item, N> = $crate::ThinFixed::new($h, [$($x),*]);
+            $crate::ThinRef::from_static(&DATA)
+        };
+    )*};
+}
@@ -0,0 +1,143 @@
+//! Utilities for recursive structures built out of thin holders.
+
+use {
+    crate::{polyfill::make_slice_mut, ThinArc, ThinBox, ThinData},
+    alloc::{
+        alloc::{dealloc, Layout},
+        collections::BTreeSet,
+        vec::Vec,
+    },
+    core::{
+        mem,
+        ptr::{self, NonNull},
+    },
+};
+
+/// Drop a tree of `ThinBox`es without recursing once per level.
+///
+/// Dropping a `ThinBox<Head, Node>` normally drops each child `Node` in turn,
+/// which recurses as deep as the tree is, and can overflow the stack
+/// for very deep trees. This instead keeps an explicit worklist of boxes.
+///
+/// `children` is called with each slice item by value, and should return
+/// the `ThinBox` it holds (if any) after dropping anything else it holds.
+/// Heads are dropped normally, so they must not hold deep trees themselves.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// struct Node(ThinBox<(), Node>);
+///
+/// let mut node = Node(ThinBox::new((), vec![]));
+/// for _ in 0..100_000 {
+///     node = Node(ThinBox::new((), vec![node]));
+/// }
+/// graph::drop_deep(node.0, |child| Some(child.0));
+/// ```
+pub fn drop_deep<Head, SliceItem, F>(root: ThinBox<Head, SliceItem>, mut children: F)
+where
+    F: FnMut(SliceItem) -> Option<ThinBox<Head, SliceItem>>,
+{
+    /// Drops the not-yet-taken slice items and frees the node.
+    struct Dismantle<Head, SliceItem> {
+        raw: NonNull<ThinData<Head, SliceItem>>,
+        items: *mut SliceItem,
+        len: usize,
+        taken: usize,
+        layout: Layout,
+    }
+
+    impl<Head, SliceItem> Drop for Dismantle<Head, SliceItem> {
+        fn drop(&mut self) {
+            unsafe {
+                let rest = make_slice_mut(self.items.add(self.taken), self.len - self.taken);
+                ptr::drop_in_place(rest);
+                dealloc(self.raw.as_ptr().cast(), self.layout);
+            }
+        }
+    }
+
+    let mut worklist = Vec::new();
+    worklist.push(root);
+    while let Some(node) = worklist.pop() {
+        let layout = Layout::for_value::<ThinData<Head, SliceItem>>(&node);
+        let len = node.slice.len();
+        let raw = unsafe { ThinData::<Head, SliceItem>::fatten_mut(ThinBox::erase(node)) };
+        let mut node = Dismantle {
+            raw,
+            items: unsafe { ptr::addr_of_mut!((*raw.as_ptr()).slice) } as *mut SliceItem,
+            len,
+            taken: 0,
+            layout,
+        };
+        unsafe {
+            ptr::drop_in_place(ptr::addr_of_mut!((*node.raw.as_ptr()).head));
+            while node.taken < node.len {
+                let item = ptr::read(node.items.add(node.taken));
+                node.taken += 1;
+                worklist.extend(children(item));
+            }
+        }
+    }
+}
+
+/// The result of [`count_unique_allocations`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct AllocationStats {
+    /// The number of distinct `ThinArc` allocations reached.
+    pub allocations: usize,
+    /// The total size of the `ThinData` in those allocations, in bytes.
+    ///
+    /// This doesn't include the reference counts `Arc` stores alongside the data,
+    /// as `Arc`'s heap layout is not stable.
+    pub bytes: usize,
+}
+
+/// Count the distinct `ThinArc` allocations reachable from `root`.
+///
+/// Structurally shared trees are really DAGs, where the same node can be
+/// reached along many paths. This visits each allocation once, deduplicating
+/// by pointer, so the result reflects the memory actually in use rather than
+/// the size of the fully expanded tree. The traversal keeps an explicit
+/// worklist, so it doesn't recurse once per level.
+///
+/// `children` is called with each slice item, and should return the `ThinArc`
+/// it holds (if any). Memory owned by heads or slice items themselves,
+/// other than through `children`, is not counted.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// struct Node(ThinArc<u32, Node>);
+///
+/// let leaf = ThinArc::new(1, vec![]);
+/// let pair = ThinArc::new(2, vec![Node(leaf.clone()), Node(leaf.clone())]);
+/// let root = ThinArc::new(3, vec![Node(pair.clone()), Node(pair), Node(leaf)]);
+///
+/// let stats = graph::count_unique_allocations(&root, |child| Some(&child.0));
+/// assert_eq!(stats.allocations, 3);
+/// ```
+pub fn count_unique_allocations<'a, Head, SliceItem, F>(
+    root: &'a ThinArc<Head, SliceItem>,
+    mut children: F,
+) -> AllocationStats
+where
+    F: FnMut(&'a SliceItem) -> Option<&'a ThinArc<Head, SliceItem>>,
+{
+    let mut seen = BTreeSet::new();
+    let mut stats = AllocationStats::default();
+    let mut worklist = Vec::new();
+    worklist.push(root);
+    while let Some(node) = worklist.pop() {
+        if !seen.insert(node.raw) {
+            continue;
+        }
+        stats.allocations += 1;
+        stats.bytes += mem::size_of_val::<ThinData<Head, SliceItem>>(node);
+        let node: &'a ThinData<Head, SliceItem> = node;
+        worklist.extend(node.slice.iter().filter_map(&mut children));
+    }
+    stats
+}
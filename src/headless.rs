@@ -0,0 +1,172 @@
+use {
+    crate::{ThinArc, ThinBox, ThinRc},
+    alloc::vec::Vec,
+    core::{
+        marker::PhantomData,
+        ops::{Index, IndexMut},
+        slice::{self, SliceIndex},
+    },
+};
+
+/// A [`ThinBox`] of just a slice, without a head.
+///
+/// The unit head takes no space, so this is a thin `Box<[T]>`.
+///
+/// The holders still dereference to `ThinData`, but with a unit head,
+/// they can also be indexed and iterated like the slice itself.
+/// For other slice methods, go through [`as_slice`](ThinBox::as_slice).
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let mut boxed = ThinSliceBox::from_items(vec![1, 2, 3]);
+/// boxed[0] = 0;
+/// assert_eq!(boxed[1..], [2, 3]);
+/// assert_eq!((&boxed).into_iter().sum::<i32>(), 5);
+/// boxed[0] = 1;
+/// assert_eq!(ThinSliceBox::as_slice(&boxed), [1, 2, 3]);
+/// assert_eq!(boxed, *[1, 2, 3].as_slice());
+/// assert_eq!(ThinSliceBox::from(vec![1, 2, 3]), boxed);
+/// ```
+pub type ThinSliceBox<SliceItem> = ThinBox<(), SliceItem>;
+
+/// A [`ThinArc`] of just a slice, without a head.
+///
+/// The unit head takes no space, so this is a thin `Arc<[T]>`.
+pub type ThinSliceArc<SliceItem> = ThinArc<(), SliceItem>;
+
+/// A [`ThinRc`] of just a slice, without a head.
+///
+/// The unit head takes no space, so this is a thin `Rc<[T]>`.
+pub type ThinSliceRc<SliceItem> = ThinRc<(), SliceItem>;
+
+/// A head which carries no information, such as `()` or a unit struct tag.
+///
+/// All values of the type are considered interchangeable, so holders with
+/// such a head compare equal to their slice directly, ignoring the head.
+///
+/// ```rust
+/// # use thin_dst::*;
+/// #[derive(Debug)]
+/// struct Tag;
+/// impl TrivialHead for Tag {}
+///
+/// let tagged = ThinBox::new(Tag, vec![1, 2]);
+/// assert_eq!(tagged, *[1, 2].as_slice());
+/// assert_eq!(tagged, vec![1, 2]);
+/// ```
+pub trait TrivialHead {}
+
+impl TrivialHead for () {}
+impl<T: ?Sized> TrivialHead for PhantomData<T> {}
+
+macro_rules! headless {
+    ($($thin:ident),* $(,)?) => {$(
+        impl<SliceItem> $thin<(), SliceItem> {
+            /// Create a new `ThinData` with no head and the given slice.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the slice iterator incorrectly reports its length.
+            pub fn from_items<I>(slice: I) -> Self
+            where
+                I: IntoIterator<Item = SliceItem>,
+                I::IntoIter: ExactSizeIterator,
+            {
+                $thin::new((), slice)
+            }
+
+            /// Borrow the slice.
+            pub fn as_slice(this: &Self) -> &[SliceItem] {
+                &this.slice
+            }
+
+            /// Iterate over the slice.
+            pub fn iter(this: &Self) -> slice::Iter<'_, SliceItem> {
+                this.slice.iter()
+            }
+        }
+
+        impl<SliceItem, I: SliceIndex<[SliceItem]>> Index<I> for $thin<(), SliceItem> {
+            type Output = I::Output;
+            fn index(&self, index: I) -> &I::Output {
+                &self.slice[index]
+            }
+        }
+
+        impl<'a, SliceItem> IntoIterator for &'a $thin<(), SliceItem> {
+            type Item = &'a SliceItem;
+            type IntoIter = slice::Iter<'a, SliceItem>;
+            fn into_iter(self) -> Self::IntoIter {
+                self.slice.iter()
+            }
+        }
+
+        impl<SliceItem> AsRef<[SliceItem]> for $thin<(), SliceItem> {
+            fn as_ref(&self) -> &[SliceItem] {
+                &self.slice
+            }
+        }
+
+        impl<SliceItem> From<Vec<SliceItem>> for $thin<(), SliceItem> {
+            fn from(slice: Vec<SliceItem>) -> Self {
+                ((), slice).into()
+            }
+        }
+
+        impl<SliceItem: Clone> From<&[SliceItem]> for $thin<(), SliceItem> {
+            fn from(slice: &[SliceItem]) -> Self {
+                $thin::new((), slice.iter().cloned())
+            }
+        }
+
+        impl<Head: TrivialHead, SliceItem: PartialEq> PartialEq<[SliceItem]>
+            for $thin<Head, SliceItem>
+        {
+            fn eq(&self, other: &[SliceItem]) -> bool {
+                self.slice == *other
+            }
+        }
+
+        impl<Head: TrivialHead, SliceItem: PartialEq> PartialEq<Vec<SliceItem>>
+            for $thin<Head, SliceItem>
+        {
+            fn eq(&self, other: &Vec<SliceItem>) -> bool {
+                self.slice == **other
+            }
+        }
+    )*};
+}
+
+headless!(ThinBox, ThinArc, ThinRc);
+
+impl<SliceItem> ThinBox<(), SliceItem> {
+    /// Mutably borrow the slice.
+    pub fn as_mut_slice(this: &mut Self) -> &mut [SliceItem] {
+        &mut this.slice
+    }
+
+    /// Iterate mutably over the slice.
+    pub fn iter_mut(this: &mut Self) -> slice::IterMut<'_, SliceItem> {
+        this.slice.iter_mut()
+    }
+}
+
+impl<SliceItem, I: SliceIndex<[SliceItem]>> IndexMut<I> for ThinBox<(), SliceItem> {
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.slice[index]
+    }
+}
+
+impl<'a, SliceItem> IntoIterator for &'a mut ThinBox<(), SliceItem> {
+    type Item = &'a mut SliceItem;
+    type IntoIter = slice::IterMut<'a, SliceItem>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.slice.iter_mut()
+    }
+}
+
+impl<SliceItem> AsMut<[SliceItem]> for ThinBox<(), SliceItem> {
+    fn as_mut(&mut self) -> &mut [SliceItem] {
+        &mut self.slice
+    }
+}
@@ -0,0 +1,162 @@
+use {
+    crate::{ErasedPtr, ThinArc, ThinBox, ThinData, ThinRc, ThinRef},
+    alloc::{boxed::Box, rc::Rc, sync::Arc},
+    core::{ops::Deref, ptr::NonNull},
+};
+
+/// A pointer to `ThinData` which can be erased to and restored from an [`ErasedPtr`].
+///
+/// This is implemented both for the thin holders and for the standard fat
+/// pointers to `ThinData` (`Box`, `Arc`, `Rc`, and references), so generic
+/// code works across both representations, e.g. while migrating from one to the other.
+///
+/// # Examples
+///
+/// ```rust
+/// # use {std::sync::Arc, thin_dst::*};
+/// fn total<P: ThinPointer<Item = u32>>(nodes: &[P]) -> u32 {
+///     nodes.iter().flat_map(|node| &node.slice).sum()
+/// }
+///
+/// let fat: Arc<ThinData<(), u32>> = ThinArc::new((), vec![1, 2]).into();
+/// assert_eq!(total(&[ThinArc::new((), vec![1, 2])]), total(&[fat]));
+/// ```
+pub trait ThinPointer: Deref<Target = ThinData<Self::Head, Self::Item>> + Sized {
+    /// The head of the pointee.
+    type Head;
+    /// The slice item type of the pointee.
+    type Item;
+
+    /// Construct a pointer from an erased pointer.
+    ///
+    /// # Safety
+    ///
+    /// This pointer must logically own a valid instance of `Self`.
+    unsafe fn from_erased(ptr: ErasedPtr) -> Self;
+
+    /// Convert this pointer into an erased pointer.
+    ///
+    /// To avoid a memory leak the pointer must be converted back
+    /// using `Self::from_erased`.
+    fn erase(this: Self) -> ErasedPtr;
+}
+
+/// An owning pointer to `ThinData`, which can allocate a new one.
+///
+/// This lets code be written once over any ownership flavor,
+/// instead of once for each holder.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// fn squares<P: ThinHolder<Head = &'static str, Item = u32>>(n: u32) -> P {
+///     P::new("squares", (0..n).map(|i| i * i))
+/// }
+///
+/// let boxed: ThinBox<_, _> = squares(4);
+/// let shared: ThinArc<_, _> = squares(4);
+/// let fat: Box<ThinData<_, _>> = squares(4);
+/// assert_eq!(boxed.slice, shared.slice);
+/// assert_eq!(boxed.slice, fat.slice);
+/// ```
+pub trait ThinHolder: ThinPointer {
+    /// Create a new `ThinData` with the given head and slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    fn new<I>(head: Self::Head, slice: I) -> Self
+    where
+        I: IntoIterator<Item = Self::Item>,
+        I::IntoIter: ExactSizeIterator;
+}
+
+macro_rules! thin_impls {
+    ($($thin:ident),* $(,)?) => {$(
+        impl<Head, SliceItem> ThinPointer for $thin<Head, SliceItem> {
+            type Head = Head;
+            type Item = SliceItem;
+
+            unsafe fn from_erased(ptr: ErasedPtr) -> Self {
+                $thin::from_erased(ptr)
+            }
+
+            fn erase(this: Self) -> ErasedPtr {
+                $thin::erase(this)
+            }
+        }
+
+        impl<Head, SliceItem> ThinHolder for $thin<Head, SliceItem> {
+            fn new<I>(head: Head, slice: I) -> Self
+            where
+                I: IntoIterator<Item = SliceItem>,
+                I::IntoIter: ExactSizeIterator,
+            {
+                $thin::new(head, slice)
+            }
+        }
+    )*};
+}
+
+thin_impls!(ThinBox, ThinArc, ThinRc);
+
+impl<Head, SliceItem> ThinPointer for ThinRef<'_, Head, SliceItem> {
+    type Head = Head;
+    type Item = SliceItem;
+
+    unsafe fn from_erased(ptr: ErasedPtr) -> Self {
+        ThinRef::from_erased(ptr)
+    }
+
+    fn erase(this: Self) -> ErasedPtr {
+        ThinRef::erase(this)
+    }
+}
+
+impl<Head, SliceItem> ThinPointer for &ThinData<Head, SliceItem> {
+    type Head = Head;
+    type Item = SliceItem;
+
+    unsafe fn from_erased(ptr: ErasedPtr) -> Self {
+        &*ThinData::fatten_const(ptr).as_ptr()
+    }
+
+    fn erase(this: Self) -> ErasedPtr {
+        ThinData::erase(NonNull::from(this))
+    }
+}
+
+macro_rules! fat_impls {
+    ($($fat:ident($thin:ident): $fatten:ident;)*) => {$(
+        impl<Head, SliceItem> ThinPointer for $fat<ThinData<Head, SliceItem>> {
+            type Head = Head;
+            type Item = SliceItem;
+
+            unsafe fn from_erased(ptr: ErasedPtr) -> Self {
+                $fat::from_raw(ThinData::$fatten(ptr).as_ptr())
+            }
+
+            fn erase(this: Self) -> ErasedPtr {
+                let ptr = $fat::into_raw(this) as *mut ThinData<Head, SliceItem>;
+                ThinData::erase(unsafe { NonNull::new_unchecked(ptr) })
+            }
+        }
+
+        impl<Head, SliceItem> ThinHolder for $fat<ThinData<Head, SliceItem>> {
+            fn new<I>(head: Head, slice: I) -> Self
+            where
+                I: IntoIterator<Item = SliceItem>,
+                I::IntoIter: ExactSizeIterator,
+            {
+                $thin::new(head, slice).into()
+            }
+        }
+    )*};
+}
+
+fat_impls! {
+    Box(ThinBox): fatten_mut;
+    Arc(ThinArc): fatten_const;
+    Rc(ThinRc): fatten_const;
+}
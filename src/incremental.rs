@@ -0,0 +1,151 @@
+use {
+    crate::{polyfill::make_slice_mut, ThinBox, ThinData},
+    alloc::alloc::{dealloc, Layout},
+    core::{
+        fmt::{self, Debug},
+        ptr::{self, NonNull},
+    },
+};
+
+/// A `ThinBox` being dropped a bounded number of items at a time.
+///
+/// Created by [`ThinBox::drop_incremental`]. Each call to [`step`](Self::step)
+/// drops the head (the first time) and then up to `budget` slice items,
+/// in the same order as dropping the box would, so destroying a huge node
+/// can be spread over many frames of a realtime loop. The allocation
+/// is freed once every item is dropped. Dropping this finishes the job at once.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let boxed = ThinBox::new("huge", (0..10_000).map(|i| i.to_string()));
+/// let mut dropping = ThinBox::drop_incremental(boxed);
+/// let mut frames = 0;
+/// while !dropping.step(1_000) {
+///     frames += 1;
+/// }
+/// assert_eq!(frames, 9);
+/// assert_eq!(dropping.remaining(), 0);
+/// ```
+pub struct IncrementalDrop<Head, SliceItem> {
+    raw: NonNull<ThinData<Head, SliceItem>>,
+    layout: Layout,
+    head_dropped: bool,
+    /// The number of slice items dropped so far.
+    dropped: usize,
+    /// Whether the allocation has been freed.
+    freed: bool,
+}
+
+unsafe impl<Head: Send, SliceItem: Send> Send for IncrementalDrop<Head, SliceItem> {}
+unsafe impl<Head: Sync, SliceItem: Sync> Sync for IncrementalDrop<Head, SliceItem> {}
+
+impl<Head, SliceItem> ThinBox<Head, SliceItem> {
+    /// Start dropping this box incrementally.
+    ///
+    /// See [`IncrementalDrop`].
+    pub fn drop_incremental(this: Self) -> IncrementalDrop<Head, SliceItem> {
+        let layout = Layout::for_value::<ThinData<Head, SliceItem>>(&this);
+        IncrementalDrop {
+            raw: unsafe { ThinData::fatten_mut(ThinBox::erase(this)) },
+            layout,
+            head_dropped: false,
+            dropped: 0,
+            freed: false,
+        }
+    }
+}
+
+impl<Head, SliceItem> IncrementalDrop<Head, SliceItem> {
+    fn len(&self) -> usize {
+        unsafe {
+            ThinData::<Head, SliceItem>::len(self.raw.cast())
+                .as_ptr()
+                .read()
+                .get()
+        }
+    }
+
+    fn items(&self) -> *mut SliceItem {
+        unsafe { ptr::addr_of_mut!((*self.raw.as_ptr()).slice) as *mut SliceItem }
+    }
+
+    /// The number of slice items not dropped yet.
+    pub fn remaining(&self) -> usize {
+        if self.freed {
+            0
+        } else {
+            self.len() - self.dropped
+        }
+    }
+
+    /// Drop the head if it hasn't been already, then up to `budget` slice items.
+    ///
+    /// Returns whether everything has been dropped and the allocation freed.
+    /// If an item panics while dropping, it counts as dropped.
+    pub fn step(&mut self, budget: usize) -> bool {
+        if self.freed {
+            return true;
+        }
+        if !self.head_dropped {
+            self.head_dropped = true;
+            unsafe { ptr::drop_in_place(ptr::addr_of_mut!((*self.raw.as_ptr()).head)) };
+        }
+        let end = self.dropped + budget.min(self.len() - self.dropped);
+        while self.dropped < end {
+            let item = unsafe { self.items().add(self.dropped) };
+            self.dropped += 1;
+            unsafe { ptr::drop_in_place(item) };
+        }
+        if self.dropped == self.len() {
+            self.freed = true;
+            unsafe { dealloc(self.raw.as_ptr().cast(), self.layout) };
+        }
+        self.freed
+    }
+}
+
+impl<Head, SliceItem> Drop for IncrementalDrop<Head, SliceItem> {
+    fn drop(&mut self) {
+        /// Frees the allocation, even if the head or an item panics while dropping.
+        struct Dealloc(*mut u8, Layout);
+        impl Drop for Dealloc {
+            fn drop(&mut self) {
+                unsafe { dealloc(self.0, self.1) };
+            }
+        }
+
+        /// Drops the rest of the items after the head, even if the head panics.
+        struct DropItems<SliceItem>(*mut [SliceItem]);
+        impl<SliceItem> Drop for DropItems<SliceItem> {
+            fn drop(&mut self) {
+                unsafe { ptr::drop_in_place(self.0) };
+            }
+        }
+
+        if self.freed {
+            return;
+        }
+        // The guards own the cleanup from here on, however it unwinds.
+        self.freed = true;
+        let _dealloc = Dealloc(self.raw.as_ptr().cast(), self.layout);
+        let _rest = DropItems(unsafe {
+            make_slice_mut(self.items().add(self.dropped), self.len() - self.dropped)
+        });
+        self.dropped = self.len();
+        if !self.head_dropped {
+            self.head_dropped = true;
+            unsafe { ptr::drop_in_place(ptr::addr_of_mut!((*self.raw.as_ptr()).head)) };
+        }
+    }
+}
+
+impl<Head, SliceItem> Debug for IncrementalDrop<Head, SliceItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IncrementalDrop")
+            .field("head_dropped", &self.head_dropped)
+            .field("remaining", &self.remaining())
+            .finish()
+    }
+}
@@ -0,0 +1,131 @@
+use {
+    crate::{ThinBox, ThinData},
+    alloc::boxed::Box,
+    core::{iter::FusedIterator, slice},
+};
+
+/// Slices of boxed, possibly unsized, items.
+///
+/// A slice item must be sized, so a heterogeneous slice of trait objects
+/// or strings is stored as `Box<U>` items, one pointer (or fat pointer) each.
+/// These helpers save spelling out the extra dereference.
+///
+/// Dropping the `ThinData` drops the head, then each boxed item in order,
+/// which drops its pointee and frees its allocation, just like a `Vec<Box<U>>`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// use std::fmt::Display;
+/// let items: Vec<Box<dyn Display>> = vec![Box::new(1), Box::new("two"), Box::new(3.5)];
+/// let row: ThinBox<_, Box<dyn Display>> = ThinBox::from_boxed_items("row", items);
+/// let rendered: Vec<String> = row.deref_items().map(|item| item.to_string()).collect();
+/// assert_eq!(rendered, ["1", "two", "3.5"]);
+///
+/// let names = ThinBox::<_, Box<str>>::from_boxed_items((), vec!["ab", "c"]);
+/// assert_eq!(names.get_deref(1), Some("c"));
+/// ```
+impl<Head, U: ?Sized> ThinData<Head, Box<U>> {
+    /// Borrow the pointee of the item at `index`.
+    pub fn get_deref(&self, index: usize) -> Option<&U> {
+        self.slice.get(index).map(|item| &**item)
+    }
+
+    /// Mutably borrow the pointee of the item at `index`.
+    pub fn get_deref_mut(&mut self, index: usize) -> Option<&mut U> {
+        self.slice.get_mut(index).map(|item| &mut **item)
+    }
+
+    /// Iterate over the pointees of the items.
+    pub fn deref_items(&self) -> DerefItems<'_, U> {
+        DerefItems {
+            items: self.slice.iter(),
+        }
+    }
+
+    /// Iterate mutably over the pointees of the items.
+    pub fn deref_items_mut(&mut self) -> DerefItemsMut<'_, U> {
+        DerefItemsMut {
+            items: self.slice.iter_mut(),
+        }
+    }
+}
+
+impl<Head, U: ?Sized> ThinBox<Head, Box<U>> {
+    /// Create a new boxed `ThinData` with the given head,
+    /// boxing each of the given items.
+    ///
+    /// This accepts anything which converts into a `Box<U>`, such as `&str`
+    /// or `String` for `Box<str>`, as well as already boxed trait objects.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the item iterator incorrectly reports its length.
+    pub fn from_boxed_items<I>(head: Head, items: I) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+        I::Item: Into<Box<U>>,
+    {
+        ThinBox::new(head, items.into_iter().map(Into::into))
+    }
+}
+
+/// An iterator over the pointees of a slice of boxes.
+///
+/// Returned by [`ThinData::deref_items`].
+#[derive(Debug, Clone)]
+pub struct DerefItems<'a, U: ?Sized> {
+    items: slice::Iter<'a, Box<U>>,
+}
+
+/// A mutable iterator over the pointees of a slice of boxes.
+///
+/// Returned by [`ThinData::deref_items_mut`].
+#[derive(Debug)]
+pub struct DerefItemsMut<'a, U: ?Sized> {
+    items: slice::IterMut<'a, Box<U>>,
+}
+
+impl<'a, U: ?Sized> Iterator for DerefItems<'a, U> {
+    type Item = &'a U;
+
+    fn next(&mut self) -> Option<&'a U> {
+        self.items.next().map(|item| &**item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<'a, U: ?Sized> DoubleEndedIterator for DerefItems<'a, U> {
+    fn next_back(&mut self) -> Option<&'a U> {
+        self.items.next_back().map(|item| &**item)
+    }
+}
+
+impl<'a, U: ?Sized> ExactSizeIterator for DerefItems<'a, U> {}
+impl<'a, U: ?Sized> FusedIterator for DerefItems<'a, U> {}
+
+impl<'a, U: ?Sized> Iterator for DerefItemsMut<'a, U> {
+    type Item = &'a mut U;
+
+    fn next(&mut self) -> Option<&'a mut U> {
+        self.items.next().map(|item| &mut **item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<'a, U: ?Sized> DoubleEndedIterator for DerefItemsMut<'a, U> {
+    fn next_back(&mut self) -> Option<&'a mut U> {
+        self.items.next_back().map(|item| &mut **item)
+    }
+}
+
+impl<'a, U: ?Sized> ExactSizeIterator for DerefItemsMut<'a, U> {}
+impl<'a, U: ?Sized> FusedIterator for DerefItemsMut<'a, U> {}
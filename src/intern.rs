@@ -0,0 +1,190 @@
+use {
+    crate::ThinArc,
+    alloc::vec::Vec,
+    core::{
+        fmt::{self, Debug},
+        hash::{Hash, Hasher},
+        marker::PhantomData,
+        mem,
+    },
+};
+
+/// A set of shared `ThinArc`s, deduplicated by value.
+///
+/// Interning a value returns the `ThinArc` already holding an equal value,
+/// if there is one. Lookups take the head and slice by reference, so nothing
+/// is allocated for a hit. A miss returns a [`PendingIntern`], which remembers
+/// the hash and the slot the value belongs in, so inserting the newly built
+/// `ThinArc` neither hashes it again nor probes the table again.
+///
+/// Values are hashed with a fresh `H` each, as with [`Prehashed`](crate::Prehashed).
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// use std::collections::hash_map::DefaultHasher;
+/// let mut interner = ThinInterner::<_, _, DefaultHasher>::new();
+///
+/// let a = interner.intern("node", vec![1, 2]);
+/// let b = match interner.lookup(&"node", &[1, 2]) {
+///     Ok(existing) => existing,
+///     Err(pending) => pending.insert(ThinArc::new("node", vec![1, 2])),
+/// };
+/// assert!(ThinArc::ptr_eq(&a, &b));
+/// assert_eq!(interner.len(), 1);
+/// ```
+pub struct ThinInterner<Head, SliceItem, H> {
+    /// A power of two number of slots, probed linearly. Entries are never removed.
+    slots: Vec<Option<(u64, ThinArc<Head, SliceItem>)>>,
+    len: usize,
+    // fn() -> H: the hasher is only used transiently.
+    marker: PhantomData<fn() -> H>,
+}
+
+impl<Head, SliceItem, H> Default for ThinInterner<Head, SliceItem, H> {
+    fn default() -> Self {
+        ThinInterner {
+            slots: Vec::new(),
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Head, SliceItem, H> ThinInterner<Head, SliceItem, H> {
+    /// Create a new, empty interner.
+    pub fn new() -> Self {
+        ThinInterner::default()
+    }
+
+    /// The number of distinct values interned.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether nothing has been interned.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the interned values, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &ThinArc<Head, SliceItem>> + '_ {
+        self.slots.iter().flatten().map(|(_, arc)| arc)
+    }
+
+    /// Make sure there's room for one more entry, keeping the load at most 7/8.
+    fn reserve_one(&mut self) {
+        if (self.len + 1) * 8 <= self.slots.len() * 7 {
+            return;
+        }
+        let capacity = (self.slots.len() * 2).max(8);
+        let old = mem::replace(&mut self.slots, Vec::with_capacity(capacity));
+        self.slots.resize_with(capacity, || None);
+        for (hash, arc) in old.into_iter().flatten() {
+            let mut slot = hash as usize & (capacity - 1);
+            while self.slots[slot].is_some() {
+                slot = (slot + 1) & (capacity - 1);
+            }
+            self.slots[slot] = Some((hash, arc));
+        }
+    }
+}
+
+impl<Head, SliceItem, H> ThinInterner<Head, SliceItem, H>
+where
+    Head: Hash + Eq,
+    SliceItem: Hash + Eq,
+    H: Hasher + Default,
+{
+    fn hash(head: &Head, slice: &[SliceItem]) -> u64 {
+        let mut hasher = H::default();
+        head.hash(&mut hasher);
+        slice.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Look up the interned value equal to `head` and `slice`.
+    ///
+    /// On a miss, the returned [`PendingIntern`] inserts a `ThinArc`
+    /// holding that value without hashing it again.
+    pub fn lookup(
+        &mut self,
+        head: &Head,
+        slice: &[SliceItem],
+    ) -> Result<ThinArc<Head, SliceItem>, PendingIntern<'_, Head, SliceItem, H>> {
+        self.reserve_one();
+        let hash = Self::hash(head, slice);
+        let mask = self.slots.len() - 1;
+        let mut slot = hash as usize & mask;
+        while let Some((slot_hash, arc)) = &self.slots[slot] {
+            if *slot_hash == hash && arc.head == *head && arc.slice == *slice {
+                return Ok(arc.clone());
+            }
+            slot = (slot + 1) & mask;
+        }
+        Err(PendingIntern {
+            interner: self,
+            hash,
+            slot,
+        })
+    }
+
+    /// Intern a value, building a new `ThinArc` only if it isn't interned yet.
+    pub fn intern<I>(&mut self, head: Head, slice: I) -> ThinArc<Head, SliceItem>
+    where
+        I: IntoIterator<Item = SliceItem>,
+    {
+        let slice: Vec<SliceItem> = slice.into_iter().collect();
+        match self.lookup(&head, &slice) {
+            Ok(existing) => existing,
+            Err(pending) => pending.insert(ThinArc::from((head, slice))),
+        }
+    }
+}
+
+impl<Head, SliceItem, H> Debug for ThinInterner<Head, SliceItem, H>
+where
+    Head: Debug,
+    SliceItem: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// A value missing from a [`ThinInterner`], and where it goes.
+///
+/// Returned by [`ThinInterner::lookup`]. Dropping this inserts nothing.
+pub struct PendingIntern<'a, Head, SliceItem, H> {
+    interner: &'a mut ThinInterner<Head, SliceItem, H>,
+    hash: u64,
+    slot: usize,
+}
+
+impl<'a, Head, SliceItem, H> PendingIntern<'a, Head, SliceItem, H> {
+    /// The hash of the value that was looked up.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Insert a `ThinArc` holding the value that was looked up,
+    /// returning it back.
+    ///
+    /// The value isn't hashed or compared again, so it must be equal to the
+    /// one looked up; otherwise, the interner will fail to find it later.
+    pub fn insert(self, arc: ThinArc<Head, SliceItem>) -> ThinArc<Head, SliceItem> {
+        self.interner.slots[self.slot] = Some((self.hash, arc.clone()));
+        self.interner.len += 1;
+        arc
+    }
+}
+
+impl<Head, SliceItem, H> Debug for PendingIntern<'_, Head, SliceItem, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingIntern")
+            .field("hash", &self.hash)
+            .field("slot", &self.slot)
+            .finish()
+    }
+}
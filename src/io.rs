@@ -0,0 +1,36 @@
+use {crate::ThinData, std::io::Cursor};
+
+impl<Head> ThinData<Head, u8> {
+    /// Read the byte slice in place through [`std::io::Read`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use thin_dst::*; use std::io::Read;
+    /// let bytes = ThinBox::new((), b"hello".iter().copied());
+    /// let mut read = String::new();
+    /// bytes.as_cursor().read_to_string(&mut read).unwrap();
+    /// assert_eq!(read, "hello");
+    /// ```
+    pub fn as_cursor(&self) -> Cursor<&[u8]> {
+        Cursor::new(&self.slice)
+    }
+
+    /// Overwrite the byte slice in place through [`std::io::Write`].
+    ///
+    /// The slice can't grow, so writes past its end are short,
+    /// and `write_all` fails with [`std::io::ErrorKind::WriteZero`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use thin_dst::*; use std::io::Write;
+    /// let mut bytes = ThinBox::new((), vec![0; 4]);
+    /// bytes.as_write_cursor().write_all(&[1, 2]).unwrap();
+    /// assert_eq!(bytes.slice, [1, 2, 0, 0]);
+    /// assert!(bytes.as_write_cursor().write_all(&[0; 5]).is_err());
+    /// ```
+    pub fn as_write_cursor(&mut self) -> Cursor<&mut [u8]> {
+        Cursor::new(&mut self.slice)
+    }
+}
@@ -0,0 +1,123 @@
+use {
+    crate::{ThinArc, ThinBox, ThinData, ThinRc},
+    core::{iter::FusedIterator, slice},
+};
+
+/// An iterator pairing each slice item with the head.
+///
+/// Returned by [`ThinData::iter_with_head`] and [`ThinData::iter_with_head_mut`].
+#[derive(Debug, Clone)]
+pub struct WithHead<'a, Head, I> {
+    head: &'a Head,
+    items: I,
+}
+
+impl<Head, SliceItem> ThinData<Head, SliceItem> {
+    /// Iterate over the slice items, each paired with the head.
+    ///
+    /// The length of the slice is only read once, up front.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let scaled = ThinBox::new(10, vec![1, 2, 3]);
+    /// let items: Vec<_> = scaled.iter_with_head().map(|(scale, x)| scale * x).collect();
+    /// assert_eq!(items, [10, 20, 30]);
+    /// ```
+    pub fn iter_with_head(&self) -> WithHead<'_, Head, slice::Iter<'_, SliceItem>> {
+        WithHead {
+            head: &self.head,
+            items: self.slice.iter(),
+        }
+    }
+
+    /// Iterate mutably over the slice items, each paired with the head.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let mut scaled = ThinBox::new(10, vec![1, 2, 3]);
+    /// scaled.iter_with_head_mut().for_each(|(scale, x)| *x *= scale);
+    /// assert_eq!(scaled.slice, [10, 20, 30]);
+    /// ```
+    pub fn iter_with_head_mut(&mut self) -> WithHead<'_, Head, slice::IterMut<'_, SliceItem>> {
+        WithHead {
+            head: &self.head,
+            items: self.slice.iter_mut(),
+        }
+    }
+}
+
+impl<'a, Head, I: Iterator> Iterator for WithHead<'a, Head, I> {
+    type Item = (&'a Head, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next().map(|item| (self.head, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.items.nth(n).map(|item| (self.head, item))
+    }
+}
+
+impl<Head, I: DoubleEndedIterator> DoubleEndedIterator for WithHead<'_, Head, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.items.next_back().map(|item| (self.head, item))
+    }
+}
+
+impl<Head, I: ExactSizeIterator> ExactSizeIterator for WithHead<'_, Head, I> {}
+
+impl<Head, I: FusedIterator> FusedIterator for WithHead<'_, Head, I> {}
+
+/// Collect an iterator into a thin holder, at the end of an iterator chain.
+///
+/// This is the same as passing the iterator to the holder's `new`,
+/// but reads left to right like `collect`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let squares = (1..5).map(|x| x * x).collect_thin_box("squares");
+/// assert_eq!(squares.slice, [1, 4, 9, 16]);
+///
+/// let words = ["a", "bb"].iter().map(|s| s.len()).collect_thin_arc(());
+/// assert_eq!(words.slice, [1, 2]);
+/// ```
+pub trait CollectThin: ExactSizeIterator + Sized {
+    /// Collect the items into a `ThinBox` with the given head.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator incorrectly reports its length.
+    fn collect_thin_box<Head>(self, head: Head) -> ThinBox<Head, Self::Item> {
+        ThinBox::new(head, self)
+    }
+
+    /// Collect the items into a `ThinArc` with the given head.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator incorrectly reports its length.
+    fn collect_thin_arc<Head>(self, head: Head) -> ThinArc<Head, Self::Item> {
+        ThinArc::new(head, self)
+    }
+
+    /// Collect the items into a `ThinRc` with the given head.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator incorrectly reports its length.
+    fn collect_thin_rc<Head>(self, head: Head) -> ThinRc<Head, Self::Item> {
+        ThinRc::new(head, self)
+    }
+}
+
+impl<I: ExactSizeIterator> CollectThin for I {}
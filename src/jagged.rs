@@ -0,0 +1,234 @@
+use {
+    crate::{
+        polyfill::{extend_layout, layout_array, make_slice, make_slice_mut, pad_layout_to_align},
+        ThinBox, ThinData,
+    },
+    alloc::{
+        alloc::{alloc, dealloc, handle_alloc_error, Layout, LayoutError},
+        vec::Vec,
+    },
+    core::{
+        fmt::{self, Debug},
+        marker::PhantomData,
+        ptr::{self, NonNull},
+    },
+};
+
+#[repr(C)]
+struct Header {
+    rows: usize,
+    items: usize,
+}
+
+pub(crate) struct Row<Head> {
+    pub(crate) head: Head,
+    /// The index one past this row's last item.
+    pub(crate) end: usize,
+}
+
+impl<Head> Row<Head> {
+    /// Move `boxed`'s slice to `slices[start..]`, freeing the box,
+    /// and return its head as the row ending after the moved items.
+    ///
+    /// # Safety
+    ///
+    /// `slices` must be valid for writes of `start + boxed.slice.len()` items.
+    pub(crate) unsafe fn move_from<SliceItem>(
+        boxed: ThinBox<Head, SliceItem>,
+        slices: *mut SliceItem,
+        start: usize,
+    ) -> Self {
+        let len = boxed.slice.len();
+        let box_layout = Layout::for_value::<ThinData<Head, SliceItem>>(&boxed);
+        let data = ThinData::<Head, SliceItem>::fatten_mut(ThinBox::erase(boxed)).as_ptr();
+        ptr::copy_nonoverlapping((*data).slice.as_ptr(), slices.add(start), len);
+        let head = ptr::read(&(*data).head);
+        dealloc(data.cast(), box_layout);
+        Row {
+            head,
+            end: start + len,
+        }
+    }
+}
+
+/// Many heads and slices, packed together into a single allocation.
+///
+/// The allocation holds the number of rows and items, then each row's head
+/// and end offset, then every row's slice items back to back. As the counts
+/// are stored inline, this is a thin pointer, just like a `ThinBox`.
+///
+/// This is meant for compacting many long-lived, read-mostly boxes to
+/// reduce allocator fragmentation. Rows can be mutated in place,
+/// but not resized, added, or removed.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let boxes = vec![
+///     ThinBox::new("a", vec![1, 2]),
+///     ThinBox::new("b", vec![]),
+///     ThinBox::new("c", vec![3]),
+/// ];
+/// let jagged = ThinJagged::concat_all(boxes);
+/// assert_eq!(jagged.len(), 3);
+/// assert_eq!(jagged.get(0), Some((&"a", &[1, 2][..])));
+/// assert_eq!(jagged.get(2), Some((&"c", &[3][..])));
+/// ```
+pub struct ThinJagged<Head, SliceItem> {
+    raw: NonNull<Header>,
+    marker: PhantomData<ThinBox<Head, SliceItem>>,
+}
+
+unsafe impl<Head: Send, SliceItem: Send> Send for ThinJagged<Head, SliceItem> {}
+unsafe impl<Head: Sync, SliceItem: Sync> Sync for ThinJagged<Head, SliceItem> {}
+
+impl<Head, SliceItem> ThinJagged<Head, SliceItem> {
+    fn try_layout(rows: usize, items: usize) -> Result<(Layout, [usize; 2]), LayoutError> {
+        let header = Layout::new::<Header>();
+        let (layout, rows_offset) = extend_layout(&header, layout_array::<Row<Head>>(rows)?)?;
+        let (layout, items_offset) = extend_layout(&layout, layout_array::<SliceItem>(items)?)?;
+        Ok((pad_layout_to_align(&layout), [rows_offset, items_offset]))
+    }
+
+    fn layout(rows: usize, items: usize) -> (Layout, [usize; 2]) {
+        Self::try_layout(rows, items).unwrap_or_else(|e| panic!("oversize jagged: {}", e))
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { self.raw.as_ref() }
+    }
+
+    fn parts(&self) -> (*mut Row<Head>, *mut SliceItem) {
+        let header = self.header();
+        let (_, [rows_offset, items_offset]) = Self::layout(header.rows, header.items);
+        let raw = self.raw.as_ptr().cast::<u8>();
+        unsafe { (raw.add(rows_offset).cast(), raw.add(items_offset).cast()) }
+    }
+
+    fn rows(&self) -> &[Row<Head>] {
+        let (rows, _) = self.parts();
+        unsafe { &*make_slice(rows, self.len()) }
+    }
+
+    /// Move the heads and slices of `boxes` into one allocation, in order,
+    /// freeing the boxes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined allocation would exceed `isize::MAX` bytes,
+    /// or the combined slice length would overflow `usize`.
+    pub fn concat_all<I>(boxes: I) -> Self
+    where
+        I: IntoIterator<Item = ThinBox<Head, SliceItem>>,
+    {
+        let boxes: Vec<_> = boxes.into_iter().collect();
+        let items = boxes.iter().fold(0, |items: usize, boxed| {
+            // Zero-sized items don't bound the total through the layout.
+            items
+                .checked_add(boxed.slice.len())
+                .unwrap_or_else(|| panic!("oversize jagged: too many slice items"))
+        });
+        let (layout, _) = Self::layout(boxes.len(), items);
+        let raw =
+            unsafe { NonNull::new(alloc(layout)).unwrap_or_else(|| handle_alloc_error(layout)) };
+        let this = ThinJagged::<Head, SliceItem> {
+            raw: raw.cast::<Header>(),
+            marker: PhantomData,
+        };
+        unsafe {
+            ptr::write(
+                this.raw.as_ptr(),
+                Header {
+                    rows: boxes.len(),
+                    items,
+                },
+            );
+        }
+        let (rows, slices) = this.parts();
+        let mut end = 0;
+        // Nothing in here can panic, so the boxes are never left half-moved.
+        for (i, boxed) in boxes.into_iter().enumerate() {
+            unsafe {
+                let row = Row::move_from(boxed, slices, end);
+                end = row.end;
+                ptr::write(rows.add(i), row);
+            }
+        }
+        this
+    }
+
+    /// The number of rows.
+    pub fn len(&self) -> usize {
+        self.header().rows
+    }
+
+    /// Whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The total number of slice items across all rows.
+    pub fn items_len(&self) -> usize {
+        self.header().items
+    }
+
+    /// Borrow the head and slice of the row at `index`.
+    pub fn get(&self, index: usize) -> Option<(&Head, &[SliceItem])> {
+        let rows = self.rows();
+        let row = rows.get(index)?;
+        let start = if index == 0 { 0 } else { rows[index - 1].end };
+        let (_, slices) = self.parts();
+        let slice = unsafe { &*make_slice(slices.add(start), row.end - start) };
+        Some((&row.head, slice))
+    }
+
+    /// Mutably borrow the head and slice of the row at `index`.
+    pub fn get_mut(&mut self, index: usize) -> Option<(&mut Head, &mut [SliceItem])> {
+        if index >= self.len() {
+            return None;
+        }
+        let (rows, slices) = self.parts();
+        unsafe {
+            let start = if index == 0 {
+                0
+            } else {
+                (*rows.add(index - 1)).end
+            };
+            let row = &mut *rows.add(index);
+            let slice = &mut *make_slice_mut(slices.add(start), row.end - start);
+            Some((&mut row.head, slice))
+        }
+    }
+
+    /// Iterate over the head and slice of each row.
+    pub fn iter(&self) -> impl Iterator<Item = (&Head, &[SliceItem])> + '_ {
+        (0..self.len()).map(move |index| match self.get(index) {
+            Some(row) => row,
+            None => unreachable!(),
+        })
+    }
+}
+
+impl<Head, SliceItem> Drop for ThinJagged<Head, SliceItem> {
+    fn drop(&mut self) {
+        let Header { rows, items } = *self.header();
+        let (layout, _) = Self::layout(rows, items);
+        let (row_ptr, slice_ptr) = self.parts();
+        unsafe {
+            ptr::drop_in_place(make_slice_mut(row_ptr, rows));
+            ptr::drop_in_place(make_slice_mut(slice_ptr, items));
+            dealloc(self.raw.as_ptr().cast(), layout);
+        }
+    }
+}
+
+impl<Head, SliceItem> Debug for ThinJagged<Head, SliceItem>
+where
+    Head: Debug,
+    SliceItem: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
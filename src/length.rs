@@ -0,0 +1,94 @@
+use crate::{ThinBox, ThinData};
+
+/// A head which declares the length of the slice after it,
+/// such as the header of a length-prefixed protocol message.
+///
+/// `ThinData` always stores its own length, so this doesn't change the layout.
+/// Instead, it lets the header decide how many items to read, and lets
+/// the stored length be checked against what the header claims.
+/// Implement it with [`head_length!`](crate::head_length) rather than by hand.
+pub trait HeadLength<SliceItem> {
+    /// The number of slice items the head declares,
+    /// or `None` if that isn't a whole number of items that fits in a `usize`.
+    fn slice_len(&self) -> Option<usize>;
+}
+
+/// Implement [`HeadLength`] from an integer field of the head.
+///
+/// The field holds either the number of slice `items`,
+/// or the number of `bytes` they take up, which must be a multiple
+/// of the (non-zero) size of the slice item.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// struct Frame {
+///     kind: u8,
+///     payload_bytes: u16,
+/// }
+///
+/// struct Record {
+///     fields: u32,
+/// }
+///
+/// head_length! {
+///     impl HeadLength<u32> for Frame { bytes: payload_bytes }
+///     impl HeadLength<String> for Record { items: fields }
+/// }
+///
+/// let header = Frame { kind: 1, payload_bytes: 8 };
+/// let frame = ThinBox::new_with_head_len(header, vec![7, 9]);
+/// assert!(frame.head_len_matches());
+/// assert_eq!(Frame { kind: 2, payload_bytes: 3 }.slice_len(), None);
+/// ```
+#[macro_export]
+macro_rules! head_length {
+    ($(impl HeadLength<$item:ty> for $head:ty { $unit:ident: $field:tt })*) => {$(
+        impl $crate::HeadLength<$item> for $head {
+            fn slice_len(&self) -> ::core::option::Option<usize> {
+                let len = <usize as ::core::convert::TryFrom<_>>::try_from(self.$field).ok()?;
+                $crate::head_length!(@$unit len, $item)
+            }
+        }
+    )*};
+    (@items $len:ident, $item:ty) => {
+        ::core::option::Option::Some($len)
+    };
+    (@bytes $len:ident, $item:ty) => {{
+        let size = ::core::mem::size_of::<$item>();
+        $len.checked_div(size).filter(|&items| items * size == $len)
+    }};
+}
+
+impl<Head, SliceItem> ThinData<Head, SliceItem>
+where
+    Head: HeadLength<SliceItem>,
+{
+    /// Whether the slice has the length that the head declares.
+    pub fn head_len_matches(&self) -> bool {
+        self.head.slice_len() == Some(self.slice.len())
+    }
+}
+
+impl<Head, SliceItem> ThinBox<Head, SliceItem>
+where
+    Head: HeadLength<SliceItem>,
+{
+    /// Create a new boxed `ThinData` with the given head,
+    /// and as many slice items as it declares.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the head's declared length is invalid,
+    /// or the slice iterator doesn't yield exactly that many items.
+    pub fn new_with_head_len<I>(head: Head, slice: I) -> Self
+    where
+        I: IntoIterator<Item = SliceItem>,
+    {
+        let len = head
+            .slice_len()
+            .expect("head declares an invalid slice length");
+        ThinBox::new_with_len(head, len, slice)
+    }
+}
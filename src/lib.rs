@@ -8,8 +8,8 @@
 //!
 //! ```rust
 //! # use thin_dst::*;
-//! let boxed_slice = ThinBox::new((), vec![0, 1, 2, 3, 4, 5]);
-//! assert_eq!(&*boxed_slice, &[0, 1, 2, 3, 4, 5][..]);
+//! let boxed_slice = ThinSliceBox::from_items(vec![0, 1, 2, 3, 4, 5]);
+//! assert_eq!(boxed_slice[1..], [1, 2, 3, 4, 5]);
 //! let boxed_slice: Box<ThinData<(), u32>> = boxed_slice.into();
 //! ```
 //!
@@ -33,28 +33,140 @@
 //!   [ref-cast]: <https://lib.rs/crates/ref-cast>
 
 #![no_std]
+#![cfg_attr(thin_dst_nightly, feature(dropck_eyepatch))]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 use {
     crate::polyfill::*,
     alloc::{
-        alloc::{alloc, dealloc, handle_alloc_error, Layout, LayoutErr},
+        alloc::{alloc, alloc_zeroed, dealloc, handle_alloc_error, realloc, Layout, LayoutError},
         boxed::Box,
         rc::Rc,
-        sync::Arc,
+        sync::{Arc, Weak},
+        vec::Vec,
     },
     core::{
         cmp::{self, PartialEq},
         fmt::{self, Debug},
         hash,
         marker::PhantomData,
-        mem::ManuallyDrop,
+        mem::{self, ManuallyDrop},
         ops::{Deref, DerefMut},
+        pin::Pin,
         ptr::{self, NonNull},
+        slice::SliceIndex,
     },
 };
 
+mod any;
+mod arena;
+mod assert;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod bitset;
+mod block;
+#[cfg(feature = "bytes")]
+mod buf;
+mod builder;
+mod bytewise;
+mod cast;
+mod cell;
+pub mod codec;
+mod compact;
+mod cow;
+#[cfg(feature = "defmt")]
+mod defmt;
+pub mod diff;
+mod erased;
+mod fixed;
+pub mod graph;
+mod headless;
+mod holder;
+mod incremental;
+mod indirect;
+mod intern;
+#[cfg(feature = "std")]
+mod io;
+mod iter;
+mod jagged;
+mod length;
+mod list;
+mod map;
+mod option;
+mod order;
 mod polyfill;
+mod pool;
+mod prehashed;
+pub mod prelude;
+mod scoped;
+mod search;
+mod send;
+#[cfg(feature = "serde")]
+mod serde;
+mod small;
+mod sorted;
+mod split;
+#[cfg(feature = "async")]
+mod stream;
+mod strtable;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(feature = "trace-alloc")]
+pub mod trace;
+pub mod tree;
+#[cfg(feature = "fuzz")]
+mod validate;
+mod vec;
+#[cfg(all(inline_asm, target_arch = "x86_64", target_feature = "cmpxchg16b"))]
+mod versioned;
+pub mod walk;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "serde")]
+pub use crate::serde::{FlattenHead, ShareNodes};
+#[cfg(feature = "fuzz")]
+pub use validate::ValidateError;
+#[cfg(all(inline_asm, target_arch = "x86_64", target_feature = "cmpxchg16b"))]
+pub use versioned::{Versioned, VersionedThinArcCell};
+pub use {
+    any::HeadAny,
+    arena::{FrozenArena, HeadWithId, ThinArena, ThinId, ThinKey},
+    bitset::{IterOnes, ThinBitSet},
+    block::ThinBlockArena,
+    builder::ThinBoxBuilder,
+    bytewise::{Bytewise, BytewiseEq},
+    cast::Pod,
+    cell::ThinArcCell,
+    compact::CompactDisplay,
+    cow::ThinMapCow,
+    erased::{ErasedVec, ErasedVecIntoIter},
+    fixed::ThinFixed,
+    headless::{ThinSliceArc, ThinSliceBox, ThinSliceRc, TrivialHead},
+    holder::{ThinHolder, ThinPointer},
+    incremental::IncrementalDrop,
+    indirect::{DerefItems, DerefItemsMut},
+    intern::{PendingIntern, ThinInterner},
+    iter::{CollectThin, WithHead},
+    jagged::ThinJagged,
+    length::HeadLength,
+    list::{ThinArcList, ThinArcListIter},
+    map::ThinMap,
+    option::ThinOption,
+    order::{SortByHead, SortByHeadAndSlice},
+    pool::Pool,
+    prehashed::Prehashed,
+    scoped::ThinScopedArena,
+    search::SliceByHead,
+    send::{ThinRcSendGuard, UniquelyOwned},
+    small::SmallThinBox,
+    sorted::ThinSortedSet,
+    split::{ThinBytes, ThinBytesMut},
+    strtable::ThinStrTable,
+    vec::ThinVec,
+};
 
 /// An erased pointer with size and stride of one byte.
 pub type ErasedPtr = NonNull<priv_in_pub::Erased>;
@@ -79,6 +191,34 @@ pub mod priv_in_pub {
 /// the offsets of its public fields are _not public_.
 /// A private field appears before them,
 /// so their offset should be treated as being unknown.
+///
+/// With the `le-header` feature, the private length field is stored
+/// as a little-endian `u64` regardless of the platform's `usize`.
+///
+/// With the `stable-layout` feature, the layout is guaranteed instead:
+/// the length is at offset 0, followed by the head at [`ThinData::HEAD_OFFSET`]
+/// and the slice at [`ThinData::SLICE_OFFSET`], with items
+/// [`ThinData::SLICE_STRIDE`] bytes apart. Code generators can then
+/// walk the slice from [`ThinData::slice_ptr`] without going through Rust slices.
+///
+/// This guarantee holds across crate versions, as identified by [`LAYOUT_VERSION`]:
+/// changing it is a breaking change, so images of `ThinData` written to disk or
+/// shared through memory maps stay readable. The feature implies `le-header`,
+/// so the length is always a little-endian `u64`, and the head and slice are
+/// each placed at the next offset aligned for them.
+///
+/// # Length invariant
+///
+/// The slice is never longer than [`ThinData::MAX_LEN`], which is checked
+/// whenever a `ThinData` is allocated. In particular, the length is never
+/// `usize::MAX`, even for zero-sized slice items.
+///
+/// # Drop order
+///
+/// Like a struct, the head is dropped before the slice,
+/// and the slice items are then dropped in order.
+/// This holds for every holder, so in a tree of nested thin owners,
+/// a node's head is always dropped before its children.
 #[repr(C)]
 #[derive(Debug, Eq, PartialEq, Hash)]
 pub struct ThinData<Head, SliceItem> {
@@ -92,15 +232,246 @@ pub struct ThinData<Head, SliceItem> {
     // We don't do that for now since we can avoid the unsoundness of offset_of!,
     // and offset_of! doesn't work for ?Sized types anyway.
     // SAFETY: must be length of self.slice
-    len: usize,
+    len: Len,
     /// The sized portion of this DST.
     pub head: Head,
     /// The slice portion of this DST.
     pub slice: [SliceItem],
 }
 
+/// The slice length as stored inline in the header.
+///
+/// With the `le-header` feature, this is always a little-endian `u64`,
+/// such that 32-bit and 64-bit platforms agree on the header layout.
+///
+/// With the `debug-checks` feature, the length is preceded by [`LEN_MAGIC`],
+/// so [`ThinData::validate_ptr`] can recognize headers written by this crate.
+/// This is skipped under `stable-layout`, whose layout has no room for it.
+#[cfg(not(feature = "le-header"))]
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+struct Len {
+    #[cfg(all(feature = "debug-checks", not(feature = "stable-layout")))]
+    magic: usize,
+    len: usize,
+}
+
+#[cfg(feature = "le-header")]
+#[repr(C, align(8))]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+struct Len {
+    #[cfg(all(feature = "debug-checks", not(feature = "stable-layout")))]
+    magic: [u8; 8],
+    len: [u8; 8],
+}
+
+/// The magic number stored before the length with the `debug-checks` feature.
+#[cfg(all(feature = "debug-checks", not(feature = "stable-layout")))]
+const LEN_MAGIC: u64 = u64::from_le_bytes(*b"thin-dst");
+
+impl Len {
+    #[cfg(not(feature = "le-header"))]
+    const fn new(len: usize) -> Self {
+        Len {
+            #[cfg(all(feature = "debug-checks", not(feature = "stable-layout")))]
+            magic: LEN_MAGIC as usize,
+            len,
+        }
+    }
+
+    #[cfg(not(feature = "le-header"))]
+    fn try_get(self) -> Option<usize> {
+        Some(self.len)
+    }
+
+    #[cfg(feature = "le-header")]
+    const fn new(len: usize) -> Self {
+        Len {
+            #[cfg(all(feature = "debug-checks", not(feature = "stable-layout")))]
+            magic: LEN_MAGIC.to_le_bytes(),
+            len: (len as u64).to_le_bytes(),
+        }
+    }
+
+    #[cfg(feature = "le-header")]
+    fn try_get(self) -> Option<usize> {
+        let len = u64::from_le_bytes(self.len);
+        if len <= usize::MAX as u64 {
+            Some(len as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the magic number is intact, if there is one.
+    #[cfg(all(feature = "debug-checks", not(feature = "stable-layout")))]
+    fn has_magic(self) -> bool {
+        Len::new(0).magic == self.magic
+    }
+
+    #[cfg(not(all(feature = "debug-checks", not(feature = "stable-layout"))))]
+    fn has_magic(self) -> bool {
+        true
+    }
+
+    fn get(self) -> usize {
+        self.try_get().expect("stored length overflows usize")
+    }
+}
+
+impl Debug for Len {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+/// The version of the `ThinData` layout guaranteed by the `stable-layout` feature.
+///
+/// This only changes in a semver-incompatible release, and lets stored images
+/// record which layout they were written with.
+#[cfg(feature = "stable-layout")]
+pub const LAYOUT_VERSION: u32 = 1;
+
+// The layout promised by `stable-layout`, checked against the actual layout.
+#[cfg(feature = "stable-layout")]
+const _: () = {
+    assert!(mem::size_of::<Len>() == 8 && mem::align_of::<Len>() == 8);
+    assert!(ThinData::<u16, u8>::HEAD_OFFSET == 8);
+    assert!(ThinData::<u16, u8>::SLICE_OFFSET == 10);
+    assert!(ThinData::<u8, u64>::SLICE_OFFSET == 16);
+    assert!(ThinData::<[u64; 2], u32>::SLICE_OFFSET == 24);
+};
+
+/// Round `offset` up to a multiple of `align`, which must be a power of two.
+const fn align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) & !(align - 1)
+}
+
 impl<Head, SliceItem> ThinData<Head, SliceItem> {
-    fn len(ptr: ErasedPtr) -> NonNull<usize> {
+    /// The offset of the `head` field from the start of the `ThinData`.
+    ///
+    /// Unlike the field order, this is computed from the crate's actual layout,
+    /// so `unsafe` projection code and FFI mirrors can assert against it.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// # #[cfg(not(feature = "debug-checks"))]
+    /// const _: () = assert!(ThinData::<u8, u32>::HEAD_OFFSET == std::mem::size_of::<usize>());
+    /// ```
+    pub const HEAD_OFFSET: usize = align_up(mem::size_of::<Len>(), mem::align_of::<Head>());
+
+    /// The offset of the `slice` field from the start of the `ThinData`.
+    ///
+    /// See [`ThinData::HEAD_OFFSET`].
+    pub const SLICE_OFFSET: usize = align_up(
+        Self::HEAD_OFFSET + mem::size_of::<Head>(),
+        mem::align_of::<SliceItem>(),
+    );
+
+    /// The distance in bytes between consecutive slice items.
+    ///
+    /// This is always `size_of::<SliceItem>()`, as Rust sizes are a multiple
+    /// of their alignment, but is spelled out for code generators which lay out
+    /// their loops over the slice from [`ThinData::SLICE_OFFSET`] and this.
+    pub const SLICE_STRIDE: usize = mem::size_of::<SliceItem>();
+
+    /// The longest slice a `ThinData<Head, SliceItem>` can have.
+    ///
+    /// Every constructor checks the length against this, so `slice.len() <= MAX_LEN`
+    /// is an invariant of every `ThinData`. This keeps the allocation within
+    /// `isize::MAX` bytes, and caps even slices of zero-sized items at
+    /// `isize::MAX` items, so `len + 1` never overflows. Check untrusted
+    /// lengths against this before trying to construct anything.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let untrusted_len = usize::MAX / 2;
+    /// assert!(untrusted_len > ThinData::<u32, u64>::MAX_LEN);
+    /// assert!(ThinData::<u32, ()>::MAX_LEN < usize::MAX);
+    /// ```
+    pub const MAX_LEN: usize = {
+        // The largest allocation size which is still a multiple of the alignment.
+        let max_size = isize::MAX as usize - (Self::ALIGN - 1);
+        match max_size
+            .saturating_sub(Self::SLICE_OFFSET)
+            .checked_div(Self::SLICE_STRIDE)
+        {
+            Some(max_len) => max_len,
+            None => isize::MAX as usize,
+        }
+    };
+
+    /// The alignment of the whole `ThinData`.
+    const ALIGN: usize = {
+        let mut align = mem::align_of::<Len>();
+        if mem::align_of::<Head>() > align {
+            align = mem::align_of::<Head>();
+        }
+        if mem::align_of::<SliceItem>() > align {
+            align = mem::align_of::<SliceItem>();
+        }
+        align
+    };
+
+    /// Cheaply check whether `ptr` plausibly points to a `ThinData<Head, SliceItem>`,
+    /// e.g. before passing a pointer received over FFI to `from_erased`.
+    ///
+    /// This checks that `ptr` is aligned for the `ThinData`, and that its stored
+    /// length is at most [`ThinData::MAX_LEN`]. With the `debug-checks` feature,
+    /// it also checks for the magic number which every constructor stores
+    /// before the length (except under `stable-layout`, which has no room for it).
+    ///
+    /// This is only a sanity check: `true` doesn't prove that `ptr` is valid.
+    ///
+    /// # Safety
+    ///
+    /// If `ptr` is aligned for `ThinData<Head, SliceItem>`, it must be valid
+    /// for reads of the length header, i.e. the first [`ThinData::HEAD_OFFSET`] bytes.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let boxed = ThinBox::new(0u32, vec![1u64, 2]);
+    /// let ptr = ThinBox::erase(boxed);
+    /// assert!(unsafe { ThinData::<u32, u64>::validate_ptr(ptr) });
+    /// let misaligned = unsafe { ErasedPtr::new_unchecked(ptr.as_ptr().cast::<u8>().add(1).cast()) };
+    /// assert!(!unsafe { ThinData::<u32, u64>::validate_ptr(misaligned) });
+    /// drop(unsafe { ThinBox::<u32, u64>::from_erased(ptr) });
+    /// ```
+    pub unsafe fn validate_ptr(ptr: ErasedPtr) -> bool {
+        if ptr.as_ptr() as usize & (Self::ALIGN - 1) != 0 {
+            return false;
+        }
+        let len = ptr::read(Self::len(ptr).as_ptr());
+        len.has_magic() && matches!(len.try_get(), Some(len) if len <= Self::MAX_LEN)
+    }
+
+    /// A pointer to the first slice item (or where it would be, if the slice is empty).
+    ///
+    /// Item `i` is [`ThinData::SLICE_STRIDE`]` * i` bytes after it.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let boxed = ThinBox::new(0u8, vec![10u32, 20, 30]);
+    /// let third = unsafe {
+    ///     let base = boxed.slice_ptr().as_ptr().cast::<u8>();
+    ///     *base.add(ThinData::<u8, u32>::SLICE_STRIDE * 2).cast::<u32>()
+    /// };
+    /// assert_eq!(third, 30);
+    /// ```
+    pub fn slice_ptr(&self) -> NonNull<SliceItem> {
+        NonNull::from(&self.slice).cast()
+    }
+
+    /// A pointer to the first slice item of the `ThinData` behind an erased pointer.
+    ///
+    /// This is just `ptr` offset by [`ThinData::SLICE_OFFSET`],
+    /// so it doesn't read the data. The pointer is only valid to use
+    /// if `ptr` points to a live `ThinData<Head, SliceItem>`.
+    pub fn erased_slice_ptr(ptr: ErasedPtr) -> NonNull<SliceItem> {
+        unsafe { NonNull::new_unchecked(ptr.as_ptr().wrapping_add(Self::SLICE_OFFSET).cast()) }
+    }
+
+    fn len(ptr: ErasedPtr) -> NonNull<Len> {
         ptr.cast()
     }
 
@@ -109,77 +480,366 @@ impl<Head, SliceItem> ThinData<Head, SliceItem> {
     }
 
     unsafe fn fatten_const(ptr: ErasedPtr) -> NonNull<Self> {
-        let len = ptr::read(Self::len(ptr).as_ptr());
+        let len = ptr::read(Self::len(ptr).as_ptr()).get();
         let slice = make_slice(ptr.cast::<SliceItem>().as_ptr(), len);
         NonNull::new_unchecked(slice as *const Self as *mut Self)
     }
 
     unsafe fn fatten_mut(ptr: ErasedPtr) -> NonNull<Self> {
-        let len = ptr::read(Self::len(ptr).as_ptr());
+        let len = ptr::read(Self::len(ptr).as_ptr()).get();
         let slice = make_slice_mut(ptr.cast::<SliceItem>().as_ptr(), len);
         NonNull::new_unchecked(slice as *mut Self)
     }
+
+    /// Hint to the processor that the slice is about to be read.
+    ///
+    /// Since the slice is stored directly after the head,
+    /// this lets traversal code start pulling in the items
+    /// while it is still busy looking at the head.
+    ///
+    /// This is purely a performance hint. It issues one prefetch per cache line
+    /// of the slice where the target has a stable prefetch instruction,
+    /// and does nothing otherwise.
+    pub fn prefetch_slice(&self) {
+        let start = self.slice.as_ptr().cast::<u8>();
+        prefetch_read(start, mem::size_of_val(&self.slice));
+    }
+
+    /// The number of leading slice items to skip such that
+    /// `N`-item chunks start at an address aligned to their size.
+    ///
+    /// For chunk sizes that aren't a power of two, this aligns to
+    /// the largest power of two that divides the chunk size.
+    fn chunk_prefix_len<const N: usize>(&self) -> Option<usize> {
+        let item_size = mem::size_of::<SliceItem>();
+        let chunk_size = item_size.checked_mul(N).filter(|&size| size != 0)?;
+        let align = chunk_size & chunk_size.wrapping_neg();
+        let addr = self.slice.as_ptr() as usize;
+        let offset = addr.wrapping_neg() & (align - 1);
+        let prefix_len = offset / item_size;
+        if prefix_len * item_size == offset {
+            Some(cmp::min(prefix_len, self.slice.len()))
+        } else {
+            None
+        }
+    }
+
+    /// Split the slice into a prefix, a run of `N`-item chunks, and a suffix,
+    /// such that each chunk starts at an address aligned to the chunk's size.
+    ///
+    /// This lets SIMD kernels use aligned loads on the chunks without checking
+    /// alignment for each one. If the slice can't be aligned that way
+    /// (e.g. if `N` or `SliceItem` is zero-sized), everything is in the prefix.
+    ///
+    /// Only chunk sizes (in bytes) that are a power of two can stay aligned to
+    /// their own size from one chunk to the next. For other sizes, each chunk
+    /// is instead aligned to the largest power of two that divides the chunk size;
+    /// e.g. 12-byte chunks are 4-byte aligned.
+    pub fn slice_aligned_chunks<const N: usize>(
+        &self,
+    ) -> (&[SliceItem], &[[SliceItem; N]], &[SliceItem]) {
+        let prefix_len = match self.chunk_prefix_len::<N>() {
+            Some(prefix_len) => prefix_len,
+            None => return (&self.slice, &[], &[]),
+        };
+        let (prefix, rest) = self.slice.split_at(prefix_len);
+        let chunks_len = rest.len() / N;
+        let (chunks, suffix) = rest.split_at(chunks_len * N);
+        let chunks = unsafe { &*make_slice(chunks.as_ptr().cast(), chunks_len) };
+        (prefix, chunks, suffix)
+    }
+
+    /// Split the slice into a prefix, a run of `N`-item chunks, and a suffix,
+    /// such that each chunk starts at an address aligned to the chunk's size.
+    ///
+    /// See [`ThinData::slice_aligned_chunks`], including for chunk sizes
+    /// that aren't a power of two.
+    pub fn slice_aligned_chunks_mut<const N: usize>(
+        &mut self,
+    ) -> (&mut [SliceItem], &mut [[SliceItem; N]], &mut [SliceItem]) {
+        let prefix_len = match self.chunk_prefix_len::<N>() {
+            Some(prefix_len) => prefix_len,
+            None => return (&mut self.slice, &mut [], &mut []),
+        };
+        let (prefix, rest) = self.slice.split_at_mut(prefix_len);
+        let chunks_len = rest.len() / N;
+        let (chunks, suffix) = rest.split_at_mut(chunks_len * N);
+        let chunks = unsafe { &mut *make_slice_mut(chunks.as_mut_ptr().cast(), chunks_len) };
+        (prefix, chunks, suffix)
+    }
+
+    /// Project a pinned `ThinData` to its pinned head and its slice.
+    ///
+    /// This is sound because the slice items are `Unpin`,
+    /// so they can be moved out of the pinned data without breaking its pin.
+    ///
+    /// ```rust
+    /// # use {std::{future::Future, pin::Pin}, thin_dst::*};
+    /// let mut boxed = ThinBox::into_pin(ThinBox::new(async { 1 }, vec![0u8; 4]));
+    /// let (head, slice) = boxed.as_mut().project_pin();
+    /// let _: Pin<&mut dyn Future<Output = i32>> = head;
+    /// slice[0] = 1;
+    /// ```
+    pub fn project_pin(self: Pin<&mut Self>) -> (Pin<&mut Head>, &mut [SliceItem])
+    where
+        SliceItem: Unpin,
+    {
+        // SAFETY: the head is pinned structurally, and the slice items are `Unpin`.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.head), &mut this.slice)
+        }
+    }
+
+    /// Project a pinned `ThinData` to its pinned head and each of its pinned slice items.
+    pub fn project_pin_items(
+        self: Pin<&mut Self>,
+    ) -> (Pin<&mut Head>, impl Iterator<Item = Pin<&mut SliceItem>>) {
+        // SAFETY: the head and the slice items are all pinned structurally.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            let items = this.slice.iter_mut().map(|item| Pin::new_unchecked(item));
+            (Pin::new_unchecked(&mut this.head), items)
+        }
+    }
+
+    /// Overwrite slice items from a batch of `(index, item)` updates, sorted by index.
+    ///
+    /// Later updates to the same index win. All of the indices are validated
+    /// in a first pass over a clone of the iterator, before any update is
+    /// applied, so nothing needs to be collected. Cloning the iterator should
+    /// be cheap, as it is for an array or a slice's `iter().cloned()`,
+    /// but not for a `Vec`'s `into_iter()`, which copies the whole batch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of bounds or smaller than the one before it.
+    /// No updates are applied in that case.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let mut column = ThinBox::new("prices", vec![10, 20, 30, 40]);
+    /// column.apply_updates([(1, 21), (3, 41)]);
+    /// assert_eq!(column.slice, [10, 21, 30, 41]);
+    /// ```
+    pub fn apply_updates<I>(&mut self, updates: I)
+    where
+        I: IntoIterator<Item = (usize, SliceItem)>,
+        I::IntoIter: Clone,
+    {
+        self.apply_updates_with(updates, |_, _| ())
+    }
+
+    /// Overwrite slice items from a batch of `(index, item)` updates, sorted by index,
+    /// then recompute the head from the updated slice.
+    ///
+    /// `recompute_head` is called once, after all of the updates,
+    /// e.g. to refresh a summary of the slice kept in the head.
+    /// The updates are validated as for [`apply_updates`](Self::apply_updates).
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of bounds or smaller than the one before it.
+    /// No updates are applied, and the head is not recomputed.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let mut column = ThinBox::new(0, vec![10, 20, 30, 40]);
+    /// column.apply_updates_with([(0, 5), (2, 35)], |sum, items| {
+    ///     *sum = items.iter().sum();
+    /// });
+    /// assert_eq!(column.head, 100);
+    /// ```
+    pub fn apply_updates_with<I, F>(&mut self, updates: I, recompute_head: F)
+    where
+        I: IntoIterator<Item = (usize, SliceItem)>,
+        I::IntoIter: Clone,
+        F: FnOnce(&mut Head, &[SliceItem]),
+    {
+        let updates = updates.into_iter();
+        let len = self.slice.len();
+        let mut last = 0;
+        for (index, _) in updates.clone() {
+            assert!(
+                last <= index && index < len,
+                "update index {} out of order or out of bounds (after {}, len {})",
+                index,
+                last,
+                len,
+            );
+            last = index;
+        }
+        for (index, item) in updates {
+            // The clone checked above isn't trusted to have replayed the same
+            // indices, so this keeps its bounds check, which then never fails.
+            self.slice[index] = item;
+        }
+        recompute_head(&mut self.head, &self.slice);
+    }
 }
 
-impl<SliceItem: PartialEq> PartialEq<[SliceItem]> for ThinData<(), SliceItem> {
+impl<Head: TrivialHead, SliceItem: PartialEq> PartialEq<[SliceItem]> for ThinData<Head, SliceItem> {
     fn eq(&self, other: &[SliceItem]) -> bool {
         &self.slice == other
     }
 }
 
-macro_rules! thin_holder {
-    ( #[nodrop] for $thin:ident<$($a:lifetime,)* Head, SliceItem> as $fat:ident<$($b:lifetime,)* ThinData<Head, SliceItem>> with $fatten:ident ) => {
-        impl<$($a,)* Head, SliceItem> $thin<$($a,)* Head, SliceItem> {
-            /// Construct an owned pointer from an erased pointer.
-            ///
-            /// # Safety
-            ///
-            /// This pointer must logically own a valid instance of `Self`.
-            pub unsafe fn from_erased(ptr: ErasedPtr) -> Self {
-                Self {
-                    raw: ptr,
-                    marker: PhantomData,
-                }
+impl<'b, Head, SliceItem> PartialEq<(Head, &'b [SliceItem])> for ThinData<Head, SliceItem>
+where
+    Head: PartialEq,
+    SliceItem: PartialEq,
+{
+    fn eq(&self, (head, slice): &(Head, &'b [SliceItem])) -> bool {
+        self.head == *head && self.slice == **slice
+    }
+}
+
+impl<Head, SliceItem> PartialEq<(Head, Vec<SliceItem>)> for ThinData<Head, SliceItem>
+where
+    Head: PartialEq,
+    SliceItem: PartialEq,
+{
+    fn eq(&self, (head, slice): &(Head, Vec<SliceItem>)) -> bool {
+        self.head == *head && self.slice == **slice
+    }
+}
+
+/// Implement `Debug`, `Eq`, and `Hash` by borrowing the `ThinData` directly,
+/// without reconstructing the fat pointer.
+macro_rules! data_traits {
+    ($($thin:ident$(<$a:lifetime>)? as $fat:ident$(<$b:lifetime>)?),* $(,)?) => {$(
+        impl<$($a,)? Head, SliceItem> Debug for $thin<$($a,)? Head, SliceItem>
+        where
+            ThinData<Head, SliceItem>: Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                <ThinData<Head, SliceItem> as Debug>::fmt(self, f)
             }
+        }
 
-            /// Convert this owned pointer into an erased pointer.
-            ///
-            /// To avoid a memory leak the pointer must be converted back
-            /// using `Self::from_erased`.
-            pub fn erase(this: Self) -> ErasedPtr {
-                let this = ManuallyDrop::new(this);
-                this.raw
+        impl<$($a,)? Head, SliceItem> cmp::Eq for $thin<$($a,)? Head, SliceItem> where
+            ThinData<Head, SliceItem>: cmp::Eq,
+        {
+        }
+        impl<$($a,)? Head, SliceItem> PartialEq for $thin<$($a,)? Head, SliceItem>
+        where
+            ThinData<Head, SliceItem>: PartialEq,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                **self == **other
+            }
+        }
+        impl<$($a,)? Head, SliceItem> PartialEq<$fat<$($b,)? ThinData<Head, SliceItem>>> for $thin<$($a,)? Head, SliceItem>
+        where
+            ThinData<Head, SliceItem>: PartialEq,
+        {
+            fn eq(&self, other: &$fat<$($b,)? ThinData<Head, SliceItem>>) -> bool {
+                **self == **other
             }
         }
 
-        impl<$($a,)* Head, SliceItem> From<$fat<$($b,)* ThinData<Head, SliceItem>>> for $thin<$($a,)* Head, SliceItem> {
-            fn from(this: $fat<$($b,)* ThinData<Head, SliceItem>>) -> $thin<$($a,)* Head, SliceItem> {
-                unsafe {
-                    let this = NonNull::new_unchecked($fat::into_raw(this) as *mut _);
-                    Self::from_erased(ThinData::<Head, SliceItem>::erase(this))
-                }
+        impl<$($a,)? Head, SliceItem> hash::Hash for $thin<$($a,)? Head, SliceItem>
+        where
+            ThinData<Head, SliceItem>: hash::Hash,
+        {
+            fn hash<H>(&self, state: &mut H)
+            where
+                H: hash::Hasher,
+            {
+                (**self).hash(state)
             }
         }
+    )*};
+}
 
-        impl<$($a,)* Head, SliceItem> Deref for $thin<$($a,)* Head, SliceItem>
+data_traits!(
+    ThinBox as Box,
+    ThinArc as Arc,
+    ThinRc as Rc,
+    ThinRef<'a> as Ref<'a>,
+    ThinRefMut<'a> as Ref<'a>,
+);
+
+macro_rules! tuple_eq {
+    ($($thin:ident$(<$a:lifetime>)?),* $(,)?) => {$(
+        impl<'b, $($a,)? Head, SliceItem> PartialEq<(Head, &'b [SliceItem])> for $thin<$($a,)? Head, SliceItem>
         where
-            $fat<$($b,)* ThinData<Head, SliceItem>>: Deref,
+            Head: PartialEq,
+            SliceItem: PartialEq,
         {
-            type Target = ThinData<Head, SliceItem>;
-            fn deref(&self) -> &ThinData<Head, SliceItem> {
-                unsafe { &*ThinData::fatten_const(self.raw).as_ptr() }
+            fn eq(&self, other: &(Head, &'b [SliceItem])) -> bool {
+                **self == *other
             }
         }
 
-        impl<$($a,)* Head, SliceItem> DerefMut for $thin<$($a,)* Head, SliceItem>
+        impl<$($a,)? Head, SliceItem> PartialEq<(Head, Vec<SliceItem>)> for $thin<$($a,)? Head, SliceItem>
         where
-            $fat<$($b,)* ThinData<Head, SliceItem>>: DerefMut,
+            Head: PartialEq,
+            SliceItem: PartialEq,
         {
-            fn deref_mut(&mut self) -> &mut ThinData<Head, SliceItem> {
-                unsafe { &mut *ThinData::fatten_mut(self.raw).as_ptr() }
+            fn eq(&self, other: &(Head, Vec<SliceItem>)) -> bool {
+                **self == *other
             }
         }
+    )*};
+}
+
+tuple_eq!(ThinBox, ThinArc, ThinRc, ThinRef<'a>, ThinRefMut<'a>);
 
+macro_rules! slice_access {
+    ($($thin:ident),* $(,)?) => {$(
+        impl<Head, SliceItem> $thin<Head, SliceItem> {
+            /// Borrow the head.
+            pub fn head_ref(this: &Self) -> &Head {
+                &this.head
+            }
+
+            /// Borrow the head and read the slice length, without touching the slice.
+            ///
+            /// This only reads the header, and doesn't create a reference to
+            /// the slice memory, so it is cheap for traversals that only
+            /// inspect heads, and doesn't invalidate raw pointers into the slice.
+            pub fn head_with_len(this: &Self) -> (&Head, usize) {
+                unsafe {
+                    let len = ptr::read(ThinData::<Head, SliceItem>::len(this.raw).as_ptr()).get();
+                    let head = this
+                        .raw
+                        .as_ptr()
+                        .add(ThinData::<Head, SliceItem>::HEAD_OFFSET)
+                        .cast::<Head>();
+                    (&*head, len)
+                }
+            }
+
+            /// Borrow a subslice of the slice.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the range is out of bounds.
+            pub fn slice_ref<R>(this: &Self, range: R) -> &[SliceItem]
+            where
+                R: SliceIndex<[SliceItem], Output = [SliceItem]>,
+            {
+                &this.slice[range]
+            }
+
+            /// Borrow a subslice of the slice, or `None` if the range is out of bounds.
+            pub fn get<R>(this: &Self, range: R) -> Option<&[SliceItem]>
+            where
+                R: SliceIndex<[SliceItem], Output = [SliceItem]>,
+            {
+                this.slice.get(range)
+            }
+        }
+    )*};
+}
+
+slice_access!(ThinBox, ThinArc, ThinRc);
+
+/// Forward `Debug`, `Eq`, and `Hash` to the fat pointer, for holders whose
+/// fat pointer doesn't forward them to the pointee, so can't go through `ThinData`.
+macro_rules! fat_traits {
+    ( for $thin:ident<$($a:lifetime,)* Head, SliceItem> as $fat:ident<$($b:lifetime,)* ThinData<Head, SliceItem>> ) => {
         impl<$($a,)* Head, SliceItem> Debug for $thin<$($a,)* Head, SliceItem>
         where
             $fat<$($b,)* ThinData<Head, SliceItem>>: Debug,
@@ -192,15 +852,6 @@ macro_rules! thin_holder {
             }
         }
 
-        unsafe impl<$($a,)* Head, SliceItem> Send for $thin<$($a,)* Head, SliceItem> where
-            $fat<$($b,)* ThinData<Head, SliceItem>>: Send
-        {
-        }
-        unsafe impl<$($a,)* Head, SliceItem> Sync for $thin<$($a,)* Head, SliceItem> where
-            $fat<$($b,)* ThinData<Head, SliceItem>>: Sync
-        {
-        }
-
         impl<$($a,)* Head, SliceItem> cmp::Eq for $thin<$($a,)* Head, SliceItem> where
             $fat<$($b,)* ThinData<Head, SliceItem>>: cmp::Eq,
         {
@@ -243,11 +894,96 @@ macro_rules! thin_holder {
             }
         }
     };
+}
+
+macro_rules! thin_holder {
+    ( #[nodrop] for $thin:ident<$($a:lifetime,)* Head, SliceItem> as $fat:ident<$($b:lifetime,)* ThinData<Head, SliceItem>> with $fatten:ident ) => {
+        impl<$($a,)* Head, SliceItem> $thin<$($a,)* Head, SliceItem> {
+            /// Construct an owned pointer from an erased pointer.
+            ///
+            /// # Safety
+            ///
+            /// This pointer must logically own a valid instance of `Self`.
+            pub unsafe fn from_erased(ptr: ErasedPtr) -> Self {
+                Self {
+                    raw: ptr,
+                    marker: PhantomData,
+                }
+            }
+
+            /// Convert this owned pointer into an erased pointer.
+            ///
+            /// To avoid a memory leak the pointer must be converted back
+            /// using `Self::from_erased`.
+            pub fn erase(this: Self) -> ErasedPtr {
+                let this = ManuallyDrop::new(this);
+                this.raw
+            }
+        }
+
+        impl<$($a,)* Head, SliceItem> From<$fat<$($b,)* ThinData<Head, SliceItem>>> for $thin<$($a,)* Head, SliceItem> {
+            fn from(this: $fat<$($b,)* ThinData<Head, SliceItem>>) -> $thin<$($a,)* Head, SliceItem> {
+                #[cfg(all(feature = "trace-alloc", debug_assertions))]
+                trace::Traced::record(&this);
+                unsafe {
+                    let this = NonNull::new_unchecked($fat::into_raw(this) as *mut _);
+                    Self::from_erased(ThinData::<Head, SliceItem>::erase(this))
+                }
+            }
+        }
+
+        impl<$($a,)* Head, SliceItem> Deref for $thin<$($a,)* Head, SliceItem>
+        where
+            $fat<$($b,)* ThinData<Head, SliceItem>>: Deref,
+        {
+            type Target = ThinData<Head, SliceItem>;
+            fn deref(&self) -> &ThinData<Head, SliceItem> {
+                unsafe { &*ThinData::fatten_const(self.raw).as_ptr() }
+            }
+        }
+
+        impl<$($a,)* Head, SliceItem> DerefMut for $thin<$($a,)* Head, SliceItem>
+        where
+            $fat<$($b,)* ThinData<Head, SliceItem>>: DerefMut,
+        {
+            fn deref_mut(&mut self) -> &mut ThinData<Head, SliceItem> {
+                unsafe { &mut *ThinData::fatten_mut(self.raw).as_ptr() }
+            }
+        }
+
+        unsafe impl<$($a,)* Head, SliceItem> Send for $thin<$($a,)* Head, SliceItem> where
+            $fat<$($b,)* ThinData<Head, SliceItem>>: Send
+        {
+        }
+        unsafe impl<$($a,)* Head, SliceItem> Sync for $thin<$($a,)* Head, SliceItem> where
+            $fat<$($b,)* ThinData<Head, SliceItem>>: Sync
+        {
+        }
+    };
     ( for $thin:ident<$($a:lifetime,)* Head, SliceItem> as $fat:ident<$($b:lifetime,)* ThinData<Head, SliceItem>> with $fatten:ident ) => {
+        #[cfg(not(thin_dst_nightly))]
         impl<$($a,)* Head, SliceItem> Drop for $thin<$($a,)* Head, SliceItem> {
             fn drop(&mut self) {
-                let this = unsafe { $fat::from_raw(ThinData::$fatten(self.raw).as_ptr()) };
-                drop::<$fat<$($b,)* ThinData<Head, SliceItem>>>(this)
+                let this: $fat<$($b,)* ThinData<Head, SliceItem>> =
+                    unsafe { $fat::from_raw(ThinData::$fatten(self.raw).as_ptr()) };
+                #[cfg(all(feature = "trace-alloc", debug_assertions))]
+                trace::Traced::release(this);
+                #[cfg(not(all(feature = "trace-alloc", debug_assertions)))]
+                drop(this)
+            }
+        }
+
+        // SAFETY: like the standard holders, this only drops the head and slice,
+        // which the marker declares ownership of.
+        #[cfg(thin_dst_nightly)]
+        unsafe impl<$($a,)* #[may_dangle] Head, #[may_dangle] SliceItem> Drop for $thin<$($a,)* Head, SliceItem> {
+            fn drop(&mut self) {
+                let this: $fat<$($b,)* ThinData<Head, SliceItem>> =
+                    unsafe { $fat::from_raw(ThinData::$fatten(self.raw).as_ptr()) };
+                #[cfg(all(feature = "trace-alloc", debug_assertions))]
+                trace::Traced::release(this);
+                #[cfg(not(all(feature = "trace-alloc", debug_assertions)))]
+                drop(this)
             }
         }
 
@@ -257,6 +993,9 @@ macro_rules! thin_holder {
 
 /// A thin version of [`Box`].
 ///
+/// The allocation always has exactly the layout of its `ThinData`:
+/// there is no spare capacity, so there is nothing to shrink.
+///
 ///   [`Box`]: <https://doc.rust-lang.org/stable/std/boxed/struct.Box.html>
 pub struct ThinBox<Head, SliceItem> {
     raw: ErasedPtr,
@@ -266,110 +1005,774 @@ pub struct ThinBox<Head, SliceItem> {
 thin_holder!(for ThinBox<Head, SliceItem> as Box<ThinData<Head, SliceItem>> with fatten_mut);
 
 impl<Head, SliceItem> ThinBox<Head, SliceItem> {
-    fn layout(len: usize) -> Result<(Layout, [usize; 3]), LayoutErr> {
-        let length_layout = Layout::new::<usize>();
+    fn layout(len: usize) -> Result<(Layout, [usize; 3]), LayoutError> {
+        if len > ThinData::<Head, SliceItem>::MAX_LEN {
+            // Only reachable for zero-sized items; otherwise the layout itself overflows.
+            return Err(layout_array::<u8>(usize::MAX).unwrap_err());
+        }
+        let length_layout = Layout::new::<Len>();
         let head_layout = Layout::new::<Head>();
         let slice_layout = layout_array::<SliceItem>(len)?;
         repr_c_3([length_layout, head_layout, slice_layout])
     }
 
-    unsafe fn alloc(len: usize, layout: Layout) -> NonNull<ThinData<Head, SliceItem>> {
-        let ptr: ErasedPtr = NonNull::new(alloc(layout))
-            .unwrap_or_else(|| handle_alloc_error(layout))
-            .cast();
-        ptr::write(ThinData::<Head, SliceItem>::len(ptr).as_ptr(), len);
-        ThinData::fatten_mut(ptr.cast())
+    /// Create a new boxed `ThinData` with the given head and slice.
+    ///
+    /// If the slice iterator panics, the items written so far are dropped,
+    /// the allocation is freed, and the head is dropped while unwinding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    pub fn new<I>(head: Head, slice: I) -> Self
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let items = slice.into_iter();
+        let len = items.len();
+        InProgress::new(len).fill(head, items)
+    }
+
+    /// Create a new boxed `ThinData` with the given head and `len` slice items.
+    ///
+    /// This is for iterators which yield a known number of items,
+    /// but don't implement `ExactSizeIterator`, such as many adapters.
+    /// The items are still written directly into the final allocation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator doesn't yield exactly `len` items.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let evens = (0..).step_by(2).take_while(|&i| i < 10);
+    /// let boxed = ThinBox::new_with_len((), 5, evens);
+    /// assert_eq!(boxed.slice, [0, 2, 4, 6, 8]);
+    /// ```
+    pub fn new_with_len<I>(head: Head, len: usize, slice: I) -> Self
+    where
+        I: IntoIterator<Item = SliceItem>,
+    {
+        InProgress::new(len).fill(head, slice.into_iter())
+    }
+
+    /// Create a new boxed `ThinData` with the given head and slice,
+    /// returning an error instead of aborting if allocation fails.
+    ///
+    /// On error, the head and slice are dropped without consuming the iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    pub fn try_new<I>(head: Head, slice: I) -> Result<Self, TryAllocError>
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let items = slice.into_iter();
+        let len = items.len();
+        Ok(InProgress::try_new(len)?.fill(head, items))
+    }
+
+    /// Create a new boxed `ThinData` with the given head and
+    /// the items of `slice` that match `predicate`.
+    ///
+    /// This allocates for every item of `slice`, then shrinks the allocation
+    /// to fit the items kept, rather than collecting them into a `Vec` first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator under-reports its length.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let evens = ThinBox::from_iter_filtered((), 0..10, |i| i % 2 == 0);
+    /// assert_eq!(evens.slice, [0, 2, 4, 6, 8]);
+    /// ```
+    pub fn from_iter_filtered<I, P>(head: Head, slice: I, mut predicate: P) -> Self
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+        P: FnMut(&SliceItem) -> bool,
+    {
+        let items = slice.into_iter();
+        let mut this = InProgress::new(items.len());
+        for slice_item in items {
+            if predicate(&slice_item) {
+                assert!(
+                    this.written_len < this.len,
+                    "ExactSizeIterator under-reported length"
+                );
+                unsafe { this.push(slice_item) };
+            }
+        }
+        this.shrink_to_written();
+        unsafe { this.finish(head) }
+    }
+
+    /// Create a new boxed `ThinData` with the given head and slice,
+    /// writing the head into the allocation before the slice.
+    ///
+    /// This means the head doesn't need to be kept on the stack while filling the slice,
+    /// and that if the slice iterator panics, the head is dropped in place
+    /// alongside the slice items written so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    pub fn new_head_first<I>(head: Head, slice: I) -> Self
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let items = slice.into_iter();
+        let mut this = InProgress::new(items.len());
+        this.write_head(head);
+        this.push_all(items);
+        unsafe { this.into_box() }
+    }
+
+    /// Create a new boxed `ThinData` with the given head and `len` slice items,
+    /// each computed from its index and the items before it.
+    ///
+    /// The items are written directly into the final allocation,
+    /// so cumulative structures such as prefix sums can be built in one pass.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let prefix_sums = ThinBox::new_scan((), 5, |prefix, i| prefix.last().unwrap_or(&0) + i);
+    /// assert_eq!(prefix_sums.slice, [0, 1, 3, 6, 10]);
+    /// ```
+    pub fn new_scan<F>(head: Head, len: usize, mut make_item: F) -> Self
+    where
+        F: FnMut(&[SliceItem], usize) -> SliceItem,
+    {
+        let mut this = InProgress::new(len);
+        for index in 0..len {
+            let slice_item = make_item(this.written(), index);
+            unsafe { this.push(slice_item) };
+        }
+        unsafe { this.finish(head) }
+    }
+
+    /// Create a new boxed `ThinData` with the given head and a slice
+    /// combining `a` and `b` element-wise with `f`.
+    ///
+    /// The items are written directly into the final allocation,
+    /// without collecting them into a `Vec` first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` differ in length.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let a = ThinBox::new("a", vec![1.0, 2.0, 3.0]);
+    /// let b = ThinBox::new("b", vec![0.5, 0.5, 2.0]);
+    /// let product = ThinBox::zip_with("a * b", &a.slice, &b.slice, |x, y| x * y);
+    /// assert_eq!(product.slice, [0.5, 1.0, 6.0]);
+    /// ```
+    pub fn zip_with<A, B, F>(head: Head, a: &[A], b: &[B], mut f: F) -> Self
+    where
+        F: FnMut(&A, &B) -> SliceItem,
+    {
+        assert_eq!(a.len(), b.len(), "zipped slices differ in length");
+        let mut this = InProgress::new(a.len());
+        for (a, b) in a.iter().zip(b) {
+            let slice_item = f(a, b);
+            unsafe { this.push(slice_item) };
+        }
+        unsafe { this.finish(head) }
+    }
+
+    /// Create a new boxed `ThinData` with the given slice,
+    /// then compute the head from the slice in place.
+    ///
+    /// If computing the head fails (or panics), the slice items are dropped
+    /// and the allocation is freed before the error is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let sum = |slice: &[u32]| slice.iter().try_fold(0u32, |sum, &x| sum.checked_add(x));
+    /// let node = ThinBox::new_with_head_result(vec![1, 2, 3], |slice| sum(slice).ok_or(()));
+    /// assert_eq!(node.unwrap().head, 6);
+    /// let node = ThinBox::new_with_head_result(vec![1, u32::MAX], |slice| sum(slice).ok_or(()));
+    /// assert!(node.is_err());
+    /// ```
+    pub fn new_with_head_result<I, F, E>(slice: I, make_head: F) -> Result<Self, E>
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+        F: FnOnce(&[SliceItem]) -> Result<Head, E>,
+    {
+        let items = slice.into_iter();
+        let mut this = InProgress::new(items.len());
+        this.push_all(items);
+        let head = make_head(this.written())?;
+        Ok(unsafe { this.finish(head) })
+    }
+
+    /// Replace the head with one of another type, reusing the allocation.
+    ///
+    /// The slice is left in place, rather than being copied into a new allocation.
+    /// It is a compile-time error to use this with heads of differing size or alignment.
+    pub fn retype_head<Head2, F>(this: Self, f: F) -> ThinBox<Head2, SliceItem>
+    where
+        F: FnOnce(Head) -> Head2,
+    {
+        let () = SameLayout::<Head, Head2>::ASSERT;
+        let len = this.slice.len();
+        let (_, [_, head_offset, _]) = InProgress::<Head, SliceItem>::layout(len);
+        let raw = ThinBox::erase(this);
+        unsafe {
+            let head = ptr::read(raw.as_ptr().add(head_offset).cast::<Head>());
+            // From here on, the allocation is a ThinBox<Head2, _> that's just missing its head.
+            let mut this = InProgress::<Head2, SliceItem>::from_alloc(len, raw.cast());
+            this.written_len = len;
+            this.write_head(f(head));
+            this.into_box()
+        }
+    }
+
+    /// Convert each slice item to another type of the same layout, reusing the allocation.
+    ///
+    /// If `f` panics, the items already converted, the items not yet converted,
+    /// and the head are all dropped, and the allocation is freed.
+    /// It is a compile-time error to use this with items of differing size or alignment.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let ints = ThinBox::new("scaled", vec![1u32, 2, 3]);
+    /// let floats = ThinBox::map_items_in_place(ints, |x| x as f32 * 0.5);
+    /// assert_eq!(floats.slice, [0.5, 1.0, 1.5]);
+    /// ```
+    pub fn map_items_in_place<U, F>(this: Self, mut f: F) -> ThinBox<Head, U>
+    where
+        F: FnMut(SliceItem) -> U,
+    {
+        match Self::try_map_items_in_place(this, |item| Ok::<_, core::convert::Infallible>(f(item)))
+        {
+            Ok(this) => this,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Fallibly convert each slice item to another type of the same layout, reusing the allocation.
+    ///
+    /// On the first error, the items already converted, the items not yet converted,
+    /// and the head are all dropped, the allocation is freed, and the error is returned.
+    /// See [`ThinBox::map_items_in_place`].
+    pub fn try_map_items_in_place<U, E, F>(this: Self, mut f: F) -> Result<ThinBox<Head, U>, E>
+    where
+        F: FnMut(SliceItem) -> Result<U, E>,
+    {
+        let () = SameLayout::<SliceItem, U>::ASSERT;
+        let len = this.slice.len();
+        let layout = Layout::for_value::<ThinData<Head, SliceItem>>(&this);
+        let raw = ThinBox::erase(this);
+        let mut guard = MapInPlace::<Head, SliceItem, U> {
+            raw,
+            len,
+            mapped: 0,
+            unmapped: 0,
+            layout,
+            marker: PhantomData,
+        };
+        let items = unsafe { raw.as_ptr().add(ThinData::<Head, SliceItem>::SLICE_OFFSET) };
+        for i in 0..len {
+            unsafe {
+                let item = ptr::read(items.cast::<SliceItem>().add(i));
+                guard.unmapped = i + 1;
+                ptr::write(items.cast::<U>().add(i), f(item)?);
+                guard.mapped = i + 1;
+            }
+        }
+        mem::forget(guard);
+        Ok(unsafe { ThinBox::from_erased(raw) })
+    }
+
+    /// Pin the box, like [`Box::into_pin`].
+    ///
+    /// The data is never moved out of the allocation while it is pinned,
+    /// and can be projected with [`ThinData::project_pin`].
+    pub fn into_pin(this: Self) -> Pin<Self> {
+        // SAFETY: the data lives in its own allocation, which is freed in place.
+        unsafe { Pin::new_unchecked(this) }
+    }
+
+    /// Adopt a boxed `ThinData` in place, checking its inline header first.
+    ///
+    /// Converting with `From` trusts that the length stored inline agrees with
+    /// the box's slice length, as `ThinBox` only has the inline one to go by.
+    /// This instead checks that they agree (and with the `debug-checks` feature,
+    /// that the header's magic number is intact), returning the box back if not,
+    /// such as for a `ThinData` assembled by hand through pointer casts.
+    ///
+    /// Nothing else about the box can be checked. Its size, alignment, and field
+    /// offsets are those of this build's `ThinData<Head, SliceItem>` by construction,
+    /// and like any `Box`, it must come from the global allocator. A box from
+    /// another build of this crate, or from another allocator, is not detected.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let fat: Box<ThinData<u8, u16>> = ThinBox::new(1, vec![2, 3]).into();
+    /// let thin = ThinBox::from_box_thin_data(fat).unwrap();
+    /// assert_eq!(thin.slice, [2, 3]);
+    /// ```
+    pub fn from_box_thin_data(
+        boxed: Box<ThinData<Head, SliceItem>>,
+    ) -> Result<Self, Box<ThinData<Head, SliceItem>>> {
+        if boxed.len.has_magic() && boxed.len.try_get() == Some(boxed.slice.len()) {
+            Ok(boxed.into())
+        } else {
+            Err(boxed)
+        }
+    }
+
+    /// Move a `ThinData` into a new box with a bitwise copy.
+    ///
+    /// # Safety
+    ///
+    /// `data` must not be used or dropped afterwards.
+    unsafe fn read_from(data: &ThinData<Head, SliceItem>) -> Self {
+        let len = data.slice.len();
+        let mut this = InProgress::new(len);
+        ptr::copy_nonoverlapping(
+            data.slice.as_ptr(),
+            this.raw.as_ptr().add(this.slice_offset).cast(),
+            len,
+        );
+        this.written_len = len;
+        this.finish(ptr::read(&data.head))
+    }
+
+    /// Move the head and slice out of the box, freeing the allocation.
+    pub fn into_parts(this: Self) -> (Head, Vec<SliceItem>) {
+        let len = this.slice.len();
+        let layout = Layout::for_value::<ThinData<Head, SliceItem>>(&this);
+        let mut slice = Vec::with_capacity(len);
+        let raw = ThinBox::erase(this).as_ptr();
+        unsafe {
+            let head = ptr::read(raw.add(ThinData::<Head, SliceItem>::HEAD_OFFSET).cast());
+            ptr::copy_nonoverlapping(
+                raw.add(ThinData::<Head, SliceItem>::SLICE_OFFSET).cast(),
+                slice.as_mut_ptr(),
+                len,
+            );
+            slice.set_len(len);
+            dealloc(raw.cast(), layout);
+            (head, slice)
+        }
+    }
+
+    /// Create many new boxed `ThinData` from pairs of head and slice.
+    ///
+    /// Each box is still its own allocation, as every `ThinBox` can be freed individually.
+    /// To amortize allocator calls across repeated bulk construction,
+    /// use [`Pool::new_many`] with a pool stocked by recycling previous boxes.
+    /// To place every node in one allocation, freed all at once,
+    /// use [`ThinBlockArena::new_many`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any slice iterator incorrectly reports its length.
+    pub fn new_many<I, S>(nodes: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = (Head, S)>,
+        S: IntoIterator<Item = SliceItem>,
+        S::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        nodes
+            .into_iter()
+            .map(|(head, slice)| ThinBox::new(head, slice))
+            .collect()
+    }
+}
+
+/// A `ThinBox` whose slice is being converted from `T` to `U` in place,
+/// which cleans up after itself if dropped.
+struct MapInPlace<Head, T, U> {
+    raw: ErasedPtr,
+    len: usize,
+    /// The items before this have been converted to `U`.
+    mapped: usize,
+    /// The items from this on are still `T`.
+    unmapped: usize,
+    layout: Layout,
+    marker: PhantomData<(Head, T, U)>,
+}
+
+impl<Head, T, U> Drop for MapInPlace<Head, T, U> {
+    fn drop(&mut self) {
+        let raw_ptr = self.raw.as_ptr();
+        unsafe {
+            let items = raw_ptr.add(ThinData::<Head, T>::SLICE_OFFSET);
+            ptr::drop_in_place(make_slice_mut(items.cast::<U>(), self.mapped));
+            ptr::drop_in_place(make_slice_mut(
+                items.cast::<T>().add(self.unmapped),
+                self.len - self.unmapped,
+            ));
+            ptr::drop_in_place(raw_ptr.add(ThinData::<Head, T>::HEAD_OFFSET).cast::<Head>());
+            dealloc(raw_ptr.cast(), self.layout);
+        }
+    }
+}
+
+struct SameLayout<A, B>(PhantomData<(A, B)>);
+impl<A, B> SameLayout<A, B> {
+    const ASSERT: () = assert!(
+        mem::size_of::<A>() == mem::size_of::<B>() && mem::align_of::<A>() == mem::align_of::<B>(),
+        "types must have the same size and alignment",
+    );
+}
+
+/// The error returned when fallibly allocating a thin holder fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TryAllocError {
+    /// The layout of the requested `ThinData` overflows `isize::MAX` bytes.
+    CapacityOverflow,
+    /// The allocator returned an error.
+    AllocError {
+        /// The layout that failed to allocate.
+        layout: Layout,
+    },
+}
+
+impl fmt::Display for TryAllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryAllocError::CapacityOverflow => f.write_str("capacity overflow"),
+            TryAllocError::AllocError { .. } => f.write_str("memory allocation failed"),
+        }
+    }
+}
+
+/// A partially initialized `ThinBox`, which cleans up after itself if dropped.
+struct InProgress<Head, SliceItem> {
+    raw: ErasedPtr,
+    len: usize,
+    written_len: usize,
+    head_written: bool,
+    /// Whether to free the allocation if dropped, or leave it to its owner.
+    owned: bool,
+    layout: Layout,
+    head_offset: usize,
+    slice_offset: usize,
+    marker: PhantomData<ThinBox<Head, SliceItem>>,
+}
+
+// SAFETY: `InProgress` owns its head and slice items, like the `ThinBox` it will become.
+unsafe impl<Head: Send, SliceItem: Send> Send for InProgress<Head, SliceItem> {}
+unsafe impl<Head: Sync, SliceItem: Sync> Sync for InProgress<Head, SliceItem> {}
+
+impl<Head, SliceItem> Drop for InProgress<Head, SliceItem> {
+    fn drop(&mut self) {
+        let raw_ptr = self.raw.as_ptr();
+        unsafe {
+            let slice = make_slice_mut(
+                raw_ptr.add(self.slice_offset).cast::<SliceItem>(),
+                self.written_len,
+            );
+            ptr::drop_in_place(slice);
+            if self.head_written {
+                ptr::drop_in_place(raw_ptr.add(self.head_offset).cast::<Head>());
+            }
+            if self.owned {
+                dealloc(raw_ptr.cast(), self.layout);
+            }
+        }
+    }
+}
+
+impl<Head, SliceItem> InProgress<Head, SliceItem> {
+    fn layout(len: usize) -> (Layout, [usize; 3]) {
+        ThinBox::<Head, SliceItem>::layout(len).unwrap_or_else(|e| panic!("oversize box: {}", e))
+    }
+
+    fn new(len: usize) -> Self {
+        let (layout, _) = Self::layout(len);
+        let ptr =
+            unsafe { NonNull::new(alloc(layout)).unwrap_or_else(|| handle_alloc_error(layout)) };
+        unsafe { Self::from_alloc(len, ptr) }
+    }
+
+    /// Allocate with the slice zeroed, such that it is already fully written.
+    ///
+    /// # Safety
+    ///
+    /// All-zero bytes must be a valid `SliceItem`.
+    unsafe fn new_zeroed(len: usize) -> Self {
+        let (layout, _) = Self::layout(len);
+        let ptr = NonNull::new(alloc_zeroed(layout)).unwrap_or_else(|| handle_alloc_error(layout));
+        let mut this = Self::from_alloc(len, ptr);
+        this.written_len = len;
+        this
+    }
+
+    fn try_new(len: usize) -> Result<Self, TryAllocError> {
+        let (layout, _) =
+            ThinBox::<Head, SliceItem>::layout(len).map_err(|_| TryAllocError::CapacityOverflow)?;
+        let ptr =
+            unsafe { NonNull::new(alloc(layout)) }.ok_or(TryAllocError::AllocError { layout })?;
+        Ok(unsafe { Self::from_alloc(len, ptr) })
+    }
+
+    /// Take over an existing allocation.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be allocated by the global allocator with `Self::layout(len)`.
+    unsafe fn from_alloc(len: usize, ptr: NonNull<u8>) -> Self {
+        let (layout, [_, head_offset, slice_offset]) = Self::layout(len);
+        debug_assert_eq!(head_offset, ThinData::<Head, SliceItem>::HEAD_OFFSET);
+        debug_assert_eq!(slice_offset, ThinData::<Head, SliceItem>::SLICE_OFFSET);
+        let raw: ErasedPtr = ptr.cast();
+        ptr::write(
+            ThinData::<Head, SliceItem>::len(raw).as_ptr(),
+            Len::new(len),
+        );
+        InProgress {
+            raw,
+            len,
+            written_len: 0,
+            head_written: false,
+            owned: true,
+            layout,
+            head_offset,
+            slice_offset,
+            marker: PhantomData,
+        }
+    }
+
+    /// Build in memory owned by someone else, such as an arena chunk,
+    /// which is left allocated if the build is abandoned.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes of `Self::layout(len)` and aligned to it,
+    /// for as long as the built `ThinData` is used.
+    unsafe fn in_place(len: usize, ptr: NonNull<u8>) -> Self {
+        let mut this = Self::from_alloc(len, ptr);
+        this.owned = false;
+        this
+    }
+
+    unsafe fn push(&mut self, item: SliceItem) {
+        self.raw
+            .as_ptr()
+            .add(self.slice_offset)
+            .cast::<SliceItem>()
+            .add(self.written_len)
+            .write(item);
+        self.written_len += 1;
+    }
+
+    /// Write a run of slice items with a single copy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there isn't room for all of `items`.
+    #[cfg(feature = "bytes")]
+    fn push_slice(&mut self, items: &[SliceItem])
+    where
+        SliceItem: Copy,
+    {
+        assert!(items.len() <= self.len - self.written_len);
+        unsafe {
+            let dst = self
+                .raw
+                .as_ptr()
+                .add(self.slice_offset)
+                .cast::<SliceItem>()
+                .add(self.written_len);
+            ptr::copy_nonoverlapping(items.as_ptr(), dst, items.len());
+        }
+        self.written_len += items.len();
+    }
+
+    fn written(&self) -> &[SliceItem] {
+        unsafe {
+            &*make_slice(
+                self.raw.as_ptr().add(self.slice_offset).cast::<SliceItem>(),
+                self.written_len,
+            )
+        }
+    }
+
+    fn write_head(&mut self, head: Head) {
+        debug_assert!(!self.head_written);
+        unsafe { ptr::write(self.raw.as_ptr().add(self.head_offset).cast(), head) };
+        self.head_written = true;
     }
 
-    /// Create a new boxed `ThinData` with the given head and slice.
+    /// Write all remaining slice items.
     ///
     /// # Panics
     ///
-    /// Panics if the slice iterator incorrectly reports its length.
-    pub fn new<I>(head: Head, slice: I) -> Self
+    /// Panics if `items` does not yield exactly the remaining number of items.
+    fn push_all<I>(&mut self, mut items: I)
     where
-        I: IntoIterator<Item = SliceItem>,
-        I::IntoIter: ExactSizeIterator, // + TrustedLen
+        I: Iterator<Item = SliceItem>,
     {
-        struct InProgress<Head, SliceItem> {
-            raw: NonNull<ThinData<Head, SliceItem>>,
-            written_len: usize,
-            layout: Layout,
-            head_offset: usize,
-            slice_offset: usize,
+        for _ in self.written_len..self.len {
+            let slice_item = items
+                .next()
+                .expect("slice iterator yielded fewer items than its length");
+            unsafe { self.push(slice_item) };
         }
+        assert!(
+            items.next().is_none(),
+            "slice iterator yielded more items than its length"
+        );
+    }
 
-        impl<Head, SliceItem> Drop for InProgress<Head, SliceItem> {
-            fn drop(&mut self) {
-                let raw_ptr = ThinData::erase(self.raw).as_ptr();
-                unsafe {
-                    let slice = make_slice_mut(
-                        raw_ptr.add(self.slice_offset).cast::<SliceItem>(),
-                        self.written_len,
-                    );
-                    ptr::drop_in_place(slice);
-                    dealloc(raw_ptr.cast(), self.layout);
-                }
-            }
+    /// Shrink the allocation to hold only the slice items written so far.
+    fn shrink_to_written(&mut self) {
+        if self.written_len == self.len {
+            return;
         }
+        let (layout, _) = Self::layout(self.written_len);
+        let ptr = unsafe { realloc(self.raw.as_ptr().cast(), self.layout, layout.size()) };
+        self.raw = NonNull::new(ptr)
+            .unwrap_or_else(|| handle_alloc_error(layout))
+            .cast();
+        self.layout = layout;
+        self.len = self.written_len;
+        unsafe {
+            ptr::write(
+                ThinData::<Head, SliceItem>::len(self.raw).as_ptr(),
+                Len::new(self.len),
+            )
+        };
+    }
 
-        impl<Head, SliceItem> InProgress<Head, SliceItem> {
-            fn raw_ptr(&self) -> ErasedPtr {
-                ThinData::erase(self.raw)
-            }
+    /// Move all slice items out of a `Vec` with a single copy, then write the head.
+    fn fill_from_vec(mut self, head: Head, mut items: Vec<SliceItem>) -> ThinBox<Head, SliceItem> {
+        assert_eq!(items.len(), self.len - self.written_len);
+        unsafe {
+            let dst = self
+                .raw
+                .as_ptr()
+                .add(self.slice_offset)
+                .cast::<SliceItem>()
+                .add(self.written_len);
+            ptr::copy_nonoverlapping(items.as_ptr(), dst, items.len());
+            self.written_len = self.len;
+            items.set_len(0);
+            self.finish(head)
+        }
+    }
 
-            fn new(len: usize) -> Self {
-                let (layout, [_, head_offset, slice_offset]) =
-                    ThinBox::<Head, SliceItem>::layout(len)
-                        .unwrap_or_else(|e| panic!("oversize box: {}", e));
-                InProgress {
-                    raw: unsafe { ThinBox::alloc(len, layout) },
-                    written_len: 0,
-                    layout,
-                    head_offset,
-                    slice_offset,
-                }
-            }
+    /// Write all slice items, then the head.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` does not yield exactly `self.len` items.
+    fn fill<I>(mut self, head: Head, items: I) -> ThinBox<Head, SliceItem>
+    where
+        I: Iterator<Item = SliceItem>,
+    {
+        self.push_all(items);
+        unsafe { self.finish(head) }
+    }
 
-            unsafe fn push(&mut self, item: SliceItem) {
-                self.raw_ptr()
-                    .as_ptr()
-                    .add(self.slice_offset)
-                    .cast::<SliceItem>()
-                    .add(self.written_len)
-                    .write(item);
-                self.written_len += 1;
-            }
+    /// # Safety
+    ///
+    /// All `self.len` slice items must have been written.
+    unsafe fn finish(mut self, head: Head) -> ThinBox<Head, SliceItem> {
+        self.write_head(head);
+        self.into_box()
+    }
 
-            unsafe fn finish(self, head: Head) -> ThinBox<Head, SliceItem> {
-                let this = ManuallyDrop::new(self);
-                let ptr = this.raw_ptr();
-                ptr::write(ptr.as_ptr().add(this.head_offset).cast(), head);
-                let out = ThinBox::from_erased(ptr);
-                assert_eq!(this.layout, Layout::for_value(&*out));
-                out
-            }
-        }
+    /// Write all slice items, then the head, returning the built `ThinData`
+    /// without taking ownership of its memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` does not yield exactly `self.len` items.
+    fn fill_in_place<I>(mut self, head: Head, items: I) -> ErasedPtr
+    where
+        I: Iterator<Item = SliceItem>,
+    {
+        self.push_all(items);
+        self.write_head(head);
+        unsafe { self.into_erased() }
+    }
 
-        let mut items = slice.into_iter();
-        let len = items.len();
+    /// # Safety
+    ///
+    /// All `self.len` slice items and the head must have been written.
+    unsafe fn into_erased(self) -> ErasedPtr {
+        debug_assert!(self.head_written && self.written_len == self.len);
+        ManuallyDrop::new(self).raw
+    }
 
-        unsafe {
-            let mut this = InProgress::new(len);
+    /// # Safety
+    ///
+    /// All `self.len` slice items and the head must have been written.
+    unsafe fn into_box(self) -> ThinBox<Head, SliceItem> {
+        debug_assert!(self.head_written && self.written_len == self.len);
+        let this = ManuallyDrop::new(self);
+        let out = ThinBox::from_erased(this.raw);
+        assert_eq!(this.layout, Layout::for_value(&*out));
+        out
+    }
+}
 
-            for _ in 0..len {
-                let slice_item = items
-                    .next()
-                    .expect("ExactSizeIterator over-reported length");
-                this.push(slice_item);
-            }
-            assert!(
-                items.next().is_none(),
-                "ExactSizeIterator under-reported length"
-            );
+impl<Head, Head2, SliceItem> ThinBox<Head, ThinBox<Head2, SliceItem>> {
+    /// Move a box of boxes out into a jagged structure of `Vec`s,
+    /// freeing the allocations.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let nested = ThinBox::new("rows", vec![ThinBox::new(0, vec![1]), ThinBox::new(1, vec![])]);
+    /// let (head, rows) = ThinBox::flatten(nested);
+    /// assert_eq!(head, "rows");
+    /// assert_eq!(rows, [(0, vec![1]), (1, vec![])]);
+    /// ```
+    pub fn flatten(this: Self) -> (Head, Vec<(Head2, Vec<SliceItem>)>) {
+        let (head, rows) = ThinBox::into_parts(this);
+        (head, rows.into_iter().map(ThinBox::into_parts).collect())
+    }
+}
 
-            this.finish(head)
-        }
+impl<Head> ThinBox<Head, u8> {
+    /// Create a new boxed `ThinData` with the given head and a zeroed byte slice.
+    ///
+    /// Reading a buffer into the slice of this avoids copying it afterwards.
+    /// A `Vec<u8>` can't be adopted as a `ThinBox` in place, as its allocation
+    /// has the wrong alignment for the inline length, so read into this instead.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// # let mut socket: &[u8] = &[1, 2, 3, 4];
+    /// use std::io::Read;
+    /// let mut frame = ThinBox::new_zeroed("frame", 4);
+    /// socket.read_exact(&mut frame.slice).unwrap();
+    /// assert_eq!(frame.slice, [1, 2, 3, 4]);
+    /// ```
+    pub fn new_zeroed(head: Head, len: usize) -> Self {
+        unsafe { InProgress::new_zeroed(len).finish(head) }
+    }
+}
+
+impl<Head, SliceItem> From<(Head, Vec<SliceItem>)> for ThinBox<Head, SliceItem> {
+    /// Move the slice items next to the head in a new allocation with a single copy.
+    fn from((head, slice): (Head, Vec<SliceItem>)) -> Self {
+        InProgress::new(slice.len()).fill_from_vec(head, slice)
+    }
+}
+
+impl<Head, SliceItem> From<(Head, Box<[SliceItem]>)> for ThinBox<Head, SliceItem> {
+    /// Move the slice items next to the head in a new allocation with a single copy.
+    fn from((head, slice): (Head, Box<[SliceItem]>)) -> Self {
+        (head, slice.into_vec()).into()
     }
 }
 
@@ -394,8 +1797,26 @@ where
     }
 }
 
+/// The reference count above which `try_clone` refuses to clone.
+const MAX_REFCOUNT: usize = isize::MAX as usize / 2;
+
 /// A thin version of [`Arc`].
 ///
+/// # Memory ordering
+///
+/// The reference count is managed by `Arc`, so it has the same guarantees:
+/// cloning is a `Relaxed` increment, and dropping is a `Release` decrement
+/// followed by an `Acquire` fence before the last owner drops the value.
+/// Everything written before dropping a `ThinArc` on one thread
+/// is visible to the thread that drops the data.
+///
+/// # Single-threaded use
+///
+/// If the pointers never cross threads (e.g. on `wasm32` without threads),
+/// the atomic operations are pure overhead. Use [`ThinRc`] instead,
+/// which offers the same API with non-atomic counts. Making `ThinArc` itself
+/// non-atomic behind a feature isn't possible, as it would still be `Send`.
+///
 ///   [`Arc`]: <https://doc.rust-lang.org/stable/std/sync/struct.Arc.html>
 pub struct ThinArc<Head, SliceItem> {
     raw: ErasedPtr,
@@ -432,6 +1853,30 @@ impl<Head, SliceItem> ThinArc<Head, SliceItem> {
     }
 }
 
+impl<Head, SliceItem> From<(Head, Vec<SliceItem>)> for ThinArc<Head, SliceItem> {
+    /// Move the slice items next to the head in a new allocation.
+    ///
+    /// As with [`ThinArc::new`], this currently goes through a `ThinBox`.
+    fn from(parts: (Head, Vec<SliceItem>)) -> Self {
+        let boxed: Box<ThinData<Head, SliceItem>> = ThinBox::from(parts).into();
+        Arc::<ThinData<Head, SliceItem>>::from(boxed).into()
+    }
+}
+
+impl<Head, SliceItem> From<(Head, Box<[SliceItem]>)> for ThinArc<Head, SliceItem> {
+    /// Move the slice items next to the head in a new allocation.
+    fn from((head, slice): (Head, Box<[SliceItem]>)) -> Self {
+        (head, slice.into_vec()).into()
+    }
+}
+
+impl<Head, SliceItem: Clone> From<(Head, Arc<[SliceItem]>)> for ThinArc<Head, SliceItem> {
+    /// Clone the shared slice items next to the head in a new allocation.
+    fn from((head, slice): (Head, Arc<[SliceItem]>)) -> Self {
+        ThinArc::new(head, slice.iter().cloned())
+    }
+}
+
 impl<Head, SliceItem> From<ThinArc<Head, SliceItem>> for Arc<ThinData<Head, SliceItem>> {
     fn from(this: ThinArc<Head, SliceItem>) -> Self {
         unsafe {
@@ -445,6 +1890,10 @@ impl<Head, SliceItem> Clone for ThinArc<Head, SliceItem>
 where
     Arc<ThinData<Head, SliceItem>>: Clone,
 {
+    /// Clone this pointer, incrementing the reference count.
+    ///
+    /// Like `Arc::clone`, this aborts the process if the reference count overflows.
+    /// Use [`ThinArc::try_clone`] to handle that case instead.
     fn clone(&self) -> Self {
         unsafe {
             let this = ManuallyDrop::new(Arc::from_raw(ThinData::fatten_const(self.raw).as_ptr()));
@@ -453,8 +1902,268 @@ where
     }
 }
 
+impl<Head, SliceItem> ThinArc<Head, SliceItem> {
+    /// Create a new weak pointer to this allocation.
+    pub fn downgrade(this: &Self) -> ThinWeak<Head, SliceItem> {
+        unsafe {
+            let this = ManuallyDrop::new(Arc::from_raw(
+                ThinData::<Head, SliceItem>::fatten_const(this.raw).as_ptr(),
+            ));
+            Arc::downgrade(&this).into()
+        }
+    }
+
+    /// Get the number of strong (`ThinArc`) pointers to this allocation.
+    pub fn strong_count(this: &Self) -> usize {
+        unsafe {
+            let this = ManuallyDrop::new(Arc::from_raw(
+                ThinData::<Head, SliceItem>::fatten_const(this.raw).as_ptr(),
+            ));
+            Arc::strong_count(&this)
+        }
+    }
+
+    /// Whether this is the only pointer to the allocation, strong or weak.
+    ///
+    /// This is when [`ThinArc::get_mut`] and [`ThinArc::try_unwrap`] succeed,
+    /// though if other threads can reach this pointer, they may clone it
+    /// as soon as this returns.
+    pub fn is_unique(this: &Self) -> bool {
+        unsafe {
+            let this = ManuallyDrop::new(Arc::from_raw(
+                ThinData::<Head, SliceItem>::fatten_const(this.raw).as_ptr(),
+            ));
+            Arc::strong_count(&this) == 1 && Arc::weak_count(&this) == 0
+        }
+    }
+
+    /// Mutably borrow the data, if this is the only pointer to it.
+    ///
+    /// Like `Arc::get_mut`, this also fails while any [`ThinWeak`] pointers exist.
+    pub fn get_mut(this: &mut Self) -> Option<&mut ThinData<Head, SliceItem>> {
+        unsafe {
+            let mut arc = ManuallyDrop::new(Arc::from_raw(
+                ThinData::<Head, SliceItem>::fatten_const(this.raw).as_ptr(),
+            ));
+            let data: *mut ThinData<Head, SliceItem> = Arc::get_mut(&mut arc)?;
+            Some(&mut *data)
+        }
+    }
+
+    /// Move the data out into a `ThinBox`, if this is the only pointer to it.
+    ///
+    /// Otherwise, the pointer is returned unchanged. This also fails while any
+    /// [`ThinWeak`] pointers to the allocation exist, as they could upgrade
+    /// concurrently.
+    ///
+    /// Unlike `Arc::into_inner`, failing doesn't release this pointer,
+    /// so when several threads race to unwrap their clones, they must drop
+    /// them one at a time to guarantee that one of them succeeds.
+    /// (`Arc::into_inner` is only available for sized types,
+    /// and `Arc`'s heap layout is not stable, so it can't be reimplemented here.)
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let arc = ThinArc::new("head", vec![1, 2]);
+    /// let clone = arc.clone();
+    /// let arc = ThinArc::try_unwrap(arc).unwrap_err();
+    /// drop(clone);
+    /// let boxed: ThinBox<_, _> = ThinArc::try_unwrap(arc).unwrap();
+    /// assert_eq!(boxed.slice, [1, 2]);
+    /// ```
+    pub fn try_unwrap(this: Self) -> Result<ThinBox<Head, SliceItem>, Self> {
+        let mut arc: Arc<ThinData<Head, SliceItem>> = this.into();
+        let boxed = match Arc::get_mut(&mut arc) {
+            Some(data) => unsafe { ThinBox::read_from(data) },
+            None => return Err(arc.into()),
+        };
+        #[cfg(all(feature = "trace-alloc", debug_assertions))]
+        trace::Traced::untrack(&arc);
+        // The data was moved out, so free the allocation without dropping it.
+        let raw =
+            Arc::into_raw(arc) as *const ThinData<ManuallyDrop<Head>, ManuallyDrop<SliceItem>>;
+        drop(unsafe { Arc::from_raw(raw) });
+        Ok(boxed)
+    }
+
+    /// Clone this pointer, unless the reference count is already very high.
+    ///
+    /// `Clone` defers to `Arc::clone`, which aborts the process
+    /// if the reference count would overflow `isize::MAX`.
+    /// The count is owned by `Arc`, so that policy can't be changed here;
+    /// this instead checks the count first, and returns `None` once it
+    /// reaches half of `isize::MAX`.
+    ///
+    /// The check and the increment are two separate steps, so clones on other
+    /// threads can still move the count between them. This is a best-effort
+    /// guard against runaway cloning, not a guarantee that cloning can't abort.
+    pub fn try_clone(this: &Self) -> Option<Self> {
+        if Self::strong_count(this) < MAX_REFCOUNT {
+            Some(this.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Increment the strong count of the `ThinArc` behind an erased pointer.
+    ///
+    /// Together with [`ThinArc::decrement_strong_count`], this lets foreign code
+    /// share ownership of a node: the reference count itself is managed by `Arc`,
+    /// whose heap layout is not stable, so it can't be manipulated directly.
+    /// Instead, export monomorphic wrappers of these functions for it to call.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// #[no_mangle]
+    /// pub unsafe extern "C" fn node_retain(node: ErasedPtr) {
+    ///     ThinArc::<u32, u8>::increment_strong_count(node)
+    /// }
+    ///
+    /// #[no_mangle]
+    /// pub unsafe extern "C" fn node_release(node: ErasedPtr) {
+    ///     ThinArc::<u32, u8>::decrement_strong_count(node)
+    /// }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The pointer must have been obtained from [`ThinArc::erase`],
+    /// and the allocation must still be alive (have a strong count of at least one).
+    pub unsafe fn increment_strong_count(ptr: ErasedPtr) {
+        Arc::increment_strong_count(ThinData::<Head, SliceItem>::fatten_const(ptr).as_ptr())
+    }
+
+    /// Decrement the strong count of the `ThinArc` behind an erased pointer,
+    /// dropping it if this was the last strong pointer.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must have been obtained from [`ThinArc::erase`],
+    /// and the strong count must be at least one.
+    /// This releases one of those strong references.
+    pub unsafe fn decrement_strong_count(ptr: ErasedPtr) {
+        drop(Self::from_erased(ptr))
+    }
+
+    /// Hand this pointer to a reclaimer, which releases it later.
+    ///
+    /// `reclaim` receives the erased pointer and the function that releases it,
+    /// the shape expected by epoch-based and hazard-pointer reclamation schemes
+    /// (e.g. deferring `release(ptr)` until no reader can still hold `ptr`).
+    /// As the release is what can free the allocation, deferring it defers
+    /// the deallocation when this was the last strong pointer.
+    ///
+    /// If `reclaim` never calls `release`, the pointer is leaked.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let mut deferred = Vec::new();
+    /// let arc = ThinArc::new((), vec![1, 2, 3]);
+    /// let erased = ThinArc::erase(arc.clone());
+    /// ThinArc::retire(arc, |ptr, release| deferred.push((ptr, release)));
+    ///
+    /// // Once no reader can be using the retired pointers:
+    /// for (ptr, release) in deferred.drain(..) {
+    ///     unsafe { release(ptr) };
+    /// }
+    /// # drop(unsafe { ThinArc::<(), i32>::from_erased(erased) });
+    /// ```
+    pub fn retire<F>(this: Self, reclaim: F)
+    where
+        F: FnOnce(ErasedPtr, unsafe fn(ErasedPtr)),
+    {
+        reclaim(Self::erase(this), Self::decrement_strong_count)
+    }
+
+    /// Check whether two pointers point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.raw == other.raw
+    }
+
+    /// Compare two nodes for equality, short-circuiting if they are the same allocation.
+    ///
+    /// Unlike `==`, this does not require `Eq` to skip comparing shared nodes.
+    /// Implementing a node's `PartialEq` in terms of this makes comparing
+    /// two trees that share structure only visit the parts that differ.
+    /// Pairing this with a [`diff::SubtreeHash`] head rejects most unequal
+    /// subtrees by their hash before looking at their children.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// #[derive(Clone)]
+    /// struct Node(ThinArc<f32, Node>);
+    /// impl PartialEq for Node {
+    ///     fn eq(&self, other: &Self) -> bool {
+    ///         ThinArc::shallow_eq(&self.0, &other.0)
+    ///     }
+    /// }
+    ///
+    /// let nan = Node(ThinArc::new(f32::NAN, vec![]));
+    /// assert!(Node(ThinArc::new(0.0, vec![nan.clone()])) == Node(ThinArc::new(0.0, vec![nan])));
+    /// ```
+    pub fn shallow_eq(this: &Self, other: &Self) -> bool
+    where
+        Head: PartialEq,
+        SliceItem: PartialEq,
+    {
+        Self::ptr_eq(this, other) || (this.head == other.head && this.slice == other.slice)
+    }
+}
+
+/// A thin version of [`sync::Weak`].
+///
+/// This can only be created from an existing [`ThinArc`], via [`ThinArc::downgrade`].
+///
+/// Note that releasing a `ThinWeak` reads the slice length out of the allocation.
+/// When a weak pointer needs to be stored inside the value it points to
+/// (such as a child's link to its parent), prefer [`tree::ParentLink`].
+///
+///   [`sync::Weak`]: <https://doc.rust-lang.org/stable/std/sync/struct.Weak.html>
+pub struct ThinWeak<Head, SliceItem> {
+    raw: ErasedPtr,
+    marker: PhantomData<Weak<ThinData<Head, SliceItem>>>,
+}
+
+thin_holder!(for ThinWeak<Head, SliceItem> as Weak<ThinData<Head, SliceItem>> with fatten_const);
+fat_traits!(for ThinWeak<Head, SliceItem> as Weak<ThinData<Head, SliceItem>>);
+
+impl<Head, SliceItem> ThinWeak<Head, SliceItem> {
+    /// Attempt to upgrade this to a `ThinArc`,
+    /// returning `None` if the value has already been dropped.
+    pub fn upgrade(&self) -> Option<ThinArc<Head, SliceItem>> {
+        // NB: the length is still readable after the value is dropped,
+        //     as it has no drop glue and the allocation is kept alive.
+        unsafe {
+            let this = ManuallyDrop::new(Weak::from_raw(ThinData::fatten_const(self.raw).as_ptr()));
+            this.upgrade().map(Into::into)
+        }
+    }
+}
+
+impl<Head, SliceItem> From<ThinWeak<Head, SliceItem>> for Weak<ThinData<Head, SliceItem>> {
+    fn from(this: ThinWeak<Head, SliceItem>) -> Self {
+        unsafe {
+            let this = ManuallyDrop::new(this);
+            Weak::from_raw(ThinData::fatten_const(this.raw).as_ptr())
+        }
+    }
+}
+
+impl<Head, SliceItem> Clone for ThinWeak<Head, SliceItem> {
+    fn clone(&self) -> Self {
+        unsafe {
+            let this = ManuallyDrop::new(Weak::from_raw(ThinData::fatten_const(self.raw).as_ptr()));
+            ManuallyDrop::into_inner(ManuallyDrop::clone(&this)).into()
+        }
+    }
+}
+
 /// A thin version of [`Rc`].
 ///
+/// Like `Rc`, this is neither `Send` nor `Sync`, whatever the head and slice are.
+/// To move a freshly built graph to another thread without rebuilding it
+/// out of `ThinArc`s, use [`ThinRc::try_into_send`].
+///
 ///   [`Rc`]: <https://doc.rust-lang.org/stable/std/rc/struct.Rc.html>
 pub struct ThinRc<Head, SliceItem> {
     raw: ErasedPtr,
@@ -491,6 +2200,30 @@ impl<Head, SliceItem> ThinRc<Head, SliceItem> {
     }
 }
 
+impl<Head, SliceItem> From<(Head, Vec<SliceItem>)> for ThinRc<Head, SliceItem> {
+    /// Move the slice items next to the head in a new allocation.
+    ///
+    /// As with [`ThinRc::new`], this currently goes through a `ThinBox`.
+    fn from(parts: (Head, Vec<SliceItem>)) -> Self {
+        let boxed: Box<ThinData<Head, SliceItem>> = ThinBox::from(parts).into();
+        Rc::<ThinData<Head, SliceItem>>::from(boxed).into()
+    }
+}
+
+impl<Head, SliceItem> From<(Head, Box<[SliceItem]>)> for ThinRc<Head, SliceItem> {
+    /// Move the slice items next to the head in a new allocation.
+    fn from((head, slice): (Head, Box<[SliceItem]>)) -> Self {
+        (head, slice.into_vec()).into()
+    }
+}
+
+impl<Head, SliceItem: Clone> From<(Head, Rc<[SliceItem]>)> for ThinRc<Head, SliceItem> {
+    /// Clone the shared slice items next to the head in a new allocation.
+    fn from((head, slice): (Head, Rc<[SliceItem]>)) -> Self {
+        ThinRc::new(head, slice.iter().cloned())
+    }
+}
+
 impl<Head, SliceItem> From<ThinRc<Head, SliceItem>> for Rc<ThinData<Head, SliceItem>> {
     fn from(this: ThinRc<Head, SliceItem>) -> Self {
         unsafe {
@@ -504,6 +2237,10 @@ impl<Head, SliceItem> Clone for ThinRc<Head, SliceItem>
 where
     Rc<ThinData<Head, SliceItem>>: Clone,
 {
+    /// Clone this pointer, incrementing the reference count.
+    ///
+    /// Like `Rc::clone`, this aborts the process if the reference count overflows.
+    /// Use [`ThinRc::try_clone`] to handle that case instead.
     fn clone(&self) -> Self {
         unsafe {
             let this = ManuallyDrop::new(Rc::from_raw(ThinData::fatten_const(self.raw).as_ptr()));
@@ -512,6 +2249,89 @@ where
     }
 }
 
+impl<Head, SliceItem> ThinRc<Head, SliceItem> {
+    /// Get the number of strong (`ThinRc`) pointers to this allocation.
+    pub fn strong_count(this: &Self) -> usize {
+        unsafe {
+            let this = ManuallyDrop::new(Rc::from_raw(
+                ThinData::<Head, SliceItem>::fatten_const(this.raw).as_ptr(),
+            ));
+            Rc::strong_count(&this)
+        }
+    }
+
+    /// Whether this is the only pointer to the allocation, strong or weak.
+    ///
+    /// This is when [`ThinRc::try_unwrap`] succeeds.
+    pub fn is_unique(this: &Self) -> bool {
+        unsafe {
+            let this = ManuallyDrop::new(Rc::from_raw(
+                ThinData::<Head, SliceItem>::fatten_const(this.raw).as_ptr(),
+            ));
+            Rc::strong_count(&this) == 1 && Rc::weak_count(&this) == 0
+        }
+    }
+
+    /// Move the data out into a `ThinBox`, if this is the only pointer to it.
+    ///
+    /// Otherwise, the pointer is returned unchanged.
+    /// This also fails while any weak pointers to the allocation exist.
+    pub fn try_unwrap(this: Self) -> Result<ThinBox<Head, SliceItem>, Self> {
+        let mut rc: Rc<ThinData<Head, SliceItem>> = this.into();
+        let boxed = match Rc::get_mut(&mut rc) {
+            Some(data) => unsafe { ThinBox::read_from(data) },
+            None => return Err(rc.into()),
+        };
+        // The data was moved out, so free the allocation without dropping it.
+        let raw = Rc::into_raw(rc) as *const ThinData<ManuallyDrop<Head>, ManuallyDrop<SliceItem>>;
+        drop(unsafe { Rc::from_raw(raw) });
+        Ok(boxed)
+    }
+
+    /// Clone this pointer, unless the reference count is already very high.
+    ///
+    /// Returns `None` once the count reaches half of `isize::MAX`.
+    /// A `ThinRc` can't be shared between threads, so unlike
+    /// [`ThinArc::try_clone`], nothing can clone between the check and the increment.
+    pub fn try_clone(this: &Self) -> Option<Self> {
+        if Self::strong_count(this) < MAX_REFCOUNT {
+            Some(this.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Check whether two pointers point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.raw == other.raw
+    }
+
+    /// Compare two nodes for equality, short-circuiting if they are the same allocation.
+    ///
+    /// See [`ThinArc::shallow_eq`].
+    pub fn shallow_eq(this: &Self, other: &Self) -> bool
+    where
+        Head: PartialEq,
+        SliceItem: PartialEq,
+    {
+        Self::ptr_eq(this, other) || (this.head == other.head && this.slice == other.slice)
+    }
+}
+
+/// A thin version of `&ThinData`.
+///
+/// This converts to and from `&'a ThinData` in both directions with [`From`],
+/// and [`ThinRef::map_head`] and [`ThinRef::map_slice`] borrow its parts for
+/// the full lifetime `'a`, rather than just for as long as the `ThinRef`.
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let boxed = ThinBox::new("head", vec![1, 2, 3]);
+/// let thin: ThinRef<'_, _, _> = (&*boxed).into();
+/// let fat: &ThinData<_, _> = thin.into();
+/// assert_eq!(fat.slice, [1, 2, 3]);
+/// ```
+#[repr(transparent)]
 pub struct ThinRef<'a, Head, SliceItem> {
     raw: ErasedPtr,
     marker: PhantomData<&'a ThinData<Head, SliceItem>>,
@@ -538,6 +2358,119 @@ impl<'a, Head, SliceItem> From<ThinRef<'a, Head, SliceItem>> for &'a ThinData<He
     }
 }
 
+impl<'a, Head, SliceItem> ThinRef<'a, Head, SliceItem> {
+    /// View a table of erased pointers as a table of thin references.
+    ///
+    /// This is a no-op cast; each pointer is only fattened when dereferenced.
+    ///
+    /// # Safety
+    ///
+    /// Every pointer in the table must point to a valid `ThinData<Head, SliceItem>`
+    /// which is borrowed immutably for `'a`.
+    pub unsafe fn from_erased_slice(ptrs: &[ErasedPtr]) -> &[Self] {
+        // SAFETY: ThinRef is a repr(transparent) ErasedPtr
+        &*(ptrs as *const [ErasedPtr] as *const [Self])
+    }
+
+    /// Borrow a part of the head for the full lifetime `'a`.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// fn name<'a>(node: ThinRef<'a, (String, u32), u8>) -> &'a str {
+    ///     ThinRef::map_head(node, |(name, _)| name)
+    /// }
+    /// let boxed = ThinBox::new((String::from("node"), 0), vec![]);
+    /// assert_eq!(name((&*boxed).into()), "node");
+    /// ```
+    pub fn map_head<U, F>(this: Self, f: F) -> &'a U
+    where
+        U: ?Sized,
+        F: FnOnce(&'a Head) -> &'a U,
+    {
+        let data: &'a ThinData<Head, SliceItem> = this.into();
+        f(&data.head)
+    }
+
+    /// Borrow a part of the slice for the full lifetime `'a`.
+    pub fn map_slice<U, F>(this: Self, f: F) -> &'a U
+    where
+        U: ?Sized,
+        F: FnOnce(&'a [SliceItem]) -> &'a U,
+    {
+        let data: &'a ThinData<Head, SliceItem> = this.into();
+        f(&data.slice)
+    }
+}
+
+/// A [`ThinRef`] with the slice length cached next to the pointer.
+///
+/// This is two words wide, just like `&ThinData`, but converts to and from
+/// a `ThinRef` without any pointer arithmetic. Use it on hot read paths
+/// where re-reading the length through the pointer on every deref is measurable.
+pub struct FatThinRef<'a, Head, SliceItem> {
+    raw: ErasedPtr,
+    // SAFETY: must be the length stored in the pointee
+    len: usize,
+    marker: PhantomData<&'a ThinData<Head, SliceItem>>,
+}
+
+impl<'a, Head, SliceItem> Deref for FatThinRef<'a, Head, SliceItem> {
+    type Target = ThinData<Head, SliceItem>;
+    fn deref(&self) -> &ThinData<Head, SliceItem> {
+        (*self).into()
+    }
+}
+
+impl<'a, Head, SliceItem> Copy for FatThinRef<'a, Head, SliceItem> {}
+impl<'a, Head, SliceItem> Clone for FatThinRef<'a, Head, SliceItem> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, Head, SliceItem> Debug for FatThinRef<'a, Head, SliceItem>
+where
+    ThinData<Head, SliceItem>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+unsafe impl<'a, Head, SliceItem> Send for FatThinRef<'a, Head, SliceItem> where
+    &'a ThinData<Head, SliceItem>: Send
+{
+}
+unsafe impl<'a, Head, SliceItem> Sync for FatThinRef<'a, Head, SliceItem> where
+    &'a ThinData<Head, SliceItem>: Sync
+{
+}
+
+impl<'a, Head, SliceItem> From<ThinRef<'a, Head, SliceItem>> for FatThinRef<'a, Head, SliceItem> {
+    fn from(this: ThinRef<'a, Head, SliceItem>) -> Self {
+        FatThinRef {
+            raw: this.raw,
+            len: unsafe { ptr::read(ThinData::<Head, SliceItem>::len(this.raw).as_ptr()).get() },
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, Head, SliceItem> From<FatThinRef<'a, Head, SliceItem>> for ThinRef<'a, Head, SliceItem> {
+    fn from(this: FatThinRef<'a, Head, SliceItem>) -> Self {
+        unsafe { ThinRef::from_erased(this.raw) }
+    }
+}
+
+impl<'a, Head, SliceItem> From<FatThinRef<'a, Head, SliceItem>> for &'a ThinData<Head, SliceItem> {
+    fn from(this: FatThinRef<'a, Head, SliceItem>) -> Self {
+        unsafe {
+            let slice = make_slice(this.raw.cast::<SliceItem>().as_ptr(), this.len);
+            &*(slice as *const ThinData<Head, SliceItem>)
+        }
+    }
+}
+
 pub struct ThinRefMut<'a, Head, SliceItem> {
     raw: ErasedPtr,
     marker: PhantomData<&'a mut ThinData<Head, SliceItem>>,
@@ -559,6 +2492,7 @@ pub struct ThinPtr<Head, SliceItem> {
 }
 
 thin_holder!(#[nodrop] for ThinPtr<Head, SliceItem> as NonNull<ThinData<Head, SliceItem>> with fatten_mut);
+fat_traits!(for ThinPtr<Head, SliceItem> as NonNull<ThinData<Head, SliceItem>>);
 
 impl<Head, SliceItem> Copy for ThinPtr<Head, SliceItem> where
     NonNull<ThinData<Head, SliceItem>>: Copy
@@ -599,11 +2533,17 @@ impl<Head, SliceItem> ThinPtr<Head, SliceItem> {
 
 // helpers for implementing ThinRef[Mut] and ThinPtr[Mut]
 
+/// # Safety
+///
+/// `from_raw` must accept any pointer produced by `into_raw`.
 unsafe trait RawExt<T: ?Sized> {
     unsafe fn from_raw(ptr: *const T) -> Self;
     unsafe fn into_raw(self) -> *const T;
 }
 
+/// # Safety
+///
+/// `from_raw` must accept any pointer produced by `into_raw`.
 unsafe trait RawMutExt<T: ?Sized> {
     unsafe fn from_raw(ptr: *mut T) -> Self;
     unsafe fn into_raw(self) -> *mut T;
@@ -0,0 +1,227 @@
+use {
+    crate::{priv_in_pub::Erased, ErasedPtr, ThinArc, ThinData},
+    core::{
+        cell::UnsafeCell,
+        fmt::{self, Debug},
+        marker::PhantomData,
+        mem::MaybeUninit,
+        ptr::{self, NonNull},
+        sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+    },
+};
+
+/// The capacity of the first segment of a list made with [`ThinArcList::new`].
+const FIRST_SEGMENT: usize = 8;
+
+struct SegmentHead<T> {
+    /// The next segment, as an erased `ThinArc`, or null.
+    next: AtomicPtr<Erased>,
+    /// The number of slots claimed by pushes, which can exceed the capacity.
+    claimed: AtomicUsize,
+    marker: PhantomData<T>,
+}
+
+struct Slot<T> {
+    ready: AtomicBool,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: a slot's value is written once before `ready` is released,
+// and only read through shared references after `ready` is acquired.
+unsafe impl<T: Send + Sync> Sync for Slot<T> {}
+unsafe impl<T: Send> Send for Slot<T> {}
+
+impl<T> Drop for Slot<T> {
+    fn drop(&mut self) {
+        if *self.ready.get_mut() {
+            unsafe { ptr::drop_in_place(self.value.get_mut().as_mut_ptr()) }
+        }
+    }
+}
+
+type Segment<T> = ThinData<SegmentHead<T>, Slot<T>>;
+
+impl<T> Drop for SegmentHead<T> {
+    fn drop(&mut self) {
+        if let Some(next) = NonNull::new(*self.next.get_mut()) {
+            drop(unsafe { ThinArc::<SegmentHead<T>, Slot<T>>::from_erased(next) });
+        }
+    }
+}
+
+fn new_segment<T>(capacity: usize) -> ThinArc<SegmentHead<T>, Slot<T>> {
+    let head = SegmentHead {
+        next: AtomicPtr::new(ptr::null_mut()),
+        claimed: AtomicUsize::new(0),
+        marker: PhantomData,
+    };
+    let slots = (0..capacity).map(|_| Slot {
+        ready: AtomicBool::new(false),
+        value: UnsafeCell::new(MaybeUninit::uninit()),
+    });
+    ThinArc::new(head, slots)
+}
+
+/// Borrow a segment owned by the list.
+///
+/// # Safety
+///
+/// `ptr` must be an erased segment `ThinArc` that lives for `'a`.
+unsafe fn segment<'a, T>(ptr: ErasedPtr) -> &'a Segment<T> {
+    &*Segment::<T>::fatten_const(ptr).as_ptr()
+}
+
+/// A thread-safe, append-only list, made of a chain of `ThinArc` segments.
+///
+/// Pushing is lock-free: a push claims a slot in the last segment with one
+/// atomic increment, and once that segment is full, links a new segment
+/// twice as large. Items are never moved or removed, so iterating only
+/// needs a shared reference and sees every push that completed before it.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let list = ThinArcList::new();
+/// std::thread::scope(|s| {
+///     for t in 0..4 {
+///         let list = &list;
+///         s.spawn(move || (0..100).for_each(|i| list.push(t * 100 + i)));
+///     }
+/// });
+/// let mut items: Vec<_> = list.iter().copied().collect();
+/// items.sort();
+/// assert_eq!(items, (0..400).collect::<Vec<_>>());
+/// ```
+pub struct ThinArcList<T> {
+    first: ThinArc<SegmentHead<T>, Slot<T>>,
+    /// A hint to the last segment, which is owned through the chain from `first`.
+    last: AtomicPtr<Erased>,
+}
+
+impl<T> Default for ThinArcList<T> {
+    fn default() -> Self {
+        ThinArcList::with_capacity(FIRST_SEGMENT)
+    }
+}
+
+impl<T> ThinArcList<T> {
+    /// Create a new, empty list.
+    pub fn new() -> Self {
+        ThinArcList::default()
+    }
+
+    /// Create a new, empty list whose first segment holds `capacity` items.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let first = new_segment(capacity.max(1));
+        let last = AtomicPtr::new(first.raw.as_ptr());
+        ThinArcList { first, last }
+    }
+
+    /// Append an item to the list.
+    pub fn push(&self, value: T) {
+        let mut value = value;
+        let mut ptr = unsafe { NonNull::new_unchecked(self.last.load(Ordering::Acquire)) };
+        loop {
+            let seg = unsafe { segment::<T>(ptr) };
+            let index = seg.head.claimed.fetch_add(1, Ordering::Relaxed);
+            if let Some(slot) = seg.slice.get(index) {
+                unsafe { (*slot.value.get()).as_mut_ptr().write(value) };
+                slot.ready.store(true, Ordering::Release);
+                return;
+            }
+
+            // This segment is full; move on to the next one, linking it if needed.
+            let mut next = seg.head.next.load(Ordering::Acquire);
+            if next.is_null() {
+                let new = new_segment::<T>(seg.slice.len() * 2);
+                new.head.claimed.store(1, Ordering::Relaxed);
+                let slot = &new.slice[0];
+                unsafe { (*slot.value.get()).as_mut_ptr().write(value) };
+                slot.ready.store(true, Ordering::Relaxed);
+                let new = ThinArc::erase(new).as_ptr();
+                match seg.head.next.compare_exchange(
+                    ptr::null_mut(),
+                    new,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                ) {
+                    Ok(_) => {
+                        self.last.store(new, Ordering::Release);
+                        return;
+                    }
+                    Err(linked) => {
+                        // Someone else linked a segment first; take the value back.
+                        let new = unsafe {
+                            ThinArc::<SegmentHead<T>, Slot<T>>::from_erased(NonNull::new_unchecked(
+                                new,
+                            ))
+                        };
+                        let slot = &new.slice[0];
+                        slot.ready.store(false, Ordering::Relaxed);
+                        value = unsafe { (*slot.value.get()).as_ptr().read() };
+                        next = linked;
+                    }
+                }
+            }
+            ptr = unsafe { NonNull::new_unchecked(next) };
+        }
+    }
+
+    /// Iterate over the items whose push completed before they were reached.
+    pub fn iter(&self) -> ThinArcListIter<'_, T> {
+        ThinArcListIter {
+            segment: Some(&*self.first),
+            index: 0,
+        }
+    }
+}
+
+impl<T: Debug> Debug for ThinArcList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over a [`ThinArcList`]. See [`ThinArcList::iter`].
+pub struct ThinArcListIter<'a, T> {
+    segment: Option<&'a Segment<T>>,
+    index: usize,
+}
+
+impl<'a, T> Iterator for ThinArcListIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let seg = self.segment?;
+            if let Some(slot) = seg.slice.get(self.index) {
+                self.index += 1;
+                if slot.ready.load(Ordering::Acquire) {
+                    return Some(unsafe { &*(*slot.value.get()).as_ptr() });
+                }
+            } else {
+                let next = NonNull::new(seg.head.next.load(Ordering::Acquire));
+                self.segment = next.map(|next| unsafe { segment::<T>(next) });
+                self.index = 0;
+            }
+        }
+    }
+}
+
+impl<T> Clone for ThinArcListIter<'_, T> {
+    fn clone(&self) -> Self {
+        ThinArcListIter {
+            segment: self.segment,
+            index: self.index,
+        }
+    }
+}
+
+impl<T> Debug for ThinArcListIter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinArcListIter")
+            .field("index", &self.index)
+            .finish()
+    }
+}
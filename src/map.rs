@@ -0,0 +1,140 @@
+use {
+    crate::{ThinBox, ThinData},
+    alloc::vec::Vec,
+    core::{
+        borrow::Borrow,
+        fmt::{self, Debug},
+        mem,
+        ops::Deref,
+    },
+};
+
+/// A [`ThinBox`] whose slice is key-value pairs sorted by key,
+/// making a small immutable map in the same allocation as its head.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let attrs = ThinMap::new("node", vec![("width", 10), ("height", 20), ("width", 30)]);
+/// assert_eq!(attrs.get("width"), Some(&30));
+/// assert_eq!(attrs.get("depth"), None);
+/// assert_eq!(attrs.keys().copied().collect::<Vec<_>>(), ["height", "width"]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ThinMap<Head, K, V> {
+    raw: ThinBox<Head, (K, V)>,
+}
+
+impl<Head, K: Ord, V> ThinMap<Head, K, V> {
+    /// Create a new map from key-value pairs.
+    ///
+    /// If a key appears more than once, the last value for it is kept.
+    pub fn new<I>(head: Head, pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut pairs: Vec<(K, V)> = pairs.into_iter().collect();
+        pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        pairs.dedup_by(|later, earlier| {
+            later.0 == earlier.0 && {
+                mem::swap(later, earlier);
+                true
+            }
+        });
+        ThinMap {
+            raw: (head, pairs).into(),
+        }
+    }
+
+    fn position<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.raw
+            .slice
+            .binary_search_by(|(k, _)| k.borrow().cmp(key))
+            .ok()
+    }
+
+    /// Get the value for a key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.position(key).map(|i| &self.raw.slice[i].1)
+    }
+
+    /// Mutably borrow the value for a key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let i = self.position(key)?;
+        Some(&mut self.raw.slice[i].1)
+    }
+
+    /// Does the map contain the key?
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.position(key).is_some()
+    }
+}
+
+impl<Head, K, V> ThinMap<Head, K, V> {
+    /// Mutably borrow the head.
+    pub fn head_mut(&mut self) -> &mut Head {
+        &mut self.raw.head
+    }
+
+    /// Iterate over the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.raw.slice.iter().map(|(k, _)| k)
+    }
+
+    /// Iterate over the values, in key order.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.raw.slice.iter().map(|(_, v)| v)
+    }
+
+    /// Unwrap the underlying `ThinBox`.
+    pub fn into_inner(this: Self) -> ThinBox<Head, (K, V)> {
+        this.raw
+    }
+}
+
+impl<Head, K, V> Deref for ThinMap<Head, K, V> {
+    type Target = ThinData<Head, (K, V)>;
+    fn deref(&self) -> &ThinData<Head, (K, V)> {
+        &self.raw
+    }
+}
+
+impl<Head, K, V> From<ThinMap<Head, K, V>> for ThinBox<Head, (K, V)> {
+    fn from(this: ThinMap<Head, K, V>) -> Self {
+        this.raw
+    }
+}
+
+impl<Head: Debug, K: Debug, V: Debug> Debug for ThinMap<Head, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Entries<'a, K, V>(&'a [(K, V)]);
+        impl<K: Debug, V: Debug> Debug for Entries<'_, K, V> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_map()
+                    .entries(self.0.iter().map(|(k, v)| (k, v)))
+                    .finish()
+            }
+        }
+        f.debug_struct("ThinMap")
+            .field("head", &self.raw.head)
+            .field("entries", &Entries(&self.raw.slice))
+            .finish()
+    }
+}
@@ -0,0 +1,122 @@
+use {
+    crate::{ThinBox, ThinData},
+    core::{
+        marker::PhantomData,
+        mem,
+        ops::{Deref, DerefMut},
+    },
+};
+
+struct HasNiche<Head>(PhantomData<Head>);
+impl<Head> HasNiche<Head> {
+    const ASSERT: () = assert!(
+        mem::size_of::<Option<Head>>() == mem::size_of::<Head>(),
+        "`Head` must have a niche to encode `None` in",
+    );
+}
+
+/// A boxed `ThinData` whose head may be absent, at no cost in size.
+///
+/// The head is stored as an `Option<Head>`, and `Head` is required to have a
+/// niche (such as `NonZeroU32`, a reference, or a `ThinBox`), so `None` is
+/// encoded in the head slot itself (e.g. as all zeros), and the allocation
+/// is no bigger than that of a `ThinBox<Head, SliceItem>`. This gives
+/// `Option`-like semantics for the head inside the allocation, while the
+/// slice is always present. It is a compile-time error to use this with a
+/// `Head` without a niche.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// use std::num::NonZeroU32;
+/// let row = ThinOption::<NonZeroU32, _>::none(vec![1, 2, 3]);
+/// assert_eq!(row.head, None);
+///
+/// let mut row = ThinOption::from(ThinBox::new(NonZeroU32::new(7).unwrap(), vec![4]));
+/// assert_eq!(row.head.take().map(NonZeroU32::get), Some(7));
+/// assert!(ThinOption::into_some(row).is_err());
+/// ```
+///
+/// ```rust,compile_fail
+/// # use thin_dst::*;
+/// // `u32` has no niche, so `None` would need a separate tag.
+/// let row = ThinOption::<u32, u8>::none(vec![]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThinOption<Head, SliceItem> {
+    raw: ThinBox<Option<Head>, SliceItem>,
+}
+
+impl<Head, SliceItem> ThinOption<Head, SliceItem> {
+    /// Create a new `ThinOption` with the given head and slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    pub fn new<I>(head: Option<Head>, slice: I) -> Self
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let () = HasNiche::<Head>::ASSERT;
+        ThinOption {
+            raw: ThinBox::new(head, slice),
+        }
+    }
+
+    /// Create a new `ThinOption` with no head and the given slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    pub fn none<I>(slice: I) -> Self
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        ThinOption::new(None, slice)
+    }
+
+    /// Convert into a `ThinBox` with a present head, reusing the allocation.
+    ///
+    /// Returns `this` back if there is no head.
+    pub fn into_some(this: Self) -> Result<ThinBox<Head, SliceItem>, Self> {
+        let () = HasNiche::<Head>::ASSERT;
+        if this.head.is_none() {
+            return Err(this);
+        }
+        Ok(ThinBox::retype_head(this.raw, |head| match head {
+            Some(head) => head,
+            None => unreachable!(),
+        }))
+    }
+
+    /// Unwrap the underlying `ThinBox`.
+    pub fn into_inner(this: Self) -> ThinBox<Option<Head>, SliceItem> {
+        this.raw
+    }
+}
+
+impl<Head, SliceItem> From<ThinBox<Head, SliceItem>> for ThinOption<Head, SliceItem> {
+    /// Wrap the head in `Some`, reusing the allocation.
+    fn from(boxed: ThinBox<Head, SliceItem>) -> Self {
+        let () = HasNiche::<Head>::ASSERT;
+        ThinOption {
+            raw: ThinBox::retype_head(boxed, Some),
+        }
+    }
+}
+
+impl<Head, SliceItem> Deref for ThinOption<Head, SliceItem> {
+    type Target = ThinData<Option<Head>, SliceItem>;
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl<Head, SliceItem> DerefMut for ThinOption<Head, SliceItem> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.raw
+    }
+}
@@ -0,0 +1,132 @@
+use {
+    crate::ThinPointer,
+    core::{
+        cmp::Ordering,
+        hash::{Hash, Hasher},
+        ops::Deref,
+    },
+};
+
+/// A thin pointer compared, ordered, and hashed by its head alone.
+///
+/// This lets collections which need `Ord` or `Hash` order thin pointers
+/// by a key kept in the head, without a newtype in every consumer.
+///
+/// # Examples
+///
+/// ```rust
+/// # use {std::collections::BinaryHeap, thin_dst::*};
+/// let mut queue = BinaryHeap::new();
+/// queue.push(SortByHead(ThinArc::new(1, vec!["low"])));
+/// queue.push(SortByHead(ThinArc::new(9, vec!["urgent", "!"])));
+/// queue.push(SortByHead(ThinArc::new(5, vec!["normal"])));
+/// assert_eq!(queue.pop().unwrap().slice, ["urgent", "!"]);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct SortByHead<P>(pub P);
+
+/// A thin pointer compared, ordered, and hashed by its head, then its slice.
+///
+/// Unlike `SortByHead`, this is consistent with the `Eq` of the pointee.
+///
+/// # Examples
+///
+/// ```rust
+/// # use {std::collections::BTreeSet, thin_dst::*};
+/// let set: BTreeSet<_> = vec![
+///     SortByHeadAndSlice(ThinBox::new('b', vec![0])),
+///     SortByHeadAndSlice(ThinBox::new('a', vec![2])),
+///     SortByHeadAndSlice(ThinBox::new('a', vec![1, 5])),
+/// ]
+/// .into_iter()
+/// .collect();
+/// let order: Vec<_> = set.iter().map(|node| (node.head, &node.slice[..])).collect();
+/// assert_eq!(order, [('a', &[1, 5][..]), ('a', &[2][..]), ('b', &[0][..])]);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct SortByHeadAndSlice<P>(pub P);
+
+macro_rules! sort_wrapper {
+    ($($wrapper:ident),* $(,)?) => {$(
+        impl<P> $wrapper<P> {
+            /// Unwrap the pointer.
+            pub fn into_inner(self) -> P {
+                self.0
+            }
+        }
+
+        impl<P> Deref for $wrapper<P> {
+            type Target = P;
+            fn deref(&self) -> &P {
+                &self.0
+            }
+        }
+
+        impl<P> From<P> for $wrapper<P> {
+            fn from(ptr: P) -> Self {
+                $wrapper(ptr)
+            }
+        }
+
+        impl<P> PartialEq for $wrapper<P>
+        where
+            Self: Ord,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl<P> Eq for $wrapper<P> where Self: Ord {}
+
+        impl<P> PartialOrd for $wrapper<P>
+        where
+            Self: Ord,
+        {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+    )*};
+}
+
+sort_wrapper!(SortByHead, SortByHeadAndSlice);
+
+impl<P: ThinPointer> Ord for SortByHead<P>
+where
+    P::Head: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.head.cmp(&other.0.head)
+    }
+}
+
+impl<P: ThinPointer> Hash for SortByHead<P>
+where
+    P::Head: Ord + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.head.hash(state)
+    }
+}
+
+impl<P: ThinPointer> Ord for SortByHeadAndSlice<P>
+where
+    P::Head: Ord,
+    P::Item: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.0.head, &self.0.slice).cmp(&(&other.0.head, &other.0.slice))
+    }
+}
+
+impl<P: ThinPointer> Hash for SortByHeadAndSlice<P>
+where
+    P::Head: Ord + Hash,
+    P::Item: Ord + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.head.hash(state);
+        self.0.slice.hash(state)
+    }
+}
@@ -1,9 +1,11 @@
 //! Polyfills for unstable features `slice_from_raw_parts` and `alloc_layout_extra`,
-//! along with a theoretical `fn repr_c` to compute `#[repr(C)]` layouts.
+//! along with a theoretical `fn repr_c` to compute `#[repr(C)]` layouts
+//! and a portable stand-in for the unstable `prefetch_read_data` intrinsic.
 
 pub(crate) use self::slice_from_raw_parts::{make_slice, make_slice_mut};
 
 #[cfg(not(slice_from_raw_parts))] // https://github.com/rust-lang/rust/issues/36925
+#[allow(clippy::cast_slice_from_raw_parts)]
 mod slice_from_raw_parts {
     use core::slice;
     pub(crate) unsafe fn make_slice<T>(data: *const T, len: usize) -> *const [T] {
@@ -26,24 +28,27 @@ pub(crate) use alloc_layout_extra::{extend_layout, layout_array, pad_layout_to_a
 #[cfg(not(alloc_layout_extra))] // https://github.com/rust-lang/rust/issues/55724
 mod alloc_layout_extra {
     use core::{
-        alloc::{Layout, LayoutErr},
+        alloc::{Layout, LayoutError},
         cmp,
     };
 
-    fn layout_err() -> LayoutErr {
+    fn layout_err() -> LayoutError {
         Layout::from_size_align(0, 0).unwrap_err()
     }
 
-    pub(crate) fn extend_layout(this: &Layout, next: Layout) -> Result<(Layout, usize), LayoutErr> {
+    pub(crate) fn extend_layout(
+        this: &Layout,
+        next: Layout,
+    ) -> Result<(Layout, usize), LayoutError> {
         let new_align = cmp::max(this.align(), next.align());
-        let pad = layout_padding_needed_for(&this, next.align());
+        let pad = layout_padding_needed_for(this, next.align());
         let offset = this.size().checked_add(pad).ok_or_else(layout_err)?;
         let new_size = offset.checked_add(next.size()).ok_or_else(layout_err)?;
         let layout = Layout::from_size_align(new_size, new_align)?;
         Ok((layout, offset))
     }
 
-    pub(crate) fn layout_array<T>(n: usize) -> Result<Layout, LayoutErr> {
+    pub(crate) fn layout_array<T>(n: usize) -> Result<Layout, LayoutError> {
         repeat_layout(&Layout::new::<T>(), n).map(|(k, _)| k)
     }
 
@@ -59,7 +64,7 @@ mod alloc_layout_extra {
         len_rounded_up.wrapping_sub(len)
     }
 
-    fn repeat_layout(this: &Layout, n: usize) -> Result<(Layout, usize), LayoutErr> {
+    fn repeat_layout(this: &Layout, n: usize) -> Result<(Layout, usize), LayoutError> {
         let padded_size = pad_layout_to_align(this).size();
         let alloc_size = padded_size.checked_mul(n).ok_or_else(layout_err)?;
         unsafe {
@@ -73,20 +78,56 @@ mod alloc_layout_extra {
 
 #[cfg(alloc_layout_extra)] // https://github.com/rust-lang/rust/issues/55724
 mod alloc_layout_extra {
-    use core::alloc::{Layout, LayoutErr};
-    pub(crate) fn extend_layout(this: &Layout, next: Layout) -> Result<(Layout, usize), LayoutErr> {
+    use core::alloc::{Layout, LayoutError};
+    pub(crate) fn extend_layout(
+        this: &Layout,
+        next: Layout,
+    ) -> Result<(Layout, usize), LayoutError> {
         this.extend(next)
     }
-    pub(crate) fn layout_array<T>(n: usize) -> Result<Layout, LayoutErr> {
+    pub(crate) fn layout_array<T>(n: usize) -> Result<Layout, LayoutError> {
         Layout::array::<T>(n)
     }
     pub(crate) fn pad_layout_to_align(this: &Layout) -> Layout {
-        this.pad_to_align().unwrap()
+        this.pad_to_align()
     }
 }
 
-use core::alloc::{Layout, LayoutErr};
-pub fn repr_c_3(fields: [Layout; 3]) -> Result<(Layout, [usize; 3]), LayoutErr> {
+pub(crate) use prefetch::prefetch_read;
+
+// https://doc.rust-lang.org/core/intrinsics/fn.prefetch_read_data.html
+mod prefetch {
+    /// Conservative cache line size; overshooting just issues redundant hints.
+    #[allow(unused)]
+    const CACHE_LINE: usize = 64;
+
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse"
+    ))]
+    pub(crate) fn prefetch_read(start: *const u8, len: usize) {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::{_mm_prefetch, _MM_HINT_T0};
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+
+        let mut offset = 0;
+        while offset < len {
+            // SAFETY: prefetching is a hint and never faults, even out of bounds
+            unsafe { _mm_prefetch::<_MM_HINT_T0>(start.wrapping_add(offset).cast()) };
+            offset += CACHE_LINE;
+        }
+    }
+
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse"
+    )))]
+    pub(crate) fn prefetch_read(_start: *const u8, _len: usize) {}
+}
+
+use core::alloc::{Layout, LayoutError};
+pub fn repr_c_3(fields: [Layout; 3]) -> Result<(Layout, [usize; 3]), LayoutError> {
     let mut offsets = [0; 3];
     let mut layout = fields[0];
     for i in 1..3 {
@@ -0,0 +1,147 @@
+use {
+    crate::{InProgress, ThinBox, ThinData},
+    alloc::{
+        alloc::{alloc, dealloc, handle_alloc_error, Layout},
+        collections::BTreeMap,
+        vec::Vec,
+    },
+    core::{
+        fmt::{self, Debug},
+        mem,
+        ptr::{self, NonNull},
+    },
+};
+
+/// A pool of recycled `ThinBox` allocations.
+///
+/// Allocations are keyed by their exact layout, so any `ThinBox`
+/// with the same size and alignment can reuse a recycled allocation,
+/// regardless of its head and slice types.
+///
+/// Boxes created from a pool are ordinary `ThinBox`es;
+/// dropping one normally frees its allocation as usual.
+/// To return the allocation to the pool instead, pass it to [`Pool::recycle`].
+///
+/// Recycling is explicit because a `ThinBox` is a single pointer with no room
+/// to remember which pool it came from. Returning allocations from `Drop`
+/// would need a global pool that every `ThinBox` drop, pooled or not, checks
+/// under a lock — a cost this crate doesn't impose on code that never pools.
+#[derive(Default)]
+pub struct Pool {
+    free: BTreeMap<(usize, usize), Vec<NonNull<u8>>>,
+}
+
+// SAFETY: the pool only holds uninitialized allocations, not any values
+unsafe impl Send for Pool {}
+unsafe impl Sync for Pool {}
+
+fn key(layout: Layout) -> (usize, usize) {
+    (layout.size(), layout.align())
+}
+
+impl Pool {
+    /// Create a new, empty pool.
+    pub fn new() -> Self {
+        Pool::default()
+    }
+
+    /// The number of recycled allocations currently held by the pool.
+    pub fn len(&self) -> usize {
+        self.free.values().map(Vec::len).sum()
+    }
+
+    /// Does the pool currently hold no recycled allocations?
+    pub fn is_empty(&self) -> bool {
+        self.free.values().all(Vec::is_empty)
+    }
+
+    /// Take an allocation of the given layout from the pool, or allocate a new one.
+    fn alloc(&mut self, layout: Layout) -> NonNull<u8> {
+        self.free
+            .get_mut(&key(layout))
+            .and_then(Vec::pop)
+            .unwrap_or_else(|| unsafe {
+                NonNull::new(alloc(layout)).unwrap_or_else(|| handle_alloc_error(layout))
+            })
+    }
+
+    /// Create a new boxed `ThinData` with the given head and slice,
+    /// reusing a recycled allocation if one with the right layout is available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    pub fn new_box<Head, SliceItem, I>(&mut self, head: Head, slice: I) -> ThinBox<Head, SliceItem>
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let items = slice.into_iter();
+        let len = items.len();
+        let (layout, _) = InProgress::<Head, SliceItem>::layout(len);
+        let ptr = self.alloc(layout);
+        unsafe { InProgress::from_alloc(len, ptr).fill(head, items) }
+    }
+
+    /// Create many new boxed `ThinData` from pairs of head and slice,
+    /// reusing recycled allocations where possible.
+    ///
+    /// Every layout is computed before anything is taken from the pool,
+    /// so an oversize node panics without disturbing it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any box would exceed `isize::MAX` bytes,
+    /// or any slice iterator incorrectly reports its length.
+    pub fn new_many<Head, SliceItem, I, S>(&mut self, nodes: I) -> Vec<ThinBox<Head, SliceItem>>
+    where
+        I: IntoIterator<Item = (Head, S)>,
+        S: IntoIterator<Item = SliceItem>,
+        S::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let nodes: Vec<(Head, S::IntoIter, Layout)> = nodes
+            .into_iter()
+            .map(|(head, slice)| {
+                let items = slice.into_iter();
+                let (layout, _) = InProgress::<Head, SliceItem>::layout(items.len());
+                (head, items, layout)
+            })
+            .collect();
+        let mut boxes = Vec::with_capacity(nodes.len());
+        for (head, items, layout) in nodes {
+            let ptr = self.alloc(layout);
+            boxes.push(unsafe { InProgress::from_alloc(items.len(), ptr).fill(head, items) });
+        }
+        boxes
+    }
+
+    /// Drop the contents of a `ThinBox`, keeping its allocation in the pool for reuse.
+    pub fn recycle<Head, SliceItem>(&mut self, this: ThinBox<Head, SliceItem>) {
+        let layout = Layout::for_value::<ThinData<Head, SliceItem>>(&this);
+        let raw = ThinBox::erase(this);
+        unsafe { ptr::drop_in_place(ThinData::<Head, SliceItem>::fatten_mut(raw).as_ptr()) };
+        self.free.entry(key(layout)).or_default().push(raw.cast());
+    }
+
+    /// Free all recycled allocations held by the pool.
+    pub fn clear(&mut self) {
+        for ((size, align), ptrs) in mem::take(&mut self.free) {
+            let layout = unsafe { Layout::from_size_align_unchecked(size, align) };
+            for ptr in ptrs {
+                unsafe { dealloc(ptr.as_ptr(), layout) };
+            }
+        }
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl Debug for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool").field("len", &self.len()).finish()
+    }
+}
@@ -0,0 +1,118 @@
+use {
+    crate::ThinData,
+    core::{
+        fmt::{self, Debug},
+        hash::{Hash, Hasher},
+        marker::PhantomData,
+        ops::Deref,
+        ptr,
+    },
+};
+
+/// A thin pointer used as a map key, with its value's hash computed once up front.
+///
+/// Hashing a `ThinRc` hashes the entire head and slice, which gets expensive
+/// when a `HashMap` rehashes every key as it grows. `Prehashed` instead hashes
+/// the value once on construction, and only feeds that hash to the map's hasher.
+/// Equality is still by value, short-circuiting on the hash and on pointer identity.
+///
+/// The pointee must not be mutated while it is wrapped, as the hash would go stale.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// # use std::collections::{hash_map::DefaultHasher, HashMap};
+/// let mut map = HashMap::new();
+/// let key = ThinRc::new("big", 0..100_000);
+/// map.insert(Prehashed::<_, DefaultHasher>::new(key.clone()), 1);
+///
+/// let equal = ThinRc::new("big", 0..100_000);
+/// assert_eq!(map.get(&Prehashed::new(equal)), Some(&1));
+/// ```
+pub struct Prehashed<P, H> {
+    hash: u64,
+    ptr: P,
+    // fn() -> H: the hasher is only used during construction.
+    marker: PhantomData<fn() -> H>,
+}
+
+impl<P, H, Head, SliceItem> Prehashed<P, H>
+where
+    P: Deref<Target = ThinData<Head, SliceItem>>,
+    H: Hasher + Default,
+    Head: Hash,
+    SliceItem: Hash,
+{
+    /// Wrap a pointer, hashing its value with a fresh `H`.
+    pub fn new(ptr: P) -> Self {
+        let mut hasher = H::default();
+        ptr.hash(&mut hasher);
+        Prehashed {
+            hash: hasher.finish(),
+            ptr,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P, H> Prehashed<P, H> {
+    /// The precomputed hash of the value.
+    pub fn prehash(this: &Self) -> u64 {
+        this.hash
+    }
+
+    /// Unwrap the pointer.
+    pub fn into_inner(this: Self) -> P {
+        this.ptr
+    }
+}
+
+impl<P, H> Deref for Prehashed<P, H> {
+    type Target = P;
+    fn deref(&self) -> &P {
+        &self.ptr
+    }
+}
+
+impl<P, H, Head, SliceItem> PartialEq for Prehashed<P, H>
+where
+    P: Deref<Target = ThinData<Head, SliceItem>>,
+    ThinData<Head, SliceItem>: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && (ptr::eq(&*self.ptr, &*other.ptr) || *self.ptr == *other.ptr)
+    }
+}
+
+impl<P, H, Head, SliceItem> Eq for Prehashed<P, H>
+where
+    P: Deref<Target = ThinData<Head, SliceItem>>,
+    ThinData<Head, SliceItem>: Eq,
+{
+}
+
+impl<P, H> Hash for Prehashed<P, H> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.hash)
+    }
+}
+
+impl<P: Clone, H> Clone for Prehashed<P, H> {
+    fn clone(&self) -> Self {
+        Prehashed {
+            hash: self.hash,
+            ptr: self.ptr.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P: Debug, H> Debug for Prehashed<P, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prehashed")
+            .field("hash", &self.hash)
+            .field("ptr", &self.ptr)
+            .finish()
+    }
+}
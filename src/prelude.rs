@@ -0,0 +1,24 @@
+//! The thin-dst prelude.
+//!
+//! This re-exports the holder types and the traits needed to use them,
+//! so downstream code can get started with a single glob import:
+//!
+//! ```rust
+//! use thin_dst::prelude::*;
+//! let boxed = ThinBox::new((), vec![0, 1, 2]);
+//! ```
+//!
+//! Traits which are only needed for their methods are imported anonymously.
+
+#[doc(no_inline)]
+pub use crate::{
+    assert_thin,
+    codec::{Decode as _, Encode as _},
+    iter::CollectThin as _,
+    search::SliceByHead as _,
+    thin_static,
+    walk::Children,
+    ErasedPtr, HeadWithId, SmallThinBox, ThinArc, ThinArena, ThinBox, ThinData, ThinFixed,
+    ThinHolder, ThinId, ThinKey, ThinPointer, ThinPtr, ThinRc, ThinRef, ThinRefMut, ThinSliceArc,
+    ThinSliceBox, ThinSliceRc, ThinWeak, UniquelyOwned,
+};
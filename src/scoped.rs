@@ -0,0 +1,105 @@
+use {
+    crate::{ThinBox, ThinData},
+    alloc::vec::Vec,
+    core::{
+        cell::RefCell,
+        fmt::{self, Debug},
+    },
+};
+
+/// An arena handing out plain `&'arena ThinData` references.
+///
+/// Unlike a [`ThinArena`](crate::ThinArena), nodes are allocated through
+/// a shared reference and can't be removed, so each one is borrowed
+/// for as long as the arena is. The borrow checker then guarantees that
+/// no reference outlives the arena, and everything is freed with it.
+/// [`ThinScopedArena::scope`] makes the arena's extent explicit.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let total = ThinScopedArena::scope(|arena| {
+///     let a: &ThinData<&str, u32> = arena.alloc("a", vec![1, 2]);
+///     let b = arena.alloc("b", vec![3]);
+///     a.slice.iter().chain(&b.slice).sum::<u32>()
+/// });
+/// assert_eq!(total, 6);
+/// ```
+///
+/// ```rust,compile_fail
+/// # use thin_dst::*;
+/// let escaped = ThinScopedArena::scope(|arena| arena.alloc((), vec![1]));
+/// ```
+pub struct ThinScopedArena<Head, SliceItem> {
+    nodes: RefCell<Vec<ThinBox<Head, SliceItem>>>,
+}
+
+impl<Head, SliceItem> Default for ThinScopedArena<Head, SliceItem> {
+    fn default() -> Self {
+        ThinScopedArena {
+            nodes: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<Head, SliceItem> ThinScopedArena<Head, SliceItem> {
+    /// Create a new, empty arena.
+    pub fn new() -> Self {
+        ThinScopedArena::default()
+    }
+
+    /// Run `f` with a new arena, freeing it and everything in it afterwards.
+    ///
+    /// The references handed out can't escape `f`.
+    pub fn scope<F, R>(f: F) -> R
+    where
+        F: FnOnce(&ThinScopedArena<Head, SliceItem>) -> R,
+    {
+        f(&ThinScopedArena::new())
+    }
+
+    /// The number of nodes in the arena.
+    pub fn len(&self) -> usize {
+        self.nodes.borrow().len()
+    }
+
+    /// Does the arena contain no nodes?
+    pub fn is_empty(&self) -> bool {
+        self.nodes.borrow().is_empty()
+    }
+
+    /// Allocate a new node in the arena, borrowed for as long as the arena is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    pub fn alloc<I>(&self, head: Head, slice: I) -> &ThinData<Head, SliceItem>
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        // Build the node before borrowing the list, in case building it allocates too.
+        let node = ThinBox::new(head, slice);
+        let data: *const ThinData<Head, SliceItem> = &*node;
+        self.nodes.borrow_mut().push(node);
+        // SAFETY: the node's allocation doesn't move when its `ThinBox` does,
+        // and nodes are only freed once the arena is no longer borrowed.
+        unsafe { &*data }
+    }
+
+    /// Consume the arena, taking ownership of the nodes.
+    pub fn into_boxes(self) -> Vec<ThinBox<Head, SliceItem>> {
+        self.nodes.into_inner()
+    }
+}
+
+impl<Head, SliceItem> Debug for ThinScopedArena<Head, SliceItem>
+where
+    Head: Debug,
+    SliceItem: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.nodes.borrow().iter()).finish()
+    }
+}
@@ -0,0 +1,90 @@
+use {
+    crate::ThinData,
+    core::{cmp::Ordering, ops::Deref},
+};
+
+/// Searching and sorting slices of thin pointers by their heads.
+///
+/// These are the slice methods of the same names, with the comparator given
+/// the head of each pointee instead of the pointer itself.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let nodes: Vec<ThinArc<(u32, &str), u8>> = vec![
+///     ThinArc::new((1, "a"), vec![]),
+///     ThinArc::new((3, "b"), vec![]),
+///     ThinArc::new((7, "c"), vec![]),
+/// ];
+/// assert_eq!(nodes.binary_search_by_head_key(&3, |&(id, _)| id), Ok(1));
+/// assert_eq!(nodes.binary_search_by_head_key(&4, |&(id, _)| id), Err(2));
+/// assert_eq!(nodes.partition_point_by_head(|&(id, _)| id < 5), 2);
+/// ```
+pub trait SliceByHead<Head> {
+    /// Binary search a slice sorted by head with a comparator function.
+    ///
+    /// See [`slice::binary_search_by`].
+    fn binary_search_by_head<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&Head) -> Ordering;
+
+    /// Binary search a slice sorted by a key extracted from the head.
+    ///
+    /// See [`slice::binary_search_by_key`].
+    fn binary_search_by_head_key<K, F>(&self, key: &K, f: F) -> Result<usize, usize>
+    where
+        K: Ord,
+        F: FnMut(&Head) -> K;
+
+    /// The index of the first element whose head doesn't satisfy the predicate,
+    /// in a slice partitioned by it.
+    ///
+    /// See [`slice::partition_point`].
+    fn partition_point_by_head<F>(&self, pred: F) -> usize
+    where
+        F: FnMut(&Head) -> bool;
+
+    /// Sort the slice by a key extracted from the head, keeping equal elements in order.
+    ///
+    /// See [`slice::sort_by_key`].
+    fn sort_by_head_key<K, F>(&mut self, f: F)
+    where
+        K: Ord,
+        F: FnMut(&Head) -> K;
+}
+
+impl<P, Head, SliceItem> SliceByHead<Head> for [P]
+where
+    P: Deref<Target = ThinData<Head, SliceItem>>,
+{
+    fn binary_search_by_head<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&Head) -> Ordering,
+    {
+        self.binary_search_by(|p| f(&p.head))
+    }
+
+    fn binary_search_by_head_key<K, F>(&self, key: &K, mut f: F) -> Result<usize, usize>
+    where
+        K: Ord,
+        F: FnMut(&Head) -> K,
+    {
+        self.binary_search_by(|p| f(&p.head).cmp(key))
+    }
+
+    fn partition_point_by_head<F>(&self, mut pred: F) -> usize
+    where
+        F: FnMut(&Head) -> bool,
+    {
+        self.partition_point(|p| pred(&p.head))
+    }
+
+    fn sort_by_head_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&Head) -> K,
+    {
+        self.sort_by_key(|p| f(&p.head))
+    }
+}
@@ -0,0 +1,129 @@
+use {
+    crate::{ThinData, ThinRc},
+    alloc::{rc::Rc, string::String},
+    core::mem::ManuallyDrop,
+};
+
+/// A type whose `Rc`-shared parts can be checked to be uniquely owned,
+/// such that it can be sent to another thread when they are.
+///
+/// This is the hook [`ThinRc::try_into_send`] uses to walk a `ThinRc` graph.
+/// It is implemented for `ThinRc` (checking the counts and recursing into
+/// the head and slice), and for common `Send` types (trivially returning `true`).
+///
+/// # Safety
+///
+/// If `is_uniquely_owned` returns `true`, then moving `self` to another thread
+/// must be sound: every `Rc` (or `ThinRc`) reachable from `self` must have
+/// no other strong or weak pointers, and everything else must be `Send`.
+pub unsafe trait UniquelyOwned {
+    /// Check that nothing reachable from `self` is shared.
+    fn is_uniquely_owned(&self) -> bool;
+}
+
+macro_rules! uniquely_owned_send {
+    ($($ty:ty),* $(,)?) => {$(
+        unsafe impl UniquelyOwned for $ty {
+            fn is_uniquely_owned(&self) -> bool {
+                true
+            }
+        }
+    )*};
+}
+
+uniquely_owned_send!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    String,
+);
+
+unsafe impl<Head, SliceItem> UniquelyOwned for ThinRc<Head, SliceItem>
+where
+    Head: UniquelyOwned,
+    SliceItem: UniquelyOwned,
+{
+    fn is_uniquely_owned(&self) -> bool {
+        let rc = unsafe {
+            ManuallyDrop::new(Rc::from_raw(
+                ThinData::<Head, SliceItem>::fatten_const(self.raw).as_ptr(),
+            ))
+        };
+        Rc::strong_count(&rc) == 1
+            && Rc::weak_count(&rc) == 0
+            && self.head.is_uniquely_owned()
+            && self.slice.iter().all(UniquelyOwned::is_uniquely_owned)
+    }
+}
+
+/// A `ThinRc` graph which has been checked to be uniquely owned,
+/// and so can be sent to another thread.
+///
+/// Created by [`ThinRc::try_into_send`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// struct Node(ThinRc<u32, Node>);
+/// unsafe impl UniquelyOwned for Node {
+///     fn is_uniquely_owned(&self) -> bool {
+///         self.0.is_uniquely_owned()
+///     }
+/// }
+///
+/// let tree = Node(ThinRc::new(0, vec![Node(ThinRc::new(1, vec![]))]));
+/// let send = ThinRc::try_into_send(tree.0).ok().unwrap();
+/// let child = std::thread::spawn(move || {
+///     let tree = Node(send.into_inner());
+///     tree.0.slice[0].0.head
+/// });
+/// assert_eq!(child.join().unwrap(), 1);
+/// ```
+#[derive(Debug)]
+pub struct ThinRcSendGuard<Head, SliceItem> {
+    inner: ThinRc<Head, SliceItem>,
+}
+
+// SAFETY: try_into_send checked UniquelyOwned,
+// and the graph can't be shared again until into_inner.
+unsafe impl<Head, SliceItem> Send for ThinRcSendGuard<Head, SliceItem> {}
+
+impl<Head, SliceItem> ThinRcSendGuard<Head, SliceItem> {
+    /// Take the `ThinRc` back out, on whichever thread the guard is now on.
+    pub fn into_inner(self) -> ThinRc<Head, SliceItem> {
+        self.inner
+    }
+}
+
+impl<Head, SliceItem> ThinRc<Head, SliceItem>
+where
+    Head: UniquelyOwned,
+    SliceItem: UniquelyOwned,
+{
+    /// Prepare to send this `ThinRc` and everything it owns to another thread,
+    /// if nothing in it is shared. Otherwise, give it back.
+    ///
+    /// This walks the entire graph, recursing once per level of `ThinRc`s.
+    pub fn try_into_send(this: Self) -> Result<ThinRcSendGuard<Head, SliceItem>, Self> {
+        if this.is_uniquely_owned() {
+            Ok(ThinRcSendGuard { inner: this })
+        } else {
+            Err(this)
+        }
+    }
+}
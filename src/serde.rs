@@ -0,0 +1,192 @@
+use {
+    crate::{ErasedPtr, ThinArc, ThinBox, ThinData, ThinRc},
+    alloc::{collections::BTreeMap, vec::Vec},
+    core::ops::Deref,
+    serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer},
+};
+
+/// A serde adapter that serializes a thin holder with the head's fields
+/// flattened into the same map as the slice, i.e. `{ ..head, slice }`.
+///
+/// The head must serialize as a struct or map, as required by `#[serde(flatten)]`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*; use serde::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Head {
+///     id: u32,
+/// }
+///
+/// let node = FlattenHead(ThinBox::new(Head { id: 0 }, vec![1, 2, 3]));
+/// let json = serde_json::to_string(&node).unwrap();
+/// assert_eq!(json, r#"{"id":0,"slice":[1,2,3]}"#);
+/// let node: FlattenHead<ThinBox<Head, u32>> = serde_json::from_str(&json).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct FlattenHead<T>(pub T);
+
+#[derive(Serialize)]
+#[serde(bound = "Head: Serialize, SliceItem: Serialize")]
+struct FlatRef<'a, Head, SliceItem> {
+    #[serde(flatten)]
+    head: &'a Head,
+    slice: &'a [SliceItem],
+}
+
+#[derive(Deserialize)]
+#[serde(bound = "Head: Deserialize<'de>, SliceItem: Deserialize<'de>")]
+struct Flat<Head, SliceItem> {
+    #[serde(flatten)]
+    head: Head,
+    slice: Vec<SliceItem>,
+}
+
+impl<T, Head, SliceItem> Serialize for FlattenHead<T>
+where
+    T: Deref<Target = ThinData<Head, SliceItem>>,
+    Head: Serialize,
+    SliceItem: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        FlatRef {
+            head: &self.0.head,
+            slice: &self.0.slice,
+        }
+        .serialize(serializer)
+    }
+}
+
+macro_rules! flatten_head_deserialize {
+    ($($thin:ident),* $(,)?) => {$(
+        impl<'de, Head, SliceItem> Deserialize<'de> for FlattenHead<$thin<Head, SliceItem>>
+        where
+            Head: Deserialize<'de>,
+            SliceItem: Deserialize<'de>,
+        {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let Flat { head, slice } = Flat::deserialize(deserializer)?;
+                Ok(FlattenHead($thin::new(head, slice)))
+            }
+        }
+    )*};
+}
+
+flatten_head_deserialize!(ThinBox, ThinArc, ThinRc);
+
+/// A serde adapter that serializes a DAG of shared thin holders
+/// with each shared allocation written only once.
+///
+/// The slice items of each node are its children, which must convert to and
+/// from the holder (typically a newtype around it). Written naively, a node
+/// reachable along many paths is written once per path, which can blow up
+/// exponentially. This instead writes a list of `[head, [child ids]]` entries,
+/// children before their parents and the root last, where each id is the
+/// index of an earlier entry. Deserializing rebuilds the sharing.
+///
+/// Neither direction recurses once per level of the graph.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// struct Node(ThinArc<String, Node>);
+/// impl AsRef<ThinArc<String, Node>> for Node {
+///     fn as_ref(&self) -> &ThinArc<String, Node> {
+///         &self.0
+///     }
+/// }
+/// impl From<ThinArc<String, Node>> for Node {
+///     fn from(arc: ThinArc<String, Node>) -> Self {
+///         Node(arc)
+///     }
+/// }
+///
+/// let leaf = ThinArc::new("leaf".into(), vec![]);
+/// let pair = ThinArc::new("pair".into(), vec![Node(leaf.clone()), Node(leaf)]);
+/// let root = ThinArc::new("root".into(), vec![Node(pair.clone()), Node(pair)]);
+///
+/// let json = serde_json::to_string(&ShareNodes(root)).unwrap();
+/// assert_eq!(json, r#"[["leaf",[]],["pair",[0,0]],["root",[1,1]]]"#);
+/// let ShareNodes(root): ShareNodes<ThinArc<_, Node>> = serde_json::from_str(&json).unwrap();
+/// assert!(ThinArc::ptr_eq(&root.slice[0].0, &root.slice[1].0));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ShareNodes<T>(pub T);
+
+macro_rules! share_nodes {
+    ($($thin:ident),* $(,)?) => {$(
+        impl<Head, Node> Serialize for ShareNodes<$thin<Head, Node>>
+        where
+            Head: Serialize,
+            Node: AsRef<$thin<Head, Node>>,
+        {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut ids = BTreeMap::<ErasedPtr, usize>::new();
+                let mut entries = Vec::<(&Head, Vec<usize>)>::new();
+                // Each node on the stack, with the index of its next child to visit.
+                let mut stack = Vec::new();
+                stack.push((&self.0, 0));
+                while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+                    if let Some(child) = node.slice.get(*next) {
+                        *next += 1;
+                        let child = child.as_ref();
+                        if !ids.contains_key(&child.raw) {
+                            stack.push((child, 0));
+                        }
+                        continue;
+                    }
+                    stack.pop();
+                    let children = node.slice.iter().map(|child| ids[&child.as_ref().raw]);
+                    let entry = (&node.head, children.collect());
+                    ids.insert(node.raw, entries.len());
+                    entries.push(entry);
+                }
+                entries.serialize(serializer)
+            }
+        }
+
+        impl<'de, Head, Node> Deserialize<'de> for ShareNodes<$thin<Head, Node>>
+        where
+            Head: Deserialize<'de>,
+            Node: From<$thin<Head, Node>>,
+        {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let entries = Vec::<(Head, Vec<usize>)>::deserialize(deserializer)?;
+                let mut nodes = Vec::<$thin<Head, Node>>::with_capacity(entries.len());
+                for (id, (head, children)) in entries.into_iter().enumerate() {
+                    let children = children
+                        .into_iter()
+                        .map(|child| match nodes.get(child) {
+                            Some(child) => Ok(Node::from(child.clone())),
+                            None => Err(D::Error::custom(format_args!(
+                                "node {} refers to child {}, which is not an earlier node",
+                                id, child,
+                            ))),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    nodes.push($thin::new(head, children));
+                }
+                match nodes.pop() {
+                    Some(root) => Ok(ShareNodes(root)),
+                    None => Err(D::Error::invalid_length(0, &"at least one node")),
+                }
+            }
+        }
+    )*};
+}
+
+share_nodes!(ThinArc, ThinRc);
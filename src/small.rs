@@ -0,0 +1,150 @@
+use {
+    crate::{Len, ThinBox, ThinData},
+    core::{
+        fmt::{self, Debug},
+        mem::{ManuallyDrop, MaybeUninit},
+        ops::{Deref, DerefMut},
+        ptr::{self, NonNull},
+    },
+};
+
+/// A [`ThinBox`] that stores up to `N` slice items inline.
+///
+/// Small instances avoid allocating entirely; larger instances spill to a `ThinBox`.
+/// Either way, this dereferences to the same `ThinData` as a `ThinBox` would.
+///
+/// Note that this is _not_ a thin pointer; it is (at least) as large as
+/// the head and `N` slice items, as that's where they're stored inline.
+pub struct SmallThinBox<Head, SliceItem, const N: usize> {
+    repr: Repr<Head, SliceItem, N>,
+}
+
+enum Repr<Head, SliceItem, const N: usize> {
+    Inline(Inline<Head, SliceItem, N>),
+    Heap(ThinBox<Head, SliceItem>),
+}
+
+// NB: this MUST have the same layout as the prefix of ThinData<Head, SliceItem>,
+//     as we fatten a pointer to it into a pointer to ThinData.
+#[repr(C)]
+struct Inline<Head, SliceItem, const N: usize> {
+    // SAFETY: must be the number of initialized items in self.slice
+    len: Len,
+    head: ManuallyDrop<Head>,
+    slice: [MaybeUninit<SliceItem>; N],
+}
+
+impl<Head, SliceItem, const N: usize> Drop for Inline<Head, SliceItem, N> {
+    fn drop(&mut self) {
+        unsafe {
+            let this = ThinData::<Head, SliceItem>::fatten_mut(NonNull::from(self).cast());
+            ptr::drop_in_place(this.as_ptr())
+        }
+    }
+}
+
+impl<Head, SliceItem, const N: usize> SmallThinBox<Head, SliceItem, N> {
+    /// Create a new `SmallThinBox` with the given head and slice.
+    ///
+    /// This only allocates if the slice is longer than `N`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice iterator incorrectly reports its length.
+    pub fn new<I>(head: Head, slice: I) -> Self
+    where
+        I: IntoIterator<Item = SliceItem>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        let mut items = slice.into_iter();
+        let len = items.len();
+
+        if len > N {
+            return SmallThinBox {
+                repr: Repr::Heap(ThinBox::new(head, items)),
+            };
+        }
+
+        let mut inline = Inline {
+            len: Len::new(0),
+            head: ManuallyDrop::new(head),
+            // SAFETY: an array of MaybeUninit does not require initialization
+            slice: unsafe { MaybeUninit::<[MaybeUninit<SliceItem>; N]>::uninit().assume_init() },
+        };
+        for slot in &mut inline.slice[..len] {
+            let slice_item = items
+                .next()
+                .expect("ExactSizeIterator over-reported length");
+            *slot = MaybeUninit::new(slice_item);
+            inline.len = Len::new(inline.len.get() + 1);
+        }
+        assert!(
+            items.next().is_none(),
+            "ExactSizeIterator under-reported length"
+        );
+
+        SmallThinBox {
+            repr: Repr::Inline(inline),
+        }
+    }
+
+    /// Is the slice stored inline (rather than on the heap)?
+    pub fn is_inline(this: &Self) -> bool {
+        match this.repr {
+            Repr::Inline(_) => true,
+            Repr::Heap(_) => false,
+        }
+    }
+}
+
+impl<Head, SliceItem, const N: usize> From<ThinBox<Head, SliceItem>>
+    for SmallThinBox<Head, SliceItem, N>
+{
+    fn from(this: ThinBox<Head, SliceItem>) -> Self {
+        SmallThinBox {
+            repr: Repr::Heap(this),
+        }
+    }
+}
+
+impl<Head, SliceItem, const N: usize> Deref for SmallThinBox<Head, SliceItem, N> {
+    type Target = ThinData<Head, SliceItem>;
+    fn deref(&self) -> &ThinData<Head, SliceItem> {
+        match &self.repr {
+            Repr::Inline(inline) => unsafe {
+                &*ThinData::fatten_const(NonNull::from(inline).cast()).as_ptr()
+            },
+            Repr::Heap(boxed) => boxed,
+        }
+    }
+}
+
+impl<Head, SliceItem, const N: usize> DerefMut for SmallThinBox<Head, SliceItem, N> {
+    fn deref_mut(&mut self) -> &mut ThinData<Head, SliceItem> {
+        match &mut self.repr {
+            Repr::Inline(inline) => unsafe {
+                &mut *ThinData::fatten_mut(NonNull::from(inline).cast()).as_ptr()
+            },
+            Repr::Heap(boxed) => boxed,
+        }
+    }
+}
+
+impl<Head, SliceItem, const N: usize> Debug for SmallThinBox<Head, SliceItem, N>
+where
+    ThinData<Head, SliceItem>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<Head, SliceItem, const N: usize> Clone for SmallThinBox<Head, SliceItem, N>
+where
+    Head: Clone,
+    SliceItem: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallThinBox::new(self.head.clone(), self.slice.iter().cloned())
+    }
+}
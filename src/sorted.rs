@@ -0,0 +1,187 @@
+use {
+    crate::{ThinBox, ThinData},
+    alloc::vec::Vec,
+    core::{
+        cmp::Ordering,
+        fmt::{self, Debug},
+        ops::{Bound, Deref, RangeBounds},
+    },
+};
+
+/// A [`ThinBox`] whose slice is kept sorted and free of duplicates.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let scope = ThinSortedSet::new("scope", vec![5, 1, 3, 1]);
+/// assert_eq!(scope.slice, [1, 3, 5]);
+/// assert!(scope.contains(&3));
+/// assert_eq!(scope.range(2..), [3, 5]);
+///
+/// let other = ThinSortedSet::new("other", vec![3, 4]);
+/// assert_eq!(scope.union("union", &other).slice, [1, 3, 4, 5]);
+/// assert_eq!(scope.intersection("intersection", &other).slice, [3]);
+/// assert_eq!(scope.difference("difference", &other).slice, [1, 5]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ThinSortedSet<Head, SliceItem> {
+    raw: ThinBox<Head, SliceItem>,
+}
+
+impl<Head, SliceItem: Ord> ThinSortedSet<Head, SliceItem> {
+    /// Create a new set, sorting and deduplicating the items.
+    pub fn new<I>(head: Head, items: I) -> Self
+    where
+        I: IntoIterator<Item = SliceItem>,
+    {
+        let mut items: Vec<SliceItem> = items.into_iter().collect();
+        items.sort_unstable();
+        items.dedup();
+        ThinSortedSet {
+            raw: (head, items).into(),
+        }
+    }
+
+    /// Create a new set from a boxed `ThinData`,
+    /// or give it back if its slice isn't strictly increasing.
+    pub fn from_sorted(raw: ThinBox<Head, SliceItem>) -> Result<Self, ThinBox<Head, SliceItem>> {
+        if raw.slice.windows(2).all(|pair| pair[0] < pair[1]) {
+            Ok(ThinSortedSet { raw })
+        } else {
+            Err(raw)
+        }
+    }
+
+    /// Binary search the set for an item. See [`slice::binary_search`].
+    ///
+    ///   [`slice::binary_search`]: <https://doc.rust-lang.org/stable/std/primitive.slice.html#method.binary_search>
+    pub fn binary_search(&self, item: &SliceItem) -> Result<usize, usize> {
+        self.raw.slice.binary_search(item)
+    }
+
+    /// Does the set contain the item?
+    pub fn contains(&self, item: &SliceItem) -> bool {
+        self.binary_search(item).is_ok()
+    }
+
+    /// The items of the set that fall within the range.
+    pub fn range<R: RangeBounds<SliceItem>>(&self, range: R) -> &[SliceItem] {
+        let slice = &self.raw.slice;
+        let start = match range.start_bound() {
+            Bound::Included(start) => slice.partition_point(|item| item < start),
+            Bound::Excluded(start) => slice.partition_point(|item| item <= start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => slice.partition_point(|item| item <= end),
+            Bound::Excluded(end) => slice.partition_point(|item| item < end),
+            Bound::Unbounded => slice.len(),
+        };
+        &slice[start..end.max(start)]
+    }
+
+    /// The items in either set, in a new allocation with the given head.
+    pub fn union<H2>(&self, head: Head, other: &ThinSortedSet<H2, SliceItem>) -> Self
+    where
+        SliceItem: Clone,
+    {
+        self.merge(head, other, true, true, true)
+    }
+
+    /// The items in both sets, in a new allocation with the given head.
+    pub fn intersection<H2>(&self, head: Head, other: &ThinSortedSet<H2, SliceItem>) -> Self
+    where
+        SliceItem: Clone,
+    {
+        self.merge(head, other, false, true, false)
+    }
+
+    /// The items in this set but not the other, in a new allocation with the given head.
+    pub fn difference<H2>(&self, head: Head, other: &ThinSortedSet<H2, SliceItem>) -> Self
+    where
+        SliceItem: Clone,
+    {
+        self.merge(head, other, true, false, false)
+    }
+
+    /// Merge two sets, keeping items only in `self`, in both, and/or only in `other`.
+    fn merge<H2>(
+        &self,
+        head: Head,
+        other: &ThinSortedSet<H2, SliceItem>,
+        keep_left: bool,
+        keep_both: bool,
+        keep_right: bool,
+    ) -> Self
+    where
+        SliceItem: Clone,
+    {
+        let (mut left, mut right) = (&self.raw.slice[..], &other.raw.slice[..]);
+        let mut items = Vec::new();
+        while let (Some(l), Some(r)) = (left.first(), right.first()) {
+            match l.cmp(r) {
+                Ordering::Less => {
+                    if keep_left {
+                        items.push(l.clone());
+                    }
+                    left = &left[1..];
+                }
+                Ordering::Equal => {
+                    if keep_both {
+                        items.push(l.clone());
+                    }
+                    left = &left[1..];
+                    right = &right[1..];
+                }
+                Ordering::Greater => {
+                    if keep_right {
+                        items.push(r.clone());
+                    }
+                    right = &right[1..];
+                }
+            }
+        }
+        if keep_left {
+            items.extend_from_slice(left);
+        }
+        if keep_right {
+            items.extend_from_slice(right);
+        }
+        ThinSortedSet {
+            raw: (head, items).into(),
+        }
+    }
+}
+
+impl<Head, SliceItem> ThinSortedSet<Head, SliceItem> {
+    /// Mutably borrow the head. The slice can't be borrowed mutably,
+    /// as that could break the ordering.
+    pub fn head_mut(&mut self) -> &mut Head {
+        &mut self.raw.head
+    }
+
+    /// Unwrap the underlying `ThinBox`.
+    pub fn into_inner(this: Self) -> ThinBox<Head, SliceItem> {
+        this.raw
+    }
+}
+
+impl<Head, SliceItem> Deref for ThinSortedSet<Head, SliceItem> {
+    type Target = ThinData<Head, SliceItem>;
+    fn deref(&self) -> &ThinData<Head, SliceItem> {
+        &self.raw
+    }
+}
+
+impl<Head, SliceItem> From<ThinSortedSet<Head, SliceItem>> for ThinBox<Head, SliceItem> {
+    fn from(this: ThinSortedSet<Head, SliceItem>) -> Self {
+        this.raw
+    }
+}
+
+impl<Head: Debug, SliceItem: Debug> Debug for ThinSortedSet<Head, SliceItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ThinSortedSet").field(&self.raw).finish()
+    }
+}
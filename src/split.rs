@@ -0,0 +1,241 @@
+use {
+    crate::{
+        polyfill::{make_slice, make_slice_mut},
+        ThinArc, ThinBox, ThinData,
+    },
+    alloc::{boxed::Box, sync::Arc},
+    core::{
+        cell::UnsafeCell,
+        fmt::{self, Debug},
+        ops::{Deref, DerefMut},
+    },
+};
+
+/// A uniquely owned, mutable window into a byte-tailed `ThinArc`.
+///
+/// This follows the `bytes` crate's `BytesMut`: the buffer can be split into
+/// several windows onto the same allocation with [`split_to`](Self::split_to)
+/// and [`split_off`](Self::split_off), each of which can be written
+/// independently, then [`freeze`](Self::freeze)d into shared [`ThinBytes`].
+/// The head is shared by every window, and is only ever borrowed immutably.
+///
+/// The bytes are stored as `UnsafeCell<u8>`, so that windows can write their
+/// own bytes while the others share the `ThinArc` (e.g. to clone or drop it).
+/// The head is outside of any `UnsafeCell`, and is never written.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let mut packet = ThinBytesMut::new("conn 7", *b"HDRpayload");
+/// let mut header = packet.split_to(3);
+/// header.make_ascii_lowercase();
+/// packet[0] = b'P';
+///
+/// let header = header.freeze();
+/// let packet = packet.freeze();
+/// assert_eq!(*header, *b"hdr");
+/// assert_eq!(*packet, *b"Payload");
+/// assert_eq!(packet.head(), &"conn 7");
+/// ```
+pub struct ThinBytesMut<Head> {
+    arc: ThinArc<Head, UnsafeCell<u8>>,
+    start: usize,
+    end: usize,
+}
+
+/// A shared, immutable window into a byte-tailed `ThinArc`.
+///
+/// This is created by [`ThinBytesMut::freeze`]. Clones share the allocation.
+pub struct ThinBytes<Head> {
+    arc: ThinArc<Head, UnsafeCell<u8>>,
+    start: usize,
+    end: usize,
+}
+
+// SAFETY: windows onto one allocation never overlap, a `ThinBytesMut` only
+// writes its own bytes, and only through `&mut self`. Nothing else is shared
+// between the windows but the head, which is only ever borrowed immutably.
+unsafe impl<Head: Send + Sync> Send for ThinBytesMut<Head> {}
+unsafe impl<Head: Send + Sync> Sync for ThinBytesMut<Head> {}
+unsafe impl<Head: Send + Sync> Send for ThinBytes<Head> {}
+unsafe impl<Head: Send + Sync> Sync for ThinBytes<Head> {}
+
+macro_rules! byte_window {
+    ($($Window:ident),* $(,)?) => {$(
+        impl<Head> $Window<Head> {
+            /// The head shared by every window onto the allocation.
+            pub fn head(&self) -> &Head {
+                let raw = self.arc.raw.as_ptr();
+                unsafe {
+                    &*raw
+                        .add(ThinData::<Head, UnsafeCell<u8>>::HEAD_OFFSET)
+                        .cast::<Head>()
+                }
+            }
+
+            /// The number of bytes in this window.
+            pub fn len(&self) -> usize {
+                self.end - self.start
+            }
+
+            /// Whether this window is empty.
+            pub fn is_empty(&self) -> bool {
+                self.start == self.end
+            }
+
+            fn bytes(&self) -> *mut u8 {
+                let bytes = ThinData::<Head, UnsafeCell<u8>>::erased_slice_ptr(self.arc.raw);
+                unsafe { UnsafeCell::raw_get(bytes.as_ptr().add(self.start)) }
+            }
+        }
+
+        impl<Head> Deref for $Window<Head> {
+            type Target = [u8];
+            fn deref(&self) -> &[u8] {
+                unsafe { &*make_slice(self.bytes(), self.len()) }
+            }
+        }
+
+        impl<Head: Debug> Debug for $Window<Head> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($Window))
+                    .field("head", self.head())
+                    .field("bytes", &&**self)
+                    .finish()
+            }
+        }
+    )*};
+}
+
+byte_window!(ThinBytesMut, ThinBytes);
+
+impl<Head> ThinBytesMut<Head> {
+    /// Create a new buffer with the given head and bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the byte iterator incorrectly reports its length.
+    pub fn new<I>(head: Head, bytes: I) -> Self
+    where
+        I: IntoIterator<Item = u8>,
+        I::IntoIter: ExactSizeIterator, // + TrustedLen
+    {
+        ThinBox::new(head, bytes).into()
+    }
+
+    /// Create a new buffer with the given head and `len` zero bytes.
+    pub fn zeroed(head: Head, len: usize) -> Self {
+        ThinBox::new_zeroed(head, len).into()
+    }
+
+    /// Split off the first `at` bytes into a new window,
+    /// leaving the rest in this one.
+    ///
+    /// This is O(1), and both windows keep the allocation alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > self.len()`.
+    pub fn split_to(&mut self, at: usize) -> Self {
+        assert!(at <= self.len(), "split_to out of bounds");
+        let front = ThinBytesMut {
+            arc: self.arc.clone(),
+            start: self.start,
+            end: self.start + at,
+        };
+        self.start += at;
+        front
+    }
+
+    /// Split off the bytes from `at` onwards into a new window,
+    /// leaving the first `at` in this one.
+    ///
+    /// This is O(1), and both windows keep the allocation alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > self.len()`.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len(), "split_off out of bounds");
+        let back = ThinBytesMut {
+            arc: self.arc.clone(),
+            start: self.start + at,
+            end: self.end,
+        };
+        self.end = self.start + at;
+        back
+    }
+
+    /// Make this window immutable, so that it can be shared.
+    pub fn freeze(self) -> ThinBytes<Head> {
+        ThinBytes {
+            arc: self.arc,
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
+
+impl<Head> From<ThinBox<Head, u8>> for ThinBytesMut<Head> {
+    /// Move the bytes into a shared allocation, as a single window over all of them.
+    fn from(boxed: ThinBox<Head, u8>) -> Self {
+        let end = boxed.slice.len();
+        let boxed = ThinBox::map_items_in_place(boxed, UnsafeCell::new);
+        let boxed: Box<ThinData<Head, UnsafeCell<u8>>> = boxed.into();
+        ThinBytesMut {
+            arc: Arc::<ThinData<Head, UnsafeCell<u8>>>::from(boxed).into(),
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<Head> DerefMut for ThinBytesMut<Head> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: windows onto one allocation never overlap,
+        // and mutable windows are uniquely owned.
+        unsafe { &mut *make_slice_mut(self.bytes(), self.len()) }
+    }
+}
+
+impl<Head> ThinBytes<Head> {
+    /// Convert back into a `ThinArc`, if this is the only window onto the
+    /// allocation, and it covers all of the bytes.
+    ///
+    /// Otherwise, the window is returned unchanged.
+    ///
+    /// ```rust
+    /// # use thin_dst::*;
+    /// let mut buf = ThinBytesMut::new((), *b"abc");
+    /// let tail = buf.split_off(1).freeze();
+    /// let head = buf.freeze();
+    /// let tail = ThinBytes::into_thin_arc(tail).unwrap_err();
+    /// drop(head);
+    /// assert!(ThinBytes::into_thin_arc(tail).is_err());
+    ///
+    /// let whole = ThinBytesMut::new((), *b"abc").freeze();
+    /// assert_eq!(ThinBytes::into_thin_arc(whole).unwrap().slice, *b"abc");
+    /// ```
+    pub fn into_thin_arc(this: Self) -> Result<ThinArc<Head, u8>, Self> {
+        let whole = this.start == 0 && this.end == this.arc.slice.len();
+        if whole && ThinArc::strong_count(&this.arc) == 1 {
+            // SAFETY: `UnsafeCell<u8>` has the same layout as `u8`, so the
+            // allocation is also a valid `ThinData<Head, u8>`. This is the only
+            // window, so nothing else can write the bytes through a cell anymore.
+            Ok(unsafe { ThinArc::from_erased(ThinArc::erase(this.arc)) })
+        } else {
+            Err(this)
+        }
+    }
+}
+
+impl<Head> Clone for ThinBytes<Head> {
+    fn clone(&self) -> Self {
+        ThinBytes {
+            arc: self.arc.clone(),
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
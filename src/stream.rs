@@ -0,0 +1,76 @@
+use {
+    crate::{InProgress, ThinBox},
+    core::{future::poll_fn, pin::pin},
+    futures_core::Stream,
+};
+
+impl<Head, SliceItem> ThinBox<Head, SliceItem> {
+    /// Create a new boxed `ThinData` with the given head and
+    /// `len` slice items awaited from a stream.
+    ///
+    /// The items are written straight into the final allocation as they
+    /// arrive. If the future is dropped before it completes, the items
+    /// received so far are dropped and the allocation is freed.
+    /// Items past the first `len` are left in the stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stream ends before yielding `len` items.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use {std::{pin::Pin, task::{Context, Poll}}, thin_dst::*};
+    /// # struct Frames(u8);
+    /// # impl futures_core::Stream for Frames {
+    /// #     type Item = u8;
+    /// #     fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u8>> {
+    /// #         self.0 += 1;
+    /// #         Poll::Ready(Some(self.0))
+    /// #     }
+    /// # }
+    /// # let network = Frames(0);
+    /// # let _ = async {
+    /// let boxed = ThinBox::from_stream("frame", 3, network).await;
+    /// assert_eq!(boxed.slice, [1, 2, 3]);
+    /// # };
+    /// ```
+    pub async fn from_stream<S>(head: Head, len: usize, stream: S) -> Self
+    where
+        S: Stream<Item = SliceItem>,
+    {
+        let mut stream = pin!(stream);
+        let mut this = InProgress::new(len);
+        for _ in 0..len {
+            let item = poll_fn(|cx| stream.as_mut().poll_next(cx))
+                .await
+                .expect("stream ended before yielding `len` items");
+            unsafe { this.push(item) };
+        }
+        unsafe { this.finish(head) }
+    }
+
+    /// Create a new boxed `ThinData` with the given head and
+    /// `len` slice items awaited from a fallible stream.
+    ///
+    /// Stops at the first error and returns it, dropping the items received so far.
+    /// Otherwise the same as [`ThinBox::from_stream`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stream ends before yielding `len` items.
+    pub async fn try_from_stream<S, E>(head: Head, len: usize, stream: S) -> Result<Self, E>
+    where
+        S: Stream<Item = Result<SliceItem, E>>,
+    {
+        let mut stream = pin!(stream);
+        let mut this = InProgress::new(len);
+        for _ in 0..len {
+            let item = poll_fn(|cx| stream.as_mut().poll_next(cx))
+                .await
+                .expect("stream ended before yielding `len` items")?;
+            unsafe { this.push(item) };
+        }
+        Ok(unsafe { this.finish(head) })
+    }
+}
@@ -0,0 +1,135 @@
+use {
+    crate::{polyfill::make_slice, ThinBox},
+    alloc::vec::Vec,
+    core::{
+        convert::TryFrom,
+        fmt::{self, Debug},
+        mem, str,
+    },
+};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct TableHead<Head> {
+    len: usize,
+    head: Head,
+}
+
+/// A table of strings stored in a single allocation with its head,
+/// as the offsets of each string followed by all of their UTF-8 bytes.
+///
+/// This is the classic string interner layout: looking up a string
+/// is two offset reads, with no per-string allocation or length header.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let table = ThinStrTable::new("symbols", ["fn", "let", "match"]);
+/// assert_eq!(table.get(1), Some("let"));
+/// assert_eq!(table.get(3), None);
+/// assert_eq!(table.iter().collect::<Vec<_>>(), ["fn", "let", "match"]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ThinStrTable<Head> {
+    // NB: the slice is `len + 1` u32 offsets into the bytes,
+    //     followed by the bytes packed into u32 words.
+    raw: ThinBox<TableHead<Head>, u32>,
+}
+
+impl<Head> ThinStrTable<Head> {
+    /// Create a new table of the given strings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total length of the strings doesn't fit in a `u32`.
+    pub fn new<I, S>(head: Head, strings: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut offsets = Vec::new();
+        let mut bytes = Vec::new();
+        offsets.push(0);
+        for string in strings {
+            bytes.extend_from_slice(string.as_ref().as_bytes());
+            offsets.push(u32::try_from(bytes.len()).expect("string table overflows u32"));
+        }
+        let len = offsets.len() - 1;
+        let chunks = bytes.chunks(mem::size_of::<u32>()).map(|chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_ne_bytes(word)
+        });
+        offsets.extend(chunks);
+        ThinStrTable {
+            raw: (TableHead { len, head }, offsets).into(),
+        }
+    }
+
+    /// The number of strings in the table.
+    pub fn len(&self) -> usize {
+        self.raw.head.len
+    }
+
+    /// Does the table contain no strings?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Borrow the head.
+    pub fn head(&self) -> &Head {
+        &self.raw.head.head
+    }
+
+    /// Mutably borrow the head.
+    pub fn head_mut(&mut self) -> &mut Head {
+        &mut self.raw.head.head
+    }
+
+    fn offsets(&self) -> &[u32] {
+        &self.raw.slice[..=self.len()]
+    }
+
+    /// All of the strings, concatenated.
+    pub fn as_str(&self) -> &str {
+        let words = &self.raw.slice[self.len() + 1..];
+        let len = self.offsets()[self.len()] as usize;
+        unsafe {
+            // SAFETY: the words are the bytes of whole strings, padded to a multiple of 4
+            let bytes = &*make_slice(words.as_ptr().cast::<u8>(), len);
+            str::from_utf8_unchecked(bytes)
+        }
+    }
+
+    /// Get the string at `index`.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        let offsets = self.offsets();
+        let start = *offsets.get(index)? as usize;
+        let end = *offsets.get(index + 1)? as usize;
+        // NB: the offsets are always on char boundaries, so this can't panic
+        Some(&self.as_str()[start..end])
+    }
+
+    /// Iterate over the strings in the table.
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        let all = self.as_str();
+        self.offsets()
+            .windows(2)
+            .map(move |pair| &all[pair[0] as usize..pair[1] as usize])
+    }
+}
+
+impl<Head: Debug> Debug for ThinStrTable<Head> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Strings<'a, Head>(&'a ThinStrTable<Head>);
+        impl<Head> Debug for Strings<'_, Head> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.0.iter()).finish()
+            }
+        }
+        f.debug_struct("ThinStrTable")
+            .field("head", self.head())
+            .field("strings", &Strings(self))
+            .finish()
+    }
+}
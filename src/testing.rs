@@ -0,0 +1,115 @@
+//! Utilities for testing code built on thin-dst.
+//!
+//! [`LiveCounter`] checks that a graph of thin pointers frees everything it owns:
+//! put a [`Live`] token in each head or slice item, then check that none
+//! are still alive once the graph is dropped.
+//!
+//! ```rust
+//! use thin_dst::{testing::LiveCounter, ThinArc};
+//!
+//! let nodes = LiveCounter::new();
+//! {
+//!     let _guard = nodes.guard();
+//!     let leaf = ThinArc::new(nodes.track(), vec![0u8]);
+//!     let root = ThinArc::new(nodes.track(), vec![leaf.clone(), leaf]);
+//!     assert_eq!(nodes.live(), 2);
+//!     drop(root);
+//! } // The guard panics here if any node leaked.
+//! ```
+
+use {
+    alloc::sync::Arc,
+    core::{
+        fmt::{self, Debug},
+        hash::{Hash, Hasher},
+    },
+};
+
+/// Counts the [`Live`] tokens it handed out that haven't been dropped yet.
+#[derive(Debug, Default)]
+pub struct LiveCounter {
+    tokens: Arc<()>,
+}
+
+impl LiveCounter {
+    /// Create a new counter, with no live tokens.
+    pub fn new() -> Self {
+        LiveCounter::default()
+    }
+
+    /// Hand out a new token, which counts as live until it's dropped.
+    pub fn track(&self) -> Live {
+        Live {
+            _tokens: self.tokens.clone(),
+        }
+    }
+
+    /// The number of tokens still alive.
+    pub fn live(&self) -> usize {
+        Arc::strong_count(&self.tokens) - 1
+    }
+
+    /// Assert that no tokens are still alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any tokens are still alive.
+    #[track_caller]
+    pub fn assert_none_live(&self) {
+        let live = self.live();
+        assert!(live == 0, "{} tracked values are still alive", live);
+    }
+
+    /// A guard which asserts that no tokens are still alive when dropped.
+    ///
+    /// If the thread is already panicking, the guard doesn't check,
+    /// as a panic while unwinding would abort.
+    pub fn guard(&self) -> LiveGuard<'_> {
+        LiveGuard { counter: self }
+    }
+}
+
+/// A token counted as live by the [`LiveCounter`] that made it, until it's dropped.
+///
+/// Cloning a token makes another live token. All tokens compare equal and hash
+/// the same, so they can be put in heads deriving those traits.
+#[derive(Clone)]
+pub struct Live {
+    // Only held to be counted.
+    _tokens: Arc<()>,
+}
+
+impl Debug for Live {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Live")
+    }
+}
+
+impl PartialEq for Live {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Live {}
+
+impl Hash for Live {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+/// Asserts that no tokens of a [`LiveCounter`] are alive when dropped.
+///
+/// See [`LiveCounter::guard`].
+#[derive(Debug)]
+#[must_use = "the guard checks the counter when dropped"]
+pub struct LiveGuard<'a> {
+    counter: &'a LiveCounter,
+}
+
+impl Drop for LiveGuard<'_> {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.counter.assert_none_live();
+        }
+    }
+}
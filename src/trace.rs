@@ -0,0 +1,130 @@
+//! Construction backtraces for `ThinArc` allocations, for hunting refcount leaks.
+//!
+//! In builds with debug assertions, the `trace-alloc` feature records a
+//! backtrace when a `ThinArc` first takes ownership of an allocation,
+//! in a global side table keyed by its erased pointer. The entry is removed
+//! when the last `ThinArc` to the allocation is dropped, so whatever is
+//! still in the table once a tree should have been freed was leaked,
+//! and its backtrace says where it came from.
+//!
+//! Without debug assertions nothing is recorded, and the table stays empty.
+//! Recording captures a full backtrace, so it is very slow.
+//!
+//! ```rust
+//! use thin_dst::{trace, ThinArc};
+//!
+//! let node = ThinArc::new("leaky", vec![0u8]);
+//! # #[cfg(debug_assertions)]
+//! assert!(trace::construction_backtrace(&node).is_some());
+//!
+//! let erased = ThinArc::erase(node);
+//! # #[cfg(debug_assertions)]
+//! assert!(trace::live().iter().any(|&(ptr, _)| ptr == erased));
+//!
+//! drop(unsafe { ThinArc::<&str, u8>::from_erased(erased) });
+//! assert!(trace::live().iter().all(|&(ptr, _)| ptr != erased));
+//! ```
+
+use {
+    crate::{ErasedPtr, ThinArc, ThinData},
+    alloc::{
+        boxed::Box,
+        collections::BTreeMap,
+        rc::Rc,
+        sync::{Arc, Weak},
+        vec::Vec,
+    },
+    core::ptr::NonNull,
+    std::{
+        backtrace::Backtrace,
+        sync::{Mutex, MutexGuard, PoisonError},
+    },
+};
+
+struct Entry {
+    ptr: ErasedPtr,
+    backtrace: Arc<Backtrace>,
+}
+
+// SAFETY: the pointer is only ever handed back out, never dereferenced.
+unsafe impl Send for Entry {}
+
+static TABLE: Mutex<BTreeMap<usize, Entry>> = Mutex::new(BTreeMap::new());
+
+fn table() -> MutexGuard<'static, BTreeMap<usize, Entry>> {
+    TABLE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The backtrace recorded when `arc`'s allocation was created.
+///
+/// This is `None` if nothing was recorded, as in builds without debug
+/// assertions, or when the `ThinArc` was made from a shared `Arc`.
+pub fn construction_backtrace<Head, SliceItem>(
+    arc: &ThinArc<Head, SliceItem>,
+) -> Option<Arc<Backtrace>> {
+    table()
+        .get(&(arc.raw.as_ptr() as usize))
+        .map(|entry| entry.backtrace.clone())
+}
+
+/// Every recorded allocation which no `ThinArc` has freed yet,
+/// with its construction backtrace, in address order.
+///
+/// Allocations are only removed when freed through a `ThinArc`,
+/// so any moved into a plain `Arc` and freed there are reported here too.
+pub fn live() -> Vec<(ErasedPtr, Arc<Backtrace>)> {
+    table()
+        .values()
+        .map(|entry| (entry.ptr, entry.backtrace.clone()))
+        .collect()
+}
+
+/// Hooks called by the thin holders as they take and release ownership.
+///
+/// Only `Arc` records anything; the other holders are untraced.
+pub(crate) trait Traced: Sized {
+    fn record(&self) {}
+
+    /// Drop this holder, removing its entry if that frees the allocation.
+    fn release(self) {
+        drop(self)
+    }
+
+    /// Remove this holder's entry, as it is about to free the allocation itself.
+    fn untrack(&self) {}
+}
+
+impl<Head, SliceItem> Traced for Arc<ThinData<Head, SliceItem>> {
+    fn record(&self) {
+        if Arc::strong_count(self) == 1 {
+            let ptr = ThinData::erase(NonNull::from(&**self));
+            // Capturing is slow, so don't hold up every other `ThinArc` meanwhile.
+            let backtrace = Arc::new(Backtrace::force_capture());
+            // Overwrite any entry left at this address by a previous allocation.
+            table().insert(ptr.as_ptr() as usize, Entry { ptr, backtrace });
+        }
+    }
+
+    fn release(self) {
+        // `Arc::into_inner` needs a sized pointee, so watch the count through
+        // a `Weak` instead. It also keeps the memory, and so this address,
+        // from being reused until the entry is removed.
+        let weak = Arc::downgrade(&self);
+        drop(self);
+        // Once no strong reference is left, none can be made again,
+        // not even by upgrading a `ThinWeak`.
+        if weak.strong_count() == 0 {
+            table().remove(&(weak.as_ptr() as *const u8 as usize));
+        }
+    }
+
+    fn untrack(&self) {
+        table().remove(&(Arc::as_ptr(self) as *const u8 as usize));
+    }
+}
+
+impl<T: ?Sized> Traced for Box<T> {}
+impl<T: ?Sized> Traced for Rc<T> {}
+impl<T: ?Sized> Traced for Weak<T> {}
+impl<T: ?Sized> Traced for &T {}
+impl<T: ?Sized> Traced for NonNull<T> {}
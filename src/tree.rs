@@ -0,0 +1,158 @@
+//! Parent-linked trees of [`ThinArc`] nodes.
+//!
+//! Children own their slot in the parent's slice through a `ThinArc`,
+//! while each child's head holds a [`ParentLink`] back to its parent
+//! through a weak pointer, so the tree does not form a reference cycle.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use thin_dst::{*, tree::*};
+//! struct Head {
+//!     parent: ParentLink<Head, Node>,
+//!     value: u32,
+//! }
+//! #[derive(Clone)]
+//! struct Node(ThinArc<Head, Node>);
+//!
+//! let leaf = |value| Node(ThinArc::new(Head { parent: ParentLink::new(), value }, vec![]));
+//! let root = new_parent(
+//!     Head { parent: ParentLink::new(), value: 0 },
+//!     vec![leaf(1), leaf(2)],
+//!     |child| &child.0.head.parent,
+//! );
+//! let parent = root.slice[1].0.head.parent.get().unwrap();
+//! assert_eq!(parent.head.value, 0);
+//! ```
+
+use {
+    crate::{ThinArc, ThinData, ThinWeak},
+    alloc::sync::Weak,
+    core::{
+        cell::UnsafeCell,
+        fmt::{self, Debug},
+        mem::MaybeUninit,
+        sync::atomic::{AtomicU8, Ordering},
+    },
+};
+
+const UNSET: u8 = 0;
+const SETTING: u8 = 1;
+const SET: u8 = 2;
+
+/// A link to the parent of a tree node, which can be set once.
+///
+/// Holds a weak reference, so it does not keep the parent alive.
+pub struct ParentLink<Head, SliceItem> {
+    state: AtomicU8,
+    // NB: this holds a fat Weak rather than a ThinWeak, as the link is usually
+    //     dropped while the parent is being dropped, and releasing a ThinWeak
+    //     would need to read the length out of the parent while it's borrowed.
+    parent: UnsafeCell<MaybeUninit<Weak<ThinData<Head, SliceItem>>>>,
+}
+
+impl<Head, SliceItem> ParentLink<Head, SliceItem> {
+    /// Create a new link with no parent set.
+    pub const fn new() -> Self {
+        ParentLink {
+            state: AtomicU8::new(UNSET),
+            parent: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    fn weak(&self) -> Option<&Weak<ThinData<Head, SliceItem>>> {
+        if self.state.load(Ordering::Acquire) == SET {
+            Some(unsafe { &*(*self.parent.get()).as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Get the parent, if it has been set and is still alive.
+    pub fn get(&self) -> Option<ThinArc<Head, SliceItem>> {
+        self.weak()?.upgrade().map(Into::into)
+    }
+
+    /// Get a weak pointer to the parent, if it has been set.
+    pub fn get_weak(&self) -> Option<ThinWeak<Head, SliceItem>> {
+        self.weak().cloned().map(Into::into)
+    }
+
+    /// Set the parent, returning it back if one was already set.
+    fn set(
+        &self,
+        parent: Weak<ThinData<Head, SliceItem>>,
+    ) -> Result<(), Weak<ThinData<Head, SliceItem>>> {
+        match self
+            .state
+            .compare_exchange(UNSET, SETTING, Ordering::Acquire, Ordering::Relaxed)
+        {
+            Ok(_) => {
+                unsafe { (*self.parent.get()).as_mut_ptr().write(parent) };
+                self.state.store(SET, Ordering::Release);
+                Ok(())
+            }
+            Err(_) => Err(parent),
+        }
+    }
+}
+
+impl<Head, SliceItem> Default for ParentLink<Head, SliceItem> {
+    fn default() -> Self {
+        ParentLink::new()
+    }
+}
+
+impl<Head, SliceItem> Drop for ParentLink<Head, SliceItem> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() == SET {
+            unsafe { self.parent.get_mut().as_mut_ptr().drop_in_place() }
+        }
+    }
+}
+
+impl<Head, SliceItem> Debug for ParentLink<Head, SliceItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let is_set = self.weak().is_some();
+        f.debug_struct("ParentLink")
+            .field("is_set", &is_set)
+            .finish()
+    }
+}
+
+// SAFETY: the link only ever holds a Weak, which is only set once
+unsafe impl<Head, SliceItem> Send for ParentLink<Head, SliceItem> where
+    Weak<ThinData<Head, SliceItem>>: Send
+{
+}
+unsafe impl<Head, SliceItem> Sync for ParentLink<Head, SliceItem> where
+    Weak<ThinData<Head, SliceItem>>: Send + Sync
+{
+}
+
+/// Create a new parent node, and link each of its children back to it.
+///
+/// `link` projects each child to the `ParentLink` stored in its head.
+///
+/// # Panics
+///
+/// Panics if the children iterator incorrectly reports its length,
+/// or if any child already has a parent.
+pub fn new_parent<Head, SliceItem, I, F>(
+    head: Head,
+    children: I,
+    link: F,
+) -> ThinArc<Head, SliceItem>
+where
+    I: IntoIterator<Item = SliceItem>,
+    I::IntoIter: ExactSizeIterator, // + TrustedLen
+    F: Fn(&SliceItem) -> &ParentLink<Head, SliceItem>,
+{
+    let parent = ThinArc::new(head, children);
+    for child in &parent.slice {
+        if link(child).set(ThinArc::downgrade(&parent).into()).is_err() {
+            panic!("child already has a parent");
+        }
+    }
+    parent
+}
@@ -0,0 +1,57 @@
+use {
+    crate::{Len, ThinBox, ThinData},
+    core::{
+        alloc::Layout,
+        fmt::{self, Display},
+        mem, ptr,
+    },
+};
+
+/// Why a byte buffer does not hold a valid `ThinData` layout.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum ValidateError {
+    /// The buffer is not sufficiently aligned.
+    Misaligned,
+    /// The buffer is shorter than the layout it describes.
+    Truncated,
+    /// The stored length describes a layout too large to exist.
+    LenOverflow,
+}
+
+impl Display for ValidateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ValidateError::Misaligned => "buffer is misaligned",
+            ValidateError::Truncated => "buffer is truncated",
+            ValidateError::LenOverflow => "stored length overflows",
+        })
+    }
+}
+
+impl<Head, SliceItem> ThinData<Head, SliceItem> {
+    /// Check that `bytes` starts with the header and storage of a `ThinData`,
+    /// returning the layout it occupies.
+    ///
+    /// This only checks the layout math; it does not (and cannot)
+    /// check that the head and slice items are valid values.
+    /// It never panics, so it is suitable as a fuzzing entry point.
+    pub fn validate_layout(bytes: &[u8]) -> Result<Layout, ValidateError> {
+        let (empty, _) =
+            ThinBox::<Head, SliceItem>::layout(0).map_err(|_| ValidateError::LenOverflow)?;
+        if bytes.as_ptr() as usize & (empty.align() - 1) != 0 {
+            return Err(ValidateError::Misaligned);
+        }
+        if bytes.len() < mem::size_of::<Len>() {
+            return Err(ValidateError::Truncated);
+        }
+        let len = unsafe { ptr::read(bytes.as_ptr().cast::<Len>()) };
+        let len = len.try_get().ok_or(ValidateError::LenOverflow)?;
+        let (layout, _) =
+            ThinBox::<Head, SliceItem>::layout(len).map_err(|_| ValidateError::LenOverflow)?;
+        if bytes.len() < layout.size() {
+            return Err(ValidateError::Truncated);
+        }
+        Ok(layout)
+    }
+}
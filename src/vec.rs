@@ -0,0 +1,283 @@
+use {
+    crate::{
+        polyfill::{extend_layout, layout_array, make_slice, make_slice_mut, pad_layout_to_align},
+        ErasedPtr, InProgress, Len, ThinBox, ThinData,
+    },
+    alloc::alloc::{alloc, dealloc, handle_alloc_error, realloc, Layout, LayoutError},
+    core::{
+        cmp,
+        fmt::{self, Debug},
+        marker::PhantomData,
+        mem,
+        ops::{Deref, DerefMut},
+        ptr::{self, NonNull},
+    },
+};
+
+/// The start of a `ThinVec` allocation, followed by the slice items.
+///
+/// Storing the length and capacity as `Len` gives this the same alignment
+/// as the `ThinData` it becomes, and puts the length where `ThinData` keeps it.
+#[repr(C)]
+struct Header<Head> {
+    len: Len,
+    cap: Len,
+    head: Head,
+}
+
+/// A growable `ThinData`, with its length and capacity stored next to the head.
+///
+/// Like a `ThinBox`, this is a single pointer, but like a `Vec`, it has spare
+/// capacity to push into. Once built, [`into_thin_box`](Self::into_thin_box)
+/// shrinks the allocation to fit and reuses it as a `ThinBox`,
+/// so building a node incrementally takes no extra allocation.
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let mut vec = ThinVec::new("evens");
+/// for i in 0..5 {
+///     vec.push(i * 2);
+/// }
+/// assert_eq!(vec.pop(), Some(8));
+/// assert_eq!(*vec, [0, 2, 4, 6]);
+///
+/// let boxed: ThinBox<_, _> = vec.into_thin_box();
+/// assert_eq!(boxed.head, "evens");
+/// assert_eq!(boxed.slice, [0, 2, 4, 6]);
+/// ```
+pub struct ThinVec<Head, SliceItem> {
+    raw: NonNull<Header<Head>>,
+    marker: PhantomData<ThinBox<Head, SliceItem>>,
+}
+
+unsafe impl<Head: Send, SliceItem: Send> Send for ThinVec<Head, SliceItem> {}
+unsafe impl<Head: Sync, SliceItem: Sync> Sync for ThinVec<Head, SliceItem> {}
+
+impl<Head, SliceItem> ThinVec<Head, SliceItem> {
+    fn try_layout(cap: usize) -> Result<(Layout, usize), LayoutError> {
+        if cap > ThinData::<Head, SliceItem>::MAX_LEN {
+            // Only reachable for zero-sized items; otherwise the layout itself overflows.
+            return Err(layout_array::<u8>(usize::MAX).unwrap_err());
+        }
+        let header = Layout::new::<Header<Head>>();
+        let (layout, items_offset) = extend_layout(&header, layout_array::<SliceItem>(cap)?)?;
+        Ok((pad_layout_to_align(&layout), items_offset))
+    }
+
+    fn layout(cap: usize) -> (Layout, usize) {
+        Self::try_layout(cap).unwrap_or_else(|e| panic!("oversize vec: {}", e))
+    }
+
+    fn header(&self) -> &Header<Head> {
+        unsafe { self.raw.as_ref() }
+    }
+
+    fn items(&self) -> *mut SliceItem {
+        let (_, items_offset) = Self::layout(0);
+        unsafe { self.raw.as_ptr().cast::<u8>().add(items_offset).cast() }
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        (*self.raw.as_ptr()).len = Len::new(len);
+    }
+
+    /// Create a new, empty vector with the given head.
+    ///
+    /// This allocates, as the head is stored on the heap.
+    pub fn new(head: Head) -> Self {
+        ThinVec::with_capacity(head, 0)
+    }
+
+    /// Create a new, empty vector with the given head
+    /// and room for `capacity` slice items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation would exceed `isize::MAX` bytes.
+    pub fn with_capacity(head: Head, capacity: usize) -> Self {
+        let (layout, _) = Self::layout(capacity);
+        let raw =
+            unsafe { NonNull::new(alloc(layout)).unwrap_or_else(|| handle_alloc_error(layout)) };
+        let raw = raw.cast::<Header<Head>>();
+        unsafe {
+            ptr::write(
+                raw.as_ptr(),
+                Header {
+                    len: Len::new(0),
+                    cap: Len::new(capacity),
+                    head,
+                },
+            );
+        }
+        ThinVec {
+            raw,
+            marker: PhantomData,
+        }
+    }
+
+    /// The number of slice items.
+    pub fn len(&self) -> usize {
+        self.header().len.get()
+    }
+
+    /// Whether there are no slice items.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of slice items there is room for without reallocating.
+    pub fn capacity(&self) -> usize {
+        if mem::size_of::<SliceItem>() == 0 {
+            ThinData::<Head, SliceItem>::MAX_LEN
+        } else {
+            self.header().cap.get()
+        }
+    }
+
+    /// Borrow the head.
+    pub fn head(&self) -> &Head {
+        &self.header().head
+    }
+
+    /// Mutably borrow the head.
+    pub fn head_mut(&mut self) -> &mut Head {
+        unsafe { &mut (*self.raw.as_ptr()).head }
+    }
+
+    /// Make room for at least `additional` more slice items.
+    ///
+    /// Like `Vec::reserve`, this may reserve more, to make repeated pushes cheap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation would exceed `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        let len = self.len();
+        if additional <= self.capacity() - len {
+            return;
+        }
+        let required = len
+            .checked_add(additional)
+            .unwrap_or_else(|| panic!("capacity overflow"));
+        let cap = cmp::max(self.capacity().saturating_mul(2), 4)
+            .min(ThinData::<Head, SliceItem>::MAX_LEN)
+            .max(required);
+        let (old_layout, _) = Self::layout(self.capacity());
+        let (layout, _) = Self::layout(cap);
+        let raw = unsafe { realloc(self.raw.as_ptr().cast(), old_layout, layout.size()) };
+        self.raw = NonNull::new(raw)
+            .unwrap_or_else(|| handle_alloc_error(layout))
+            .cast();
+        unsafe { (*self.raw.as_ptr()).cap = Len::new(cap) };
+    }
+
+    /// Append a slice item, growing the allocation if it is full.
+    pub fn push(&mut self, item: SliceItem) {
+        let len = self.len();
+        if len == self.capacity() {
+            self.reserve(1);
+        }
+        unsafe {
+            self.items().add(len).write(item);
+            self.set_len(len + 1);
+        }
+    }
+
+    /// Remove the last slice item, if there is one.
+    pub fn pop(&mut self) -> Option<SliceItem> {
+        let len = self.len().checked_sub(1)?;
+        unsafe {
+            self.set_len(len);
+            Some(self.items().add(len).read())
+        }
+    }
+
+    /// Move the head and slice items into a `ThinBox`.
+    ///
+    /// The items are moved down over the vector's header within the allocation,
+    /// which is then shrunk to fit, so this doesn't allocate.
+    pub fn into_thin_box(self) -> ThinBox<Head, SliceItem> {
+        let this = mem::ManuallyDrop::new(self);
+        let len = this.len();
+        let (old_layout, _) = Self::layout(this.capacity());
+        let (layout, [_, head_offset, slice_offset]) = InProgress::<Head, SliceItem>::layout(len);
+        debug_assert_eq!(layout.align(), old_layout.align());
+        let raw = this.raw.as_ptr().cast::<u8>();
+        unsafe {
+            let head = ptr::read(&(*this.raw.as_ptr()).head);
+            // The destination starts no later than the source, but may overlap it.
+            ptr::copy(this.items(), raw.add(slice_offset).cast(), len);
+            ptr::write(raw.add(head_offset).cast(), head);
+            ptr::write(raw.cast(), Len::new(len));
+            let raw = realloc(raw, old_layout, layout.size());
+            let raw: ErasedPtr = NonNull::new(raw)
+                .unwrap_or_else(|| handle_alloc_error(layout))
+                .cast();
+            ThinBox::from_erased(raw)
+        }
+    }
+}
+
+impl<Head, SliceItem> Drop for ThinVec<Head, SliceItem> {
+    fn drop(&mut self) {
+        /// Frees the allocation, even if the head or an item panics while dropping.
+        struct Dealloc(*mut u8, Layout);
+        impl Drop for Dealloc {
+            fn drop(&mut self) {
+                unsafe { dealloc(self.0, self.1) };
+            }
+        }
+
+        /// Drops the items after the head, even if the head panics.
+        struct DropItems<SliceItem>(*mut [SliceItem]);
+        impl<SliceItem> Drop for DropItems<SliceItem> {
+            fn drop(&mut self) {
+                unsafe { ptr::drop_in_place(self.0) };
+            }
+        }
+
+        let (layout, _) = Self::layout(self.capacity());
+        let _dealloc = Dealloc(self.raw.as_ptr().cast(), layout);
+        let _items;
+        unsafe {
+            _items = DropItems(make_slice_mut(self.items(), self.len()));
+            ptr::drop_in_place(self.head_mut());
+        }
+    }
+}
+
+impl<Head, SliceItem> Deref for ThinVec<Head, SliceItem> {
+    type Target = [SliceItem];
+    fn deref(&self) -> &[SliceItem] {
+        unsafe { &*make_slice(self.items(), self.len()) }
+    }
+}
+
+impl<Head, SliceItem> DerefMut for ThinVec<Head, SliceItem> {
+    fn deref_mut(&mut self) -> &mut [SliceItem] {
+        unsafe { &mut *make_slice_mut(self.items(), self.len()) }
+    }
+}
+
+impl<Head, SliceItem> Extend<SliceItem> for ThinVec<Head, SliceItem> {
+    fn extend<I: IntoIterator<Item = SliceItem>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|item| self.push(item));
+    }
+}
+
+impl<Head, SliceItem> Debug for ThinVec<Head, SliceItem>
+where
+    Head: Debug,
+    SliceItem: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinVec")
+            .field("head", self.head())
+            .field("slice", &&**self)
+            .finish()
+    }
+}
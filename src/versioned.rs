@@ -0,0 +1,194 @@
+use {
+    crate::{ErasedPtr, ThinArc},
+    core::{
+        arch::asm,
+        cell::UnsafeCell,
+        fmt::{self, Debug},
+        marker::PhantomData,
+        ptr::NonNull,
+    },
+};
+
+/// A snapshot of a [`VersionedThinArcCell`]: the stored pointer and its version.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Versioned {
+    /// The stored `ThinArc`, erased, or `None` if the cell is empty.
+    pub ptr: Option<ErasedPtr>,
+    /// The number of successful exchanges on the cell, wrapping on overflow.
+    pub version: usize,
+}
+
+/// The pointer and version, laid out as one 16 byte word for `cmpxchg16b`.
+#[repr(C, align(16))]
+struct Pair {
+    ptr: usize,
+    version: usize,
+}
+
+/// Atomically compare `*dst` with `old` and replace it with `new` if equal.
+///
+/// Returns the value seen in `*dst` and whether it was replaced.
+///
+/// # Safety
+///
+/// `dst` must be valid for writes.
+unsafe fn cmpxchg16b(
+    dst: *mut Pair,
+    old: (usize, usize),
+    new: (usize, usize),
+) -> ((usize, usize), bool) {
+    let (prev_lo, prev_hi);
+    let replaced: usize;
+    // `rbx` is reserved by LLVM, so the low half of `new` is swapped in and out of it.
+    asm!(
+        "xchg {rbx_tmp}, rbx",
+        "lock cmpxchg16b xmmword ptr [{dst}]",
+        "sete cl",
+        "mov rbx, {rbx_tmp}",
+        dst = in(reg) dst,
+        rbx_tmp = inout(reg) new.0 => _,
+        inout("rcx") new.1 => replaced,
+        inout("rax") old.0 => prev_lo,
+        inout("rdx") old.1 => prev_hi,
+        options(nostack),
+    );
+    // `sete` only writes `cl`, the low byte of `rcx`.
+    ((prev_lo, prev_hi), replaced as u8 != 0)
+}
+
+fn encode(ptr: Option<ErasedPtr>) -> usize {
+    ptr.map_or(0, |ptr| ptr.as_ptr() as usize)
+}
+
+fn decode(ptr: usize) -> Option<ErasedPtr> {
+    NonNull::new(ptr as *mut _)
+}
+
+/// An atomic `Option<ThinArc>` paired with a version counter, for lock-free
+/// structures that need to rule out the ABA problem.
+///
+/// Every successful [`compare_exchange`](Self::compare_exchange) bumps the
+/// version, and both halves are compared and exchanged together with a
+/// double-word `cmpxchg16b`. A pointer that was removed and put back in the
+/// meantime thus no longer matches an earlier snapshot.
+///
+/// This type is only available on `x86_64` targets with the `cmpxchg16b`
+/// target feature enabled (e.g. with `-C target-cpu=native` or
+/// `-C target-feature=+cmpxchg16b`). All operations are sequentially consistent;
+/// for loads with a chosen memory ordering, use a [`ThinArcCell`](crate::ThinArcCell).
+///
+/// The cell owns the `ThinArc` stored in it. Reading through the pointer of a
+/// [`Versioned`] snapshot is only sound while something else keeps it alive,
+/// such as the reclaimer behind [`ThinArc::retire`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use thin_dst::*;
+/// let a = ThinArc::new("a", vec![1]);
+/// let cell = VersionedThinArcCell::new(Some(a.clone()));
+/// let seen = cell.load();
+///
+/// // Meanwhile, `a` is removed and put back...
+/// let taken = cell.compare_exchange(seen, None).unwrap();
+/// let now = cell.load();
+/// assert!(cell.compare_exchange(now, taken).is_ok());
+///
+/// // ...so the stale snapshot no longer matches, even though the pointer does.
+/// assert_eq!(cell.load().ptr, seen.ptr);
+/// assert!(cell.compare_exchange(seen, None).is_err());
+/// ```
+pub struct VersionedThinArcCell<Head, SliceItem> {
+    pair: UnsafeCell<Pair>,
+    marker: PhantomData<ThinArc<Head, SliceItem>>,
+}
+
+unsafe impl<Head, SliceItem> Send for VersionedThinArcCell<Head, SliceItem> where
+    ThinArc<Head, SliceItem>: Send
+{
+}
+unsafe impl<Head, SliceItem> Sync for VersionedThinArcCell<Head, SliceItem> where
+    ThinArc<Head, SliceItem>: Send + Sync
+{
+}
+
+impl<Head, SliceItem> VersionedThinArcCell<Head, SliceItem> {
+    /// Create a new cell holding `value`, at version 0.
+    pub fn new(value: Option<ThinArc<Head, SliceItem>>) -> Self {
+        VersionedThinArcCell {
+            pair: UnsafeCell::new(Pair {
+                ptr: encode(value.map(ThinArc::erase)),
+                version: 0,
+            }),
+            marker: PhantomData,
+        }
+    }
+
+    /// Load the current pointer and version.
+    pub fn load(&self) -> Versioned {
+        // Exchanging zero with itself is a no-op, so either way this reads the pair atomically.
+        let ((ptr, version), _) = unsafe { cmpxchg16b(self.pair.get(), (0, 0), (0, 0)) };
+        Versioned {
+            ptr: decode(ptr),
+            version,
+        }
+    }
+
+    /// Store `new` if the cell still holds `current`, bumping the version.
+    ///
+    /// On success, returns the `ThinArc` that was stored. On failure, returns
+    /// `new` back along with what the cell holds instead.
+    #[allow(clippy::type_complexity)]
+    pub fn compare_exchange(
+        &self,
+        current: Versioned,
+        new: Option<ThinArc<Head, SliceItem>>,
+    ) -> Result<Option<ThinArc<Head, SliceItem>>, (Option<ThinArc<Head, SliceItem>>, Versioned)>
+    {
+        let old = (encode(current.ptr), current.version);
+        let new_ptr = encode(new.as_ref().map(|arc| arc.raw));
+        let ((ptr, version), replaced) = unsafe {
+            cmpxchg16b(
+                self.pair.get(),
+                old,
+                (new_ptr, current.version.wrapping_add(1)),
+            )
+        };
+        if replaced {
+            // The cell now owns `new`, and handed over ownership of the old pointer.
+            core::mem::forget(new);
+            Ok(decode(ptr).map(|ptr| unsafe { ThinArc::from_erased(ptr) }))
+        } else {
+            Err((
+                new,
+                Versioned {
+                    ptr: decode(ptr),
+                    version,
+                },
+            ))
+        }
+    }
+
+    /// Take the stored `ThinArc` out of the cell.
+    pub fn into_inner(self) -> Option<ThinArc<Head, SliceItem>> {
+        let this = core::mem::ManuallyDrop::new(self);
+        let ptr = unsafe { (*this.pair.get()).ptr };
+        decode(ptr).map(|ptr| unsafe { ThinArc::from_erased(ptr) })
+    }
+}
+
+impl<Head, SliceItem> Drop for VersionedThinArcCell<Head, SliceItem> {
+    fn drop(&mut self) {
+        if let Some(ptr) = decode(self.pair.get_mut().ptr) {
+            drop(unsafe { ThinArc::<Head, SliceItem>::from_erased(ptr) });
+        }
+    }
+}
+
+impl<Head, SliceItem> Debug for VersionedThinArcCell<Head, SliceItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VersionedThinArcCell")
+            .field("current", &self.load())
+            .finish()
+    }
+}
@@ -0,0 +1,104 @@
+//! Non-recursive traversal of recursive thin trees.
+//!
+//! The usual shape of a thin tree is `struct Node(ThinArc<Head, Node>)`,
+//! where a node's children are its slice. Traversing such a tree recursively
+//! can overflow the stack for deep trees, so these walkers keep an explicit
+//! stack on the heap instead.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use thin_dst::{*, walk::*};
+//! struct Node(ThinArc<u32, Node>);
+//!
+//! impl Children for Node {
+//!     fn children(&self) -> &[Node] {
+//!         &self.0.slice
+//!     }
+//! }
+//!
+//! let leaf = |value| Node(ThinArc::new(value, vec![]));
+//! let tree = Node(ThinArc::new(0, vec![
+//!     Node(ThinArc::new(1, vec![leaf(2), leaf(3)])),
+//!     leaf(4),
+//! ]));
+//!
+//! let pre: Vec<u32> = pre_order(&tree).map(|node| node.0.head).collect();
+//! assert_eq!(pre, [0, 1, 2, 3, 4]);
+//! let post: Vec<u32> = post_order(&tree).map(|node| node.0.head).collect();
+//! assert_eq!(post, [2, 3, 1, 4, 0]);
+//! ```
+
+use {
+    alloc::{vec, vec::Vec},
+    core::{iter::FusedIterator, slice},
+};
+
+/// A projection from a tree node to its children.
+pub trait Children: Sized {
+    /// The children of this node, in order.
+    fn children(&self) -> &[Self];
+}
+
+/// Visit the tree rooted at `root` in pre-order (parents before children).
+pub fn pre_order<Node: Children>(root: &Node) -> PreOrder<'_, Node> {
+    PreOrder {
+        stack: vec![slice::from_ref(root).iter()],
+    }
+}
+
+/// Visit the tree rooted at `root` in post-order (children before parents).
+pub fn post_order<Node: Children>(root: &Node) -> PostOrder<'_, Node> {
+    PostOrder {
+        stack: vec![(root, root.children().iter())],
+    }
+}
+
+/// A pre-order iterator over a tree. See [`pre_order`].
+#[derive(Debug, Clone)]
+pub struct PreOrder<'a, Node> {
+    stack: Vec<slice::Iter<'a, Node>>,
+}
+
+impl<'a, Node: Children> Iterator for PreOrder<'a, Node> {
+    type Item = &'a Node;
+
+    fn next(&mut self) -> Option<&'a Node> {
+        loop {
+            let siblings = self.stack.last_mut()?;
+            match siblings.next() {
+                Some(node) => {
+                    self.stack.push(node.children().iter());
+                    return Some(node);
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+impl<Node: Children> FusedIterator for PreOrder<'_, Node> {}
+
+/// A post-order iterator over a tree. See [`post_order`].
+#[derive(Debug, Clone)]
+pub struct PostOrder<'a, Node> {
+    stack: Vec<(&'a Node, slice::Iter<'a, Node>)>,
+}
+
+impl<'a, Node: Children> Iterator for PostOrder<'a, Node> {
+    type Item = &'a Node;
+
+    fn next(&mut self) -> Option<&'a Node> {
+        loop {
+            let (_, children) = self.stack.last_mut()?;
+            match children.next() {
+                Some(child) => self.stack.push((child, child.children().iter())),
+                None => return self.stack.pop().map(|(node, _)| node),
+            }
+        }
+    }
+}
+
+impl<Node: Children> FusedIterator for PostOrder<'_, Node> {}
@@ -0,0 +1,173 @@
+//! Handles for passing thin pointers across the JavaScript boundary.
+//!
+//! JavaScript can't hold a Rust pointer directly; instead, the Rust side keeps
+//! the pointers in a [`Registry`] and hands out [`Handle`]s, which round-trip
+//! through JavaScript as numbers. A stale or forged handle is detected on use
+//! rather than being treated as a pointer.
+//!
+//! Generic functions can't be exported to JavaScript, so export monomorphic
+//! functions that look the handle up in a registry for your node type.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use thin_dst::{*, wasm::*};
+//! use std::cell::RefCell;
+//!
+//! thread_local! {
+//!     static NODES: RefCell<Registry<ThinArc<u32, u8>>> = RefCell::new(Registry::new());
+//! }
+//!
+//! // #[wasm_bindgen]
+//! pub fn node_head(node: f64) -> Option<u32> {
+//!     let node = Handle::from_f64(node)?;
+//!     NODES.with(|nodes| Some(nodes.borrow().get(node)?.head))
+//! }
+//!
+//! // #[wasm_bindgen]
+//! pub fn node_release(node: f64) {
+//!     if let Some(node) = Handle::from_f64(node) {
+//!         NODES.with(|nodes| nodes.borrow_mut().remove(node));
+//!     }
+//! }
+//!
+//! let node = NODES.with(|nodes| nodes.borrow_mut().insert(ThinArc::new(7, vec![])));
+//! let node = node.into_f64();
+//! assert_eq!(node_head(node), Some(7));
+//! node_release(node);
+//! assert_eq!(node_head(node), None);
+//! ```
+
+use {alloc::vec::Vec, core::convert::TryFrom, wasm_bindgen::JsValue};
+
+/// The number of generation bits kept in a handle, such that
+/// a handle fits in the 53-bit integer range of a JavaScript number.
+const GENERATION_BITS: u32 = 21;
+const GENERATION_MASK: u32 = (1 << GENERATION_BITS) - 1;
+
+/// A generational handle to a value in a [`Registry`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+impl Handle {
+    /// Encode this handle as a number that JavaScript can represent exactly.
+    pub fn into_f64(self) -> f64 {
+        (u64::from(self.generation) << 32 | u64::from(self.index)) as f64
+    }
+
+    /// Decode a handle from a number, returning `None` if it isn't a valid handle encoding.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        let bits = value as u64;
+        if bits as f64 != value || bits >> 32 > u64::from(GENERATION_MASK) {
+            return None;
+        }
+        Some(Handle {
+            index: bits as u32,
+            generation: (bits >> 32) as u32,
+        })
+    }
+}
+
+impl From<Handle> for JsValue {
+    fn from(handle: Handle) -> JsValue {
+        JsValue::from_f64(handle.into_f64())
+    }
+}
+
+impl TryFrom<JsValue> for Handle {
+    type Error = JsValue;
+    fn try_from(value: JsValue) -> Result<Self, JsValue> {
+        match value.as_f64().and_then(Handle::from_f64) {
+            Some(handle) => Ok(handle),
+            None => Err(value),
+        }
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// A table of values (typically `ThinArc`s) addressed by [`Handle`].
+pub struct Registry<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Registry {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+impl<T> Registry<T> {
+    /// Create a new, empty registry.
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// The number of values in the registry.
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Does the registry hold no values?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Store a value, returning the handle to retrieve it by.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the registry already holds `u32::MAX` slots.
+    pub fn insert(&mut self, value: T) -> Handle {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                assert!(self.slots.len() < u32::MAX as usize, "Registry is full");
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: None,
+                });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.value = Some(value);
+        Handle {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    fn slot(&self, handle: Handle) -> Option<&Slot<T>> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+    }
+
+    /// Look up a value, returning `None` if the handle is stale.
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.slot(handle)?.value.as_ref()
+    }
+
+    /// Remove a value, returning `None` if the handle is stale.
+    ///
+    /// This is the drop hook: dropping the returned value releases it.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        self.slot(handle)?;
+        let slot = &mut self.slots[handle.index as usize];
+        let value = slot.value.take()?;
+        slot.generation = (slot.generation + 1) & GENERATION_MASK;
+        self.free.push(handle.index);
+        Some(value)
+    }
+}
@@ -43,3 +43,253 @@ fn test_thinbox() {
     use thin_dst::ThinBox;
     test_box(|leaker, panicker| ThinBox::new(leaker, std::iter::once(panicker)));
 }
+
+#[test]
+fn test_thinbox_head_first() {
+    use thin_dst::ThinBox;
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let head = leak_detector.clone();
+
+    std::panic::catch_unwind(move || {
+        let items = [PanicsOnClone, PanicsOnClone];
+        ThinBox::new_head_first(head, items.iter().cloned())
+    })
+    .expect_err("PanicsOnClone didn't panic");
+
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thinbox_head_result() {
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let items = vec![leak_detector.clone(), leak_detector.clone()];
+    let result = thin_dst::ThinBox::<(), _>::new_with_head_result(items, |_| Err(()));
+    assert!(result.is_err());
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn test_thinbox_stream_cancelled() {
+    use std::{
+        future::Future,
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+    use thin_dst::ThinBox;
+
+    /// Yields one item, then never another.
+    struct Stalls(Option<DontLeakMe>);
+    impl futures_core::Stream for Stalls {
+        type Item = DontLeakMe;
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<DontLeakMe>> {
+            match self.0.take() {
+                Some(item) => Poll::Ready(Some(item)),
+                None => Poll::Pending,
+            }
+        }
+    }
+
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let stream = Stalls(Some(leak_detector.clone()));
+    {
+        let mut fut = pin!(ThinBox::from_stream(leak_detector.clone(), 2, stream));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+    }
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thinbox_map_items_in_place() {
+    use thin_dst::ThinBox;
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let items: Vec<_> = (0..4).map(|_| leak_detector.clone()).collect();
+    let boxed = ThinBox::new(leak_detector.clone(), items);
+
+    std::panic::catch_unwind(move || {
+        let mut count = 0;
+        ThinBox::map_items_in_place(boxed, |item| {
+            count += 1;
+            if count == 3 {
+                panic!("mapping panicked");
+            }
+            item
+        })
+    })
+    .expect_err("mapping didn't panic");
+
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thinbox_builder() {
+    use thin_dst::ThinBox;
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let mut builder = ThinBox::with_capacity(leak_detector.clone(), 4);
+    for slot in &mut builder.spare_capacity_mut()[..2] {
+        slot.write(leak_detector.clone());
+    }
+    unsafe { builder.set_len(2) };
+    drop(builder);
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thinbox_zip_with() {
+    use thin_dst::ThinBox;
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let items = vec![leak_detector.clone(); 3];
+
+    std::panic::catch_unwind(|| {
+        ThinBox::zip_with(leak_detector.clone(), &items, &[0, 1, 2], |item, &i| {
+            if i == 2 {
+                panic!("zipping panicked");
+            }
+            item.clone()
+        })
+    })
+    .expect_err("zipping didn't panic");
+
+    drop(items);
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thinarc_debug_panics() {
+    use std::fmt;
+    use thin_dst::ThinArc;
+    struct PanicsOnDebug;
+    impl fmt::Debug for PanicsOnDebug {
+        fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+            panic!("PanicsOnDebug panicking on debug");
+        }
+    }
+
+    let arc = ThinArc::new(PanicsOnDebug, vec![0u8]);
+    let shared = arc.clone();
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| format!("{:?}", shared)))
+        .expect_err("PanicsOnDebug didn't panic");
+    drop(shared);
+    assert!(ThinArc::try_unwrap(arc).is_ok());
+}
+
+#[test]
+fn test_thinbox_new_with_len_short() {
+    use thin_dst::ThinBox;
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let items = vec![leak_detector.clone(); 2];
+
+    std::panic::catch_unwind(|| ThinBox::new_with_len(leak_detector.clone(), 3, items))
+        .expect_err("short iterator didn't panic");
+
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thin_bytes_mut_split() {
+    use thin_dst::ThinBytesMut;
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let mut buf = ThinBytesMut::new(leak_detector.clone(), vec![0u8; 4]);
+    let front = buf.split_to(1).freeze();
+    let back = buf.split_off(1);
+    drop((buf, front.clone()));
+    drop((front, back));
+
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thinbox_drop_incremental_abandoned() {
+    use thin_dst::ThinBox;
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let boxed = ThinBox::new(leak_detector.clone(), vec![leak_detector.clone(); 5]);
+    let mut dropping = ThinBox::drop_incremental(boxed);
+    dropping.step(2);
+    drop(dropping);
+
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thin_vec_dropped() {
+    use thin_dst::ThinVec;
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let mut vec = ThinVec::new(leak_detector.clone());
+    vec.extend(vec![leak_detector.clone(); 5]);
+    drop(vec.pop());
+    drop(vec);
+
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_frozen_arena_shared() {
+    use thin_dst::{HeadWithId, ThinArena, ThinId};
+    struct Node(ThinId, DontLeakMe);
+    impl HeadWithId for Node {
+        fn id(&self) -> ThinId {
+            self.0
+        }
+    }
+
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let mut arena = ThinArena::new();
+    let a = arena.insert_with(
+        |id| Node(id, leak_detector.clone()),
+        vec![leak_detector.clone(); 3],
+    );
+    arena.insert_with(|id| Node(id, leak_detector.clone()), vec![]);
+    arena.remove(a);
+    let frozen = arena.freeze();
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let frozen = frozen.clone();
+            std::thread::spawn(move || drop(frozen))
+        })
+        .collect();
+    drop(frozen);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thinbox_drop_incremental_head_panics() {
+    use thin_dst::ThinBox;
+    struct PanicsOnDrop;
+    impl Drop for PanicsOnDrop {
+        fn drop(&mut self) {
+            panic!("PanicsOnDrop panicking on drop");
+        }
+    }
+
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let boxed = ThinBox::new(PanicsOnDrop, vec![leak_detector.clone(); 5]);
+    let dropping = ThinBox::drop_incremental(boxed);
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || drop(dropping)))
+        .expect_err("PanicsOnDrop didn't panic");
+
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
+
+#[test]
+fn test_thin_vec_head_panics() {
+    use thin_dst::ThinVec;
+    struct PanicsOnDrop;
+    impl Drop for PanicsOnDrop {
+        fn drop(&mut self) {
+            panic!("PanicsOnDrop panicking on drop");
+        }
+    }
+
+    let mut leak_detector = DontLeakMe(Arc::new(()));
+    let mut vec = ThinVec::new(PanicsOnDrop);
+    vec.extend(vec![leak_detector.clone(); 5]);
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || drop(vec)))
+        .expect_err("PanicsOnDrop didn't panic");
+
+    assert!(Arc::get_mut(&mut leak_detector.0).is_some());
+}
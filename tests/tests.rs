@@ -10,6 +10,7 @@ fn slice() {
     let slice: Vec<u32> = vec![0, 1, 2, 3, 4, 5];
     let slice: ThinBox<(), u32> = ThinBox::new((), slice);
     assert_eq!(slice.slice, [0, 1, 2, 3, 4, 5]);
+    slice.prefetch_slice();
     let slice = slice.clone();
 }
 
@@ -52,3 +53,1407 @@ fn node() {
     let boxed = Node::new(children.iter().map(|node| node.data()).sum(), children);
     dbg!(boxed);
 }
+
+#[test]
+fn erased_table() {
+    let table: Vec<ErasedPtr> = (0..4)
+        .map(|i| ThinBox::erase(ThinBox::new(i, vec![0u8; i])))
+        .collect();
+    let refs: &[ThinRef<usize, u8>] = unsafe { ThinRef::from_erased_slice(&table) };
+    for (i, node) in refs.iter().enumerate() {
+        assert_eq!(node.head, i);
+        assert_eq!(node.slice.len(), i);
+        let fat: FatThinRef<usize, u8> = (*node).into();
+        assert_eq!(fat.slice.len(), i);
+    }
+    for ptr in table {
+        drop(unsafe { ThinBox::<usize, u8>::from_erased(ptr) });
+    }
+}
+
+#[test]
+fn small() {
+    let inline: SmallThinBox<(), String, 2> = SmallThinBox::new((), vec!["a".into()]);
+    assert!(SmallThinBox::is_inline(&inline));
+    assert_eq!(inline.slice, ["a"]);
+    let heap: SmallThinBox<(), String, 2> = SmallThinBox::new((), vec![String::new(); 3]);
+    assert!(!SmallThinBox::is_inline(&heap));
+    let inline = inline.clone();
+}
+
+#[test]
+fn pool() {
+    let mut pool = Pool::new();
+    let a: ThinBox<u32, String> = pool.new_box(1, vec![String::from("a"); 4]);
+    pool.recycle(a);
+    assert_eq!(pool.len(), 1);
+    let b: ThinBox<u32, String> = pool.new_box(2, vec![String::from("b"); 4]);
+    assert!(pool.is_empty());
+    assert_eq!(b.head, 2);
+    pool.recycle(b);
+
+    let many: Vec<ThinBox<u32, u8>> = ThinBox::new_many((0..3).map(|i| (i, vec![0; 8])));
+    assert_eq!(many[2].head, 2);
+    many.into_iter().for_each(|b| pool.recycle(b));
+    assert_eq!(pool.len(), 4);
+    let many: Vec<ThinBox<u32, u8>> = pool.new_many((0..3).map(|i| (i, vec![1; 8])));
+    assert_eq!(pool.len(), 1);
+    assert!(many.iter().all(|b| b.slice == [1; 8]));
+}
+
+#[test]
+fn fixed() {
+    let mut fixed = ThinFixed::new(1u16, [1u32, 2, 3]);
+    let data: &mut ThinData<u16, u32> = (&mut fixed).into();
+    data.slice[0] = 0;
+    assert_eq!(data.slice, [0, 2, 3]);
+    assert!(data.try_as_fixed::<2>().is_none());
+    assert_eq!(
+        data.try_as_fixed::<3>(),
+        Some(&ThinFixed::new(1, [0, 2, 3]))
+    );
+
+    let boxed = ThinBox::new(1u16, vec![0u32, 2, 3]);
+    assert_eq!(boxed.try_as_fixed::<3>(), Some(&fixed));
+    assert_eq!(
+        format!("{:?}", fixed),
+        "ThinFixed { head: 1, slice: [0, 2, 3] }"
+    );
+}
+
+#[test]
+fn arena() {
+    struct Head(ThinId);
+    impl HeadWithId for Head {
+        fn id(&self) -> ThinId {
+            self.0
+        }
+    }
+
+    let mut arena = ThinArena::new();
+    let a = arena.insert_with(Head, vec![1, 2, 3]);
+    let b = arena.insert_with(Head, vec![4]);
+    assert_eq!(arena.get(a).unwrap().slice, [1, 2, 3]);
+    assert!(arena.remove(a).is_some());
+    assert!(arena.get(a).is_none());
+    let c = arena.insert_with(Head, vec![]);
+    assert_eq!(c.id().index, a.id().index);
+    assert!(arena.get(a).is_none());
+    assert_eq!(ThinArena::key_of(arena.get(b).unwrap()), b);
+    assert_eq!(arena.len(), 2);
+
+    let frozen = arena.freeze();
+    assert_eq!(frozen.len(), 2);
+    assert!(frozen.get(a).is_none());
+    assert_eq!(frozen.get(b).unwrap().1, [4]);
+    assert!(frozen.get(c).unwrap().1.is_empty());
+    let keys: Vec<_> = frozen.clone().iter().map(|(key, _, _)| key).collect();
+    assert_eq!(keys, [c, b]);
+    assert!(ThinArena::<Head, u8>::new().freeze().is_empty());
+}
+
+#[test]
+fn drop_deep() {
+    struct Deep(ThinBox<String, Deep>);
+    let mut node = Deep(ThinBox::new(String::new(), vec![]));
+    for i in 0..100 {
+        node = Deep(ThinBox::new(i.to_string(), vec![node]));
+    }
+    graph::drop_deep(node.0, |child| Some(child.0));
+}
+
+#[test]
+fn try_clone() {
+    let arc = ThinArc::new((), vec![1, 2, 3]);
+    let clone = ThinArc::try_clone(&arc).unwrap();
+    assert_eq!(ThinArc::strong_count(&arc), 2);
+    let rc = ThinRc::new((), vec![1, 2, 3]);
+    assert!(ThinRc::try_clone(&rc).is_some());
+}
+
+#[test]
+#[cfg(feature = "fuzz")]
+fn validate_layout() {
+    let boxed = ThinBox::new(0u32, vec![1u16, 2, 3]);
+    let size = std::alloc::Layout::for_value::<ThinData<u32, u16>>(&boxed).size();
+    let ptr = ThinBox::erase(boxed);
+    let bytes = unsafe { std::slice::from_raw_parts(ptr.as_ptr().cast::<u8>(), size) };
+    assert!(ThinData::<u32, u16>::validate_layout(bytes).is_ok());
+    assert_eq!(
+        ThinData::<u32, u16>::validate_layout(&bytes[..size - 1]),
+        Err(ValidateError::Truncated)
+    );
+    assert_eq!(
+        ThinData::<u32, u16>::validate_layout(&bytes[1..]),
+        Err(ValidateError::Misaligned)
+    );
+    drop(unsafe { ThinBox::<u32, u16>::from_erased(ptr) });
+}
+
+#[test]
+fn aligned_chunks() {
+    let boxed = ThinBox::new((), 0..37u32);
+    let (prefix, chunks, suffix) = boxed.slice_aligned_chunks::<4>();
+    assert_eq!(prefix.len() + chunks.len() * 4 + suffix.len(), 37);
+    assert!(prefix.len() < 4 && suffix.len() < 4);
+    assert_eq!(chunks.as_ptr() as usize % 16, 0);
+    let (_, chunks, _) = boxed.slice_aligned_chunks::<3>();
+    assert!(chunks
+        .iter()
+        .all(|chunk| (chunk.as_ptr() as usize).is_multiple_of(4)));
+    let (prefix, chunks, suffix) = boxed.slice_aligned_chunks::<0>();
+    assert_eq!((prefix.len(), chunks.len(), suffix.len()), (37, 0, 0));
+}
+
+#[test]
+fn retype_head() {
+    let boxed = ThinBox::new(1u32, vec![String::from("a"), String::from("b")]);
+    let boxed: ThinBox<i32, String> = ThinBox::retype_head(boxed, |head| -(head as i32));
+    assert_eq!(boxed.head, -1);
+    assert_eq!(boxed.slice, ["a", "b"]);
+}
+
+#[test]
+fn codec() {
+    use thin_dst::codec::*;
+    let arc = ThinArc::new(true, vec![-1i32, 2]);
+    let mut buf = [0; 17];
+    let mut sink = &mut buf[..];
+    arc.encode(&mut sink).unwrap();
+    assert!(sink.is_empty());
+    assert_eq!(
+        arc.encode(&mut &mut buf[..16]),
+        Err(EncodeError::BufferFull)
+    );
+
+    let mut input = &buf[..];
+    let decoded = ThinRc::<bool, i32>::decode(&mut input).unwrap();
+    assert!(input.is_empty());
+    assert_eq!(*decoded, *arc);
+    assert_eq!(
+        ThinBox::<bool, i32>::decode(&mut &buf[..16]),
+        Err(DecodeError::UnexpectedEnd)
+    );
+    buf[8] = 2;
+    assert_eq!(
+        ThinBox::<bool, i32>::decode(&mut &buf[..]),
+        Err(DecodeError::Invalid)
+    );
+
+    // Zero-sized items take no input, so their count is limited instead.
+    let mut huge = (isize::MAX as u64).to_le_bytes().to_vec();
+    huge.push(0);
+    assert_eq!(
+        ThinBox::<u8, ()>::decode(&mut &huge[..]),
+        Err(DecodeError::TooLong)
+    );
+    let mut units = Vec::new();
+    ThinBox::new(0u8, vec![(); 3]).encode(&mut units).unwrap();
+    assert_eq!(
+        ThinBox::<u8, ()>::decode_with_limit(&mut &units[..], 2),
+        Err(DecodeError::TooLong)
+    );
+    assert_eq!(
+        ThinBox::<u8, ()>::decode(&mut &units[..])
+            .unwrap()
+            .slice
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn walk() {
+    use thin_dst::walk::*;
+    struct Node(ThinBox<usize, Node>);
+    impl Children for Node {
+        fn children(&self) -> &[Node] {
+            &self.0.slice
+        }
+    }
+
+    let mut node = Node(ThinBox::new(0, vec![]));
+    for depth in 1..10_000 {
+        node = Node(ThinBox::new(depth, vec![node]));
+    }
+    assert!(pre_order(&node)
+        .map(|node| node.0.head)
+        .eq((0..10_000).rev()));
+    assert!(post_order(&node).map(|node| node.0.head).eq(0..10_000));
+    graph::drop_deep(node.0, |child| Some(child.0));
+}
+
+#[test]
+fn ptr_eq() {
+    let rc = ThinRc::new(0u8, vec![1u8]);
+    let other = ThinRc::new(0u8, vec![1u8]);
+    assert!(ThinRc::ptr_eq(&rc, &rc.clone()));
+    assert!(!ThinRc::ptr_eq(&rc, &other));
+    assert!(ThinRc::shallow_eq(&rc, &other));
+}
+
+#[test]
+fn offsets() {
+    fn check<Head: Default, SliceItem: Default>() {
+        let boxed = ThinBox::new(Head::default(), vec![SliceItem::default()]);
+        let start = &*boxed as *const ThinData<Head, SliceItem> as *const u8 as usize;
+        let head = &boxed.head as *const Head as usize;
+        let slice = boxed.slice.as_ptr() as usize;
+        assert_eq!(head - start, ThinData::<Head, SliceItem>::HEAD_OFFSET);
+        assert_eq!(slice - start, ThinData::<Head, SliceItem>::SLICE_OFFSET);
+    }
+    check::<(), u8>();
+    check::<u8, u64>();
+    check::<u128, u8>();
+    check::<[u8; 3], u16>();
+}
+
+#[test]
+fn try_new() {
+    let boxed = ThinBox::try_new(0u8, vec![1u32, 2]).unwrap();
+    assert_eq!(boxed.slice, [1, 2]);
+    let huge = (0..usize::MAX / 4).map(|_| 0u64);
+    assert_eq!(
+        ThinBox::try_new((), huge).unwrap_err(),
+        TryAllocError::CapacityOverflow
+    );
+}
+
+#[test]
+fn max_len() {
+    assert_eq!(ThinData::<(), ()>::MAX_LEN, isize::MAX as usize);
+    assert_eq!(
+        ThinData::<u8, u64>::MAX_LEN,
+        (isize::MAX as usize - 7 - ThinData::<u8, u64>::SLICE_OFFSET) / 8
+    );
+    let too_long = (0..ThinData::<u8, ()>::MAX_LEN + 1).map(|_| ());
+    assert_eq!(
+        ThinBox::try_new(0u8, too_long).unwrap_err(),
+        TryAllocError::CapacityOverflow
+    );
+    let too_long = (0..ThinData::<u8, u64>::MAX_LEN + 1).map(|_| 0u64);
+    assert_eq!(
+        ThinBox::try_new(0u8, too_long).unwrap_err(),
+        TryAllocError::CapacityOverflow
+    );
+}
+
+#[test]
+fn from_iter_filtered() {
+    let words = ["a", "bb", "ccc", "dd"].iter().map(|s| s.to_string());
+    let boxed = ThinBox::from_iter_filtered(1u8, words, |s| s.len() == 2);
+    assert_eq!(boxed.slice, ["bb", "dd"]);
+    let empty = ThinBox::from_iter_filtered((), 0..10u32, |_| false);
+    assert!(empty.slice.is_empty());
+}
+
+#[test]
+fn tuple_eq() {
+    let arc = ThinArc::new("head", vec![1, 2, 3]);
+    assert!(arc == ("head", &[1, 2, 3][..]));
+    assert!(arc == ("head", vec![1, 2, 3]));
+    assert!(arc != ("tail", vec![1, 2, 3]));
+    assert!(*arc != ("head", &[1, 2][..]));
+}
+
+#[test]
+fn slice_access() {
+    let boxed = ThinBox::new(7u8, 0..10u32);
+    assert_eq!(*ThinBox::head_ref(&boxed), 7);
+    assert_eq!(ThinBox::slice_ref(&boxed, 2..4), [2, 3]);
+    assert_eq!(ThinBox::get(&boxed, 8..), Some(&[8, 9][..]));
+    assert_eq!(ThinBox::get(&boxed, 8..11), None);
+    assert_eq!(ThinBox::head_with_len(&boxed), (&7, 10));
+    let empty = ThinArc::new(String::from("empty"), Vec::<u64>::new());
+    assert_eq!(ThinArc::head_with_len(&empty), (&String::from("empty"), 0));
+}
+
+#[test]
+fn try_into_send() {
+    let shared = ThinRc::new(0u8, vec![String::from("a")]);
+    let tree = ThinRc::new((), vec![shared.clone()]);
+    let tree = ThinRc::try_into_send(tree).unwrap_err();
+    drop(shared);
+    let send = ThinRc::try_into_send(tree).unwrap();
+    std::thread::spawn(move || assert_eq!(send.into_inner().slice[0].slice, ["a"]))
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn from_parts() {
+    let boxed: ThinBox<u8, String> = (1, vec![String::from("a")].into_boxed_slice()).into();
+    assert!(boxed == (1, vec![String::from("a")]));
+    let arc: ThinArc<(), u32> = ((), Arc::<[u32]>::from(&[1, 2][..])).into();
+    assert_eq!(arc.slice, [1, 2]);
+    let rc: ThinRc<(), u32> = ((), vec![]).into();
+    assert!(rc.slice.is_empty());
+}
+
+#[test]
+fn new_zeroed() {
+    let mut bytes = ThinBox::new_zeroed(String::from("head"), 1000);
+    assert!(bytes.slice.iter().all(|&b| b == 0));
+    bytes.slice[999] = 1;
+    let arc: ThinArc<String, u8> = ThinArc::from(Arc::from(Box::from(bytes)));
+    assert_eq!(arc.head, "head");
+    assert_eq!(arc.slice.len(), 1000);
+    assert_eq!((arc.slice[998], arc.slice[999]), (0, 1));
+}
+
+#[test]
+fn drop_order() {
+    use std::cell::RefCell;
+    struct Noisy<'a>(&'a RefCell<Vec<u8>>, u8);
+    impl Drop for Noisy<'_> {
+        fn drop(&mut self) {
+            self.0.borrow_mut().push(self.1);
+        }
+    }
+
+    let log = RefCell::new(vec![]);
+    let child = |n| ThinBox::new(Noisy(&log, n), vec![Noisy(&log, n + 1)]);
+    drop(ThinBox::new(Noisy(&log, 0), vec![child(1), child(3)]));
+    assert_eq!(*log.borrow(), [0, 1, 2, 3, 4]);
+
+    // Flattening moves everything out without dropping any of it.
+    log.borrow_mut().clear();
+    let (head, rows) = ThinBox::flatten(ThinBox::new(Noisy(&log, 0), vec![child(1)]));
+    assert!(log.borrow().is_empty());
+    assert_eq!(head.1, 0);
+    assert_eq!(rows.len(), 1);
+    assert_eq!((rows[0].0 .1, rows[0].1[0].1), (1, 2));
+    drop((head, rows));
+    assert_eq!(*log.borrow(), [0, 1, 2]);
+}
+
+#[test]
+fn new_scan() {
+    let strings = ThinBox::new_scan((), 3, |prefix: &[String], i| {
+        prefix.concat() + &i.to_string()
+    });
+    assert_eq!(strings.slice, ["0", "01", "0012"]);
+}
+
+#[test]
+fn sorted_set() {
+    let set = ThinSortedSet::new((), vec![4, 2, 8, 6]);
+    assert_eq!(set.range(3..=6), [4, 6]);
+    assert_eq!(set.range(..2), [] as [i32; 0]);
+    assert_eq!(set.range(9..), [] as [i32; 0]);
+    assert!(ThinSortedSet::from_sorted(ThinBox::new((), vec![1, 1])).is_err());
+    let boxed: ThinBox<(), i32> = set.into();
+    assert!(ThinSortedSet::from_sorted(boxed).is_ok());
+}
+
+#[test]
+fn bitset() {
+    let mut bits = ThinBitSet::new((), 129);
+    assert_eq!(bits.words().len(), 129 / (usize::BITS as usize) + 1);
+    for i in [0, 63, 64, 128] {
+        bits.set(i, true);
+    }
+    bits.set(63, false);
+    assert_eq!(bits.iter_ones().collect::<Vec<_>>(), [0, 64, 128]);
+    assert_eq!(bits.count_ones(), 3);
+    assert!(ThinBitSet::new((), 0).iter_ones().next().is_none());
+    let panicked = std::panic::catch_unwind(|| ThinBitSet::new((), 10).get(10));
+    assert!(panicked.is_err());
+}
+
+#[test]
+fn map() {
+    let mut map = ThinMap::new((), (0..10).map(|i| (i % 4, i.to_string())));
+    assert_eq!(map.slice.len(), 4);
+    assert_eq!(map.get(&1).map(String::as_str), Some("9"));
+    map.get_mut(&1).unwrap().push('!');
+    assert_eq!(map.values().collect::<Vec<_>>(), ["8", "9!", "6", "7"]);
+    assert!(!map.contains_key(&4));
+}
+
+#[test]
+fn str_table() {
+    let table = ThinStrTable::new((), vec!["", "héllo", "", "wörld!"]);
+    assert_eq!(table.len(), 4);
+    assert_eq!(table.as_str(), "héllowörld!");
+    assert_eq!(table.get(3), Some("wörld!"));
+    assert_eq!(table.iter().filter(|s| s.is_empty()).count(), 2);
+    let empty = ThinStrTable::new((), Vec::<String>::new());
+    assert!(empty.is_empty() && empty.as_str().is_empty());
+}
+
+#[test]
+fn arc_list() {
+    let list = ThinArcList::with_capacity(1);
+    std::thread::scope(|s| {
+        for t in 0..4 {
+            let list = &list;
+            s.spawn(move || {
+                for i in 0..20 {
+                    list.push(format!("{}-{}", t, i));
+                }
+            });
+        }
+    });
+    let mut items: Vec<&String> = list.iter().collect();
+    assert_eq!(items.len(), 80);
+    items.sort();
+    items.dedup();
+    assert_eq!(items.len(), 80);
+}
+
+#[test]
+#[cfg(all(inline_asm, target_arch = "x86_64", target_feature = "cmpxchg16b"))]
+fn versioned_cell() {
+    let cell = VersionedThinArcCell::new(None);
+    std::thread::scope(|s| {
+        for t in 0..4 {
+            let cell = &cell;
+            s.spawn(move || {
+                for i in 0..100 {
+                    let mut new = Some(ThinArc::new(t, vec![i]));
+                    let mut seen = cell.load();
+                    while let Err((back, now)) = cell.compare_exchange(seen, new) {
+                        new = back;
+                        seen = now;
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!(cell.load().version, 400);
+    assert_eq!(cell.into_inner().unwrap().slice, [99]);
+}
+
+#[test]
+fn thin_arc_cell() {
+    use std::sync::atomic::Ordering;
+    let cell = ThinArcCell::new(None);
+    std::thread::scope(|s| {
+        for t in 0..4 {
+            let cell = &cell;
+            s.spawn(move || {
+                for i in 0..100 {
+                    let mut new = Some(ThinArc::new(t, vec![i]));
+                    let mut seen = cell.load(Ordering::Relaxed);
+                    while let Err((back, now)) =
+                        cell.compare_exchange(seen, new, Ordering::AcqRel, Ordering::Relaxed)
+                    {
+                        new = back;
+                        seen = now;
+                    }
+                }
+            });
+        }
+    });
+    let last = cell.swap(None, Ordering::AcqRel).unwrap();
+    assert_eq!(last.slice, [99]);
+    assert_eq!(cell.load(Ordering::Acquire), None);
+    assert!(cell.into_inner().is_none());
+}
+
+#[test]
+fn project_pin() {
+    use std::{marker::PhantomPinned, pin::Pin};
+    struct Pinned(u32, PhantomPinned);
+    let mut boxed = ThinBox::into_pin(ThinBox::new(Pinned(1, PhantomPinned), vec![0u8, 1]));
+    let (head, slice) = boxed.as_mut().project_pin();
+    assert_eq!(head.0, 1);
+    slice.swap(0, 1);
+    assert_eq!(boxed.slice, [1, 0]);
+
+    let mut boxed = ThinBox::into_pin(ThinBox::new((), (0..3).map(|i| Pinned(i, PhantomPinned))));
+    let (_, items) = boxed.as_mut().project_pin_items();
+    let items: Vec<Pin<&mut Pinned>> = items.collect();
+    assert_eq!(items.iter().map(|item| item.0).sum::<u32>(), 3);
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn from_stream() {
+    use std::{
+        future::Future,
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+
+    struct Count(u32);
+    impl futures_core::Stream for Count {
+        type Item = Result<u32, u32>;
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.0 += 1;
+            Poll::Ready(Some(if self.0 < 4 { Ok(self.0) } else { Err(self.0) }))
+        }
+    }
+
+    fn ready<F: Future>(fut: F) -> F::Output {
+        match pin!(fut).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future not ready"),
+        }
+    }
+
+    let boxed = ready(ThinBox::try_from_stream((), 3, Count(0))).unwrap();
+    assert_eq!(boxed.slice, [1, 2, 3]);
+    let err = ready(ThinBox::<(), u32>::try_from_stream((), 5, Count(0))).unwrap_err();
+    assert_eq!(err, 4);
+}
+
+#[test]
+#[cfg(feature = "bytes")]
+fn from_buf() {
+    use bytes::Buf;
+    let frame = (&b"thin"[..]).chain(&b""[..]).chain(&b"-dst"[..]);
+    let boxed = ThinBox::from_buf(4, frame);
+    assert_eq!(boxed, (4, &b"thin-dst"[..]));
+    assert!(ThinBox::from_buf((), &b""[..]).slice.is_empty());
+}
+
+#[test]
+fn bytewise() {
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    struct Pair(u16, u16);
+    unsafe impl BytewiseEq for Pair {}
+
+    let a = Bytewise(ThinBox::new(1, (0..100).map(|i| Pair(i, i + 1))));
+    let b = Bytewise(ThinBox::new(1, (0..100).map(|i| Pair(i, i + 1))));
+    assert_eq!(a.slice_bytes().len(), 400);
+    assert_eq!(a, b);
+    assert_ne!(a, Bytewise(ThinBox::new(2, a.slice.iter().copied())));
+
+    use std::{collections::hash_map::RandomState, hash::BuildHasher};
+    let state = RandomState::new();
+    assert_eq!(state.hash_one(&a), state.hash_one(&b));
+}
+
+#[test]
+fn slice_by_head() {
+    let mut nodes: Vec<ThinRc<u32, ()>> = [5, 1, 4, 1, 3]
+        .iter()
+        .map(|&id| ThinRc::new(id, vec![]))
+        .collect();
+    nodes.sort_by_head_key(|&id| id);
+    assert_eq!(
+        nodes.iter().map(|n| n.head).collect::<Vec<_>>(),
+        [1, 1, 3, 4, 5]
+    );
+    assert_eq!(nodes.binary_search_by_head(|id| id.cmp(&4)), Ok(3));
+    assert_eq!(nodes.binary_search_by_head_key(&2, |&id| id), Err(2));
+    assert_eq!(nodes.partition_point_by_head(|&id| id < 2), 2);
+}
+
+#[test]
+fn holder() {
+    fn round_trip<P: ThinHolder<Head = String, Item = u8>>() {
+        let p = P::new("head".into(), vec![1, 2, 3]);
+        let p = unsafe { P::from_erased(P::erase(p)) };
+        assert_eq!(p.head, "head");
+        assert_eq!(p.slice, [1, 2, 3]);
+    }
+    round_trip::<ThinBox<_, _>>();
+    round_trip::<ThinArc<_, _>>();
+    round_trip::<ThinRc<_, _>>();
+    round_trip::<Box<ThinData<_, _>>>();
+    round_trip::<Arc<ThinData<_, _>>>();
+    round_trip::<std::rc::Rc<ThinData<_, _>>>();
+
+    let data = ThinBox::new((), vec![1]);
+    let erased = ThinPointer::erase(&*data);
+    assert_eq!(
+        unsafe { <&ThinData<(), u8>>::from_erased(erased) }.slice,
+        [1]
+    );
+}
+
+#[test]
+fn cast_item_slice() {
+    let bytes = ThinBox::new(7u64, vec![0u8; 12]);
+    let words = ThinBox::cast_item_slice::<u32>(bytes).unwrap();
+    assert_eq!(words.slice, [0; 3]);
+    let bytes = ThinBox::new((), vec![0u8; 3]);
+    let bytes = ThinBox::cast_item_slice::<u16>(bytes).unwrap_err();
+    let words: ThinBox<(), [u8; 3]> = ThinBox::cast_item_slice(bytes).unwrap();
+    assert_eq!(words.slice.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn live_counter() {
+    use thin_dst::testing::LiveCounter;
+    let counter = LiveCounter::new();
+    let guard = counter.guard();
+    let boxed = ThinBox::new(counter.track(), (0..3).map(|_| counter.track()));
+    assert_eq!(counter.live(), 4);
+    let leaked = std::mem::ManuallyDrop::new(boxed.clone());
+    drop(boxed);
+    assert_eq!(counter.live(), 4);
+    ThinBox::into_parts(std::mem::ManuallyDrop::into_inner(leaked));
+    counter.assert_none_live();
+}
+
+#[test]
+fn try_unwrap() {
+    let arc = ThinArc::new(String::from("head"), vec![String::from("item")]);
+    let weak = ThinArc::downgrade(&arc);
+    let arc = ThinArc::try_unwrap(arc).unwrap_err();
+    drop(weak);
+    let boxed = ThinArc::try_unwrap(arc).unwrap();
+    assert_eq!(boxed, (String::from("head"), vec![String::from("item")]));
+
+    let rc = ThinRc::new(String::from("head"), vec![String::from("item")]);
+    let clone = rc.clone();
+    let rc = ThinRc::try_unwrap(rc).unwrap_err();
+    drop(clone);
+    assert_eq!(ThinRc::try_unwrap(rc).unwrap().slice, ["item"]);
+}
+
+#[test]
+fn headless() {
+    let boxed: ThinSliceBox<u32> = vec![1, 2, 3].into();
+    assert_eq!(boxed, vec![1, 2, 3]);
+    let arc = ThinSliceArc::from(ThinSliceBox::as_slice(&boxed));
+    assert_eq!(arc, *ThinSliceBox::as_slice(&boxed));
+    let rc = ThinSliceRc::from_items(ThinSliceArc::iter(&arc).rev().copied());
+    assert_eq!(ThinSliceRc::as_slice(&rc), [3, 2, 1]);
+}
+
+#[test]
+fn headless_index() {
+    let mut boxed = ThinSliceBox::from_items(0..4);
+    for item in &mut boxed {
+        *item *= 2;
+    }
+    boxed[3] += 1;
+    assert_eq!(boxed[..], [0, 2, 4, 7]);
+    let rc = ThinSliceRc::from(boxed.as_ref());
+    assert_eq!((&rc).into_iter().max(), Some(&7));
+    assert_eq!(rc[1], 2);
+}
+
+#[test]
+fn slice_ptr() {
+    let boxed = ThinBox::new(1u16, vec![1u64, 2, 3]);
+    let ptr = boxed.slice_ptr();
+    assert_eq!(ptr.as_ptr() as *const u64, boxed.slice.as_ptr());
+    let erased = ThinBox::erase(boxed);
+    assert_eq!(ThinData::<u16, u64>::erased_slice_ptr(erased), ptr);
+    let boxed = unsafe { ThinBox::<u16, u64>::from_erased(erased) };
+    assert_eq!(ThinData::<u16, u64>::SLICE_STRIDE, 8);
+}
+
+#[test]
+#[cfg(feature = "stable-layout")]
+fn stable_layout() {
+    assert_eq!(LAYOUT_VERSION, 1);
+    let boxed = ThinBox::new(0x0102u16, vec![7u8, 8, 9]);
+    let erased = ThinBox::erase(boxed);
+    let image = unsafe { std::slice::from_raw_parts(erased.as_ptr().cast::<u8>(), 13) };
+    let [h0, h1] = 0x0102u16.to_ne_bytes();
+    assert_eq!(image, [3, 0, 0, 0, 0, 0, 0, 0, h0, h1, 7, 8, 9]);
+    drop(unsafe { ThinBox::<u16, u8>::from_erased(erased) });
+}
+
+#[test]
+fn head_any() {
+    let node = ThinArc::new(HeadAny::new(String::from("name")), vec![0u8]);
+    assert_eq!(node.head.as_any().downcast_ref::<String>().unwrap(), "name");
+    let erased = ThinArc::erase(node);
+    unsafe {
+        assert!(ThinRef::<HeadAny<u32>, u8>::downcast(erased).is_none());
+        let node = ThinRef::<HeadAny<String>, u8>::downcast(erased).unwrap();
+        assert_eq!(node.head.len(), 4);
+        drop(ThinArc::<HeadAny<String>, u8>::from_erased(erased));
+    }
+}
+
+#[test]
+fn map_items_in_place() {
+    let boxed = ThinBox::new(String::from("head"), (0..4).map(|i| i.to_string()));
+    let boxed = ThinBox::map_items_in_place(boxed, |s| s + "!");
+    assert_eq!(boxed.slice, ["0!", "1!", "2!", "3!"]);
+    let err = ThinBox::try_map_items_in_place(boxed, |s| if s == "2!" { Err(s) } else { Ok(s) });
+    assert_eq!(err.unwrap_err(), "2!");
+}
+
+#[test]
+fn iter_with_head() {
+    let mut boxed = ThinBox::new(
+        String::from("-"),
+        vec![String::from("a"), String::from("b")],
+    );
+    for (sep, item) in boxed.iter_with_head_mut() {
+        item.push_str(sep);
+    }
+    let iter = boxed.iter_with_head();
+    assert_eq!(iter.len(), 2);
+    let joined: Vec<String> = iter
+        .rev()
+        .map(|(sep, item)| format!("{}{}", item, sep))
+        .collect();
+    assert_eq!(joined, ["b--", "a--"]);
+}
+
+#[test]
+fn block_arena() {
+    use thin_dst::codec::*;
+    let mut file = Vec::new();
+    for i in 0..4u8 {
+        let items = (0..i).map(|j| ThinBox::new(j, vec![i; j as usize]));
+        ThinBox::new(i, items.collect::<Vec<_>>())
+            .encode(&mut file)
+            .unwrap();
+    }
+    // Large enough to need a chunk of its own.
+    ThinBox::new(4u8, vec![ThinBox::new(0u8, vec![0u8; 0]); 10_000])
+        .encode(&mut file)
+        .unwrap();
+
+    let arena = ThinBlockArena::<u8, ThinBox<u8, u8>>::decode_all(&file).unwrap();
+    assert_eq!(arena.len(), 5);
+    let third = arena.get(3).unwrap();
+    assert_eq!(third.head, 3);
+    assert_eq!(third.slice[2].slice, [3, 3]);
+    assert_eq!(arena.iter().last().unwrap().slice.len(), 10_000);
+    let _ = format!("{:?}", arena.get(1));
+
+    // A truncated record fails without leaking what was already decoded.
+    let err = ThinBlockArena::<u8, ThinBox<u8, u8>>::decode_all(&file[..file.len() - 1]);
+    assert_eq!(err.unwrap_err(), DecodeError::UnexpectedEnd);
+    let huge = [0xFF; 8];
+    let err = ThinBlockArena::<u8, u8>::decode_all(&huge);
+    assert_eq!(err.unwrap_err(), DecodeError::UnexpectedEnd);
+    let mut units = (isize::MAX as u64).to_le_bytes().to_vec();
+    units.push(0);
+    let err = ThinBlockArena::<u8, ()>::decode_all(&units);
+    assert_eq!(err.unwrap_err(), DecodeError::TooLong);
+    #[cfg(feature = "std")]
+    {
+        let arena = ThinBlockArena::<u8, ThinBox<u8, u8>>::read_all(&file[..]).unwrap();
+        assert_eq!(arena.len(), 5);
+        let err = ThinBlockArena::<u8, u8>::read_all(&huge[..]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    let mut many =
+        ThinBlockArena::new_many((0..100u8).map(|i| (i, vec![String::new(); i as usize])));
+    assert_eq!(many.len(), 100);
+    many.push(100, vec![String::from("last")]);
+    assert_eq!(many.get(100).unwrap().slice, ["last"]);
+    assert_eq!(many.get(42).unwrap().slice.len(), 42);
+}
+
+#[test]
+fn count_unique_allocations() {
+    struct Node(ThinArc<u64, Node>);
+    let leaf = ThinArc::new(0, vec![]);
+    let mut node = leaf.clone();
+    for i in 1..50 {
+        node = ThinArc::new(i, vec![Node(node.clone()), Node(node)]);
+    }
+    let stats = graph::count_unique_allocations(&node, |child| Some(&child.0));
+    assert_eq!(stats.allocations, 50);
+    let node_size = |len| ThinData::<u64, Node>::SLICE_OFFSET + len * std::mem::size_of::<Node>();
+    assert_eq!(stats.bytes, node_size(0) + 49 * node_size(2));
+
+    let alone = graph::count_unique_allocations(&leaf, |_| None);
+    assert_eq!(alone.allocations, 1);
+}
+
+#[test]
+fn thin_box_builder() {
+    let mut builder = ThinBox::with_capacity(String::from("head"), 4);
+    assert_eq!((builder.len(), builder.capacity()), (0, 4));
+    builder.spare_capacity_mut()[0].write(String::from("a"));
+    unsafe { builder.set_len(1) };
+    builder.spare_capacity_mut()[0].write(String::from("b"));
+    unsafe { builder.set_len(2) };
+    assert_eq!(builder.as_slice(), ["a", "b"]);
+    assert_eq!(builder.spare_capacity_mut().len(), 2);
+    let boxed = builder.assume_init_prefix();
+    assert_eq!(boxed.slice, ["a", "b"]);
+
+    let empty = ThinBox::<_, u8>::with_capacity((), 0).assume_init_prefix();
+    assert!(empty.slice.is_empty());
+}
+
+#[test]
+fn zip_with() {
+    let names = ThinBox::new((), vec!["a", "b"]);
+    let counts = ThinArc::new((), vec![1, 2]);
+    let zipped = ThinBox::zip_with(0, &names.slice, &counts.slice, |name, &n| name.repeat(n));
+    assert_eq!(zipped.slice, ["a", "bb"]);
+    let empty = ThinBox::<(), u8>::zip_with((), &[(); 0], &[(); 0], |_, _| 0);
+    assert!(empty.slice.is_empty());
+}
+
+#[test]
+fn thin_option() {
+    use std::num::NonZeroU32;
+    assert_eq!(
+        std::mem::size_of::<ThinOption<NonZeroU32, u8>>(),
+        std::mem::size_of::<usize>()
+    );
+    let mut row = ThinOption::new(NonZeroU32::new(1), vec![String::from("a")]);
+    row.slice[0].push('b');
+    let boxed = ThinOption::into_some(row).unwrap();
+    assert_eq!(boxed.head.get(), 1);
+    assert_eq!(boxed.slice, ["ab"]);
+
+    let mut row = ThinOption::from(boxed);
+    row.head = None;
+    let row = ThinOption::into_some(row).unwrap_err();
+    assert_eq!(ThinOption::into_inner(row.clone()).head, None);
+    assert_eq!(row, ThinOption::none(vec![String::from("ab")]));
+}
+
+#[test]
+fn jagged() {
+    let boxes: Vec<_> = (0..5)
+        .map(|i| ThinBox::new(i.to_string(), (0..i).map(|j| vec![j; i])))
+        .collect();
+    let mut jagged = ThinJagged::concat_all(boxes);
+    assert_eq!((jagged.len(), jagged.items_len()), (5, 10));
+    let (head, slice) = jagged.get_mut(3).unwrap();
+    head.push('!');
+    slice[2].push(9);
+    assert_eq!(jagged.get(3).unwrap().0, "3!");
+    assert_eq!(jagged.get(3).unwrap().1[2], [2, 2, 2, 9]);
+    assert!(jagged.get(5).is_none());
+    let lens: Vec<_> = jagged.iter().map(|(_, slice)| slice.len()).collect();
+    assert_eq!(lens, [0, 1, 2, 3, 4]);
+    let _ = format!("{:?}", jagged);
+
+    let empty = ThinJagged::<(), ()>::concat_all(vec![]);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn portable_hash() {
+    use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+    let hash = |data: &dyn Fn(&mut DefaultHasher)| {
+        let mut hasher = DefaultHasher::new();
+        data(&mut hasher);
+        hasher.finish()
+    };
+    let native = ThinBox::new(1u32, vec![2usize, 3]);
+    let wide = ThinArc::new(1u32, vec![2u64, 3]);
+    assert_eq!(
+        hash(&|h| native.portable_hash(h)),
+        hash(&|h| wide.portable_hash(h))
+    );
+    assert_ne!(
+        hash(&|h| native.portable_hash(h)),
+        hash(&|h| ThinBox::new(1u32, vec![3usize, 2]).portable_hash(h))
+    );
+}
+
+#[test]
+fn erased_vec() {
+    let mut table: ErasedVec<ThinBox<String, u32>> =
+        (0..4).map(|i| ThinBox::new(i.to_string(), 0..i)).collect();
+    table.extend(std::iter::once(ThinBox::new(String::from("x"), vec![])));
+    assert_eq!(table.len(), 5);
+    assert_eq!(table.swap_remove(1).head, "1");
+    assert_eq!(table.get(1).unwrap().head, "x");
+    assert_eq!(table.pop().unwrap().slice, [0, 1, 2]);
+    let _ = format!("{:?}", table);
+
+    let erased = ErasedVec::into_erased(table.clone());
+    let restored = unsafe { ErasedVec::<ThinBox<String, u32>>::from_erased(erased) };
+    let heads: Vec<_> = restored
+        .into_iter()
+        .rev()
+        .map(|node| node.head.clone())
+        .collect();
+    assert_eq!(heads, ["2", "x", "0"]);
+
+    let shared: ErasedVec<ThinArc<(), u8>> = vec![ThinArc::new((), vec![1])].into_iter().collect();
+    let mut iter = shared.clone().into_iter();
+    assert_eq!(iter.len(), 1);
+    drop(iter);
+    assert_eq!(shared.as_refs()[0].slice, [1]);
+}
+
+#[test]
+fn new_with_len() {
+    let words = "a bb ccc".split(' ').map(String::from);
+    let boxed = ThinBox::new_with_len((), 3, words);
+    assert_eq!(boxed.slice, ["a", "bb", "ccc"]);
+    let result = std::panic::catch_unwind(|| ThinBox::new_with_len((), 1, 0..2));
+    assert!(result.is_err());
+}
+
+#[test]
+fn thin_ref_projections() {
+    let boxed = ThinBox::new(String::from("head"), vec![1, 2, 3]);
+    let (head, tail) = {
+        let thin = ThinRef::from(&*boxed);
+        (
+            ThinRef::map_head(thin, String::as_str),
+            ThinRef::map_slice(thin, |slice| &slice[1..]),
+        )
+    };
+    assert_eq!((head, tail), ("head", &[2, 3][..]));
+    let fat: &ThinData<_, _> = ThinRef::from(&*boxed).into();
+    assert!(std::ptr::eq(fat, &*boxed));
+}
+
+#[test]
+fn sort_by_head() {
+    use std::collections::{BinaryHeap, HashSet};
+    let mut heap: BinaryHeap<_> = (0..5)
+        .map(|i| SortByHead(ThinArc::new(i % 3, vec![i])))
+        .collect();
+    assert_eq!(heap.pop().unwrap().head, 2);
+    assert_eq!(heap.pop().unwrap().head, 1);
+    assert_eq!(heap.len(), 3);
+
+    let set: HashSet<_> = vec![
+        SortByHead(ThinBox::new(1, vec![1])),
+        SortByHead(ThinBox::new(1, vec![2])),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(set.len(), 1);
+    let a = SortByHeadAndSlice(ThinRc::new(1, vec![1]));
+    let b = SortByHeadAndSlice(ThinRc::new(1, vec![2]));
+    assert!(a < b);
+    assert_eq!(b.into_inner().slice, [2]);
+}
+
+#[test]
+fn from_box_thin_data() {
+    let fat: Box<ThinData<String, u64>> = ThinBox::new(String::from("a"), vec![1, 2]).into();
+    let thin = ThinBox::from_box_thin_data(fat).unwrap();
+    assert_eq!(thin.slice, [1, 2]);
+    let fat: Box<ThinData<(), ()>> = ThinBox::new((), vec![(); 3]).into();
+    assert_eq!(ThinBox::from_box_thin_data(fat).unwrap().slice.len(), 3);
+}
+
+#[test]
+fn thin_bytes_mut() {
+    let mut buf = ThinBytesMut::zeroed(String::from("conn"), 8);
+    let mut front = buf.split_to(2);
+    let mut back = buf.split_off(4);
+    front.copy_from_slice(b"ab");
+    buf.copy_from_slice(b"cdef");
+    back.copy_from_slice(b"gh");
+    assert_eq!((front.len(), buf.len(), back.len()), (2, 4, 2));
+    assert!(buf.split_to(0).is_empty());
+
+    let (front, buf, back) = (front.freeze(), buf.freeze(), back.freeze());
+    let shared = buf.clone();
+    assert_eq!(*front, *b"ab");
+    assert_eq!(*shared, *b"cdef");
+    assert_eq!(*back, *b"gh");
+    assert_eq!(back.head(), "conn");
+    let buf = ThinBytes::into_thin_arc(buf).unwrap_err();
+    drop((front, back, shared));
+    assert!(ThinBytes::into_thin_arc(buf).is_err());
+
+    // Windows can be written on different threads while the others are cloned.
+    let mut buf = ThinBytesMut::zeroed((), 64);
+    let mut back = buf.split_off(32);
+    std::thread::scope(|s| {
+        s.spawn(|| back.fill(1));
+        s.spawn(|| {
+            buf.fill(2);
+            let _ = buf.split_to(0);
+        });
+    });
+    assert!(back.iter().all(|&b| b == 1) && buf.iter().all(|&b| b == 2));
+
+    let whole = ThinBytesMut::from(ThinBox::new(1u8, *b"xyz")).freeze();
+    let arc = ThinBytes::into_thin_arc(whole).unwrap();
+    assert_eq!(arc.slice, *b"xyz");
+}
+
+#[test]
+fn apply_updates() {
+    let mut column = ThinBox::new((0, 0), vec![1, 2, 3, 4, 5]);
+    column.apply_updates_with([(0, 10), (2, 30), (2, 31), (4, 50)], |head, items| {
+        *head = (items.iter().sum(), items.len());
+    });
+    assert_eq!(column.slice, [10, 2, 31, 4, 50]);
+    assert_eq!(column.head, (97, 5));
+    column.apply_updates(None);
+    assert_eq!(column.slice, [10, 2, 31, 4, 50]);
+    let batch: &[(usize, i32)] = &[(1, 20), (3, 40)];
+    column.apply_updates(batch.iter().copied());
+    assert_eq!(column.slice, [10, 20, 31, 40, 50]);
+
+    let unsorted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        column.apply_updates(vec![(3, 0), (1, 0)]);
+    }));
+    assert!(unsorted.is_err());
+    assert_eq!(column.slice, [10, 20, 31, 40, 50]);
+    let mut column = ThinBox::new((), vec![0; 2]);
+    let out_of_bounds = std::panic::catch_unwind(move || column.apply_updates(vec![(2, 1)]));
+    assert!(out_of_bounds.is_err());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn share_nodes() {
+    struct Node(ThinRc<u32, Node>);
+    impl AsRef<ThinRc<u32, Node>> for Node {
+        fn as_ref(&self) -> &ThinRc<u32, Node> {
+            &self.0
+        }
+    }
+    impl From<ThinRc<u32, Node>> for Node {
+        fn from(rc: ThinRc<u32, Node>) -> Self {
+            Node(rc)
+        }
+    }
+
+    // Each level refers to the one below twice, so the expanded tree has 2^64 leaves.
+    let mut node = ThinRc::new(0, vec![]);
+    for depth in 1..=64 {
+        node = ThinRc::new(depth, vec![Node(node.clone()), Node(node)]);
+    }
+    let json = serde_json::to_string(&ShareNodes(node)).unwrap();
+    let ShareNodes(root): ShareNodes<ThinRc<u32, Node>> = serde_json::from_str(&json).unwrap();
+    let mut node = &root;
+    for depth in (1..=64).rev() {
+        assert_eq!(node.head, depth);
+        assert!(ThinRc::ptr_eq(&node.slice[0].0, &node.slice[1].0));
+        node = &node.slice[0].0;
+    }
+    assert_eq!(node.head, 0);
+
+    let bad = serde_json::from_str::<ShareNodes<ThinRc<u32, Node>>>("[[0,[]],[1,[1]]]");
+    assert!(bad.is_err());
+    assert!(serde_json::from_str::<ShareNodes<ThinRc<u32, Node>>>("[]").is_err());
+}
+
+#[test]
+fn thin_map_cow() {
+    let mapped = ThinBox::new(String::from("k"), vec![1, 2, 3]);
+    let mut entry = ThinMapCow::from(ThinRef::from(&*mapped));
+    let snapshot = entry.clone();
+    entry.to_mut().head.push('!');
+    assert_eq!(entry.head, "k!");
+    assert!(snapshot.is_mapped());
+    assert_eq!(snapshot.head, "k");
+
+    // Mutating a shared owned copy copies it again.
+    let shared = entry.clone();
+    entry.to_mut().slice[0] = 0;
+    assert_eq!(entry.slice, [0, 2, 3]);
+    assert_eq!(shared.slice, [1, 2, 3]);
+    drop(shared);
+    let owned = entry.into_owned();
+    assert_eq!(ThinArc::strong_count(&owned), 1);
+    assert_eq!(snapshot.into_owned().slice, [1, 2, 3]);
+
+    let mut arc = ThinArc::new((), vec![1]);
+    let weak = ThinArc::downgrade(&arc);
+    assert!(ThinArc::get_mut(&mut arc).is_none());
+    drop(weak);
+    ThinArc::get_mut(&mut arc).unwrap().slice[0] = 2;
+    assert_eq!(arc.slice, [2]);
+}
+
+#[test]
+fn head_length() {
+    struct Bytes(u64);
+    struct Items(u8);
+    struct Unit(usize);
+    head_length! {
+        impl HeadLength<u16> for Bytes { bytes: 0 }
+        impl HeadLength<char> for Items { items: 0 }
+        impl HeadLength<()> for Unit { bytes: 0 }
+    }
+
+    assert_eq!(Bytes(6).slice_len(), Some(3));
+    assert_eq!(Bytes(5).slice_len(), None);
+    assert_eq!(Items(3).slice_len(), Some(3));
+    assert_eq!(Unit(0).slice_len(), None);
+
+    let boxed = ThinBox::new_with_head_len(Bytes(4), vec![1, 2]);
+    assert_eq!(boxed.slice, [1, 2]);
+    assert!(boxed.head_len_matches());
+    let boxed = ThinBox::new(Items(2), vec!['a', 'b', 'c']);
+    assert!(!boxed.head_len_matches());
+    assert!(
+        std::panic::catch_unwind(|| ThinBox::new_with_head_len(Items(2), "a".chars())).is_err()
+    );
+}
+
+#[test]
+fn validate_ptr() {
+    let arc = ThinArc::new(String::from("head"), vec![1u16, 2]);
+    let ptr = ThinArc::erase(arc);
+    assert!(unsafe { ThinData::<String, u16>::validate_ptr(ptr) });
+    let arc = unsafe { ThinArc::<String, u16>::from_erased(ptr) };
+    assert_eq!(arc.slice, [1, 2]);
+
+    #[repr(C, align(8))]
+    struct Header([usize; 2]);
+    let forged = Header([usize::MAX, 0]);
+    let ptr: ErasedPtr = std::ptr::NonNull::from(&forged).cast();
+    assert!(!unsafe { ThinData::<(), u8>::validate_ptr(ptr) });
+}
+
+#[test]
+fn boxed_items() {
+    use std::fmt::Debug;
+    let mut row: ThinBox<u8, Box<[u32]>> =
+        ThinBox::from_boxed_items(0, vec![vec![1, 2], vec![], vec![3]]);
+    assert_eq!(row.deref_items().len(), 3);
+    row.get_deref_mut(0).unwrap()[1] = 20;
+    for items in row.deref_items_mut().rev() {
+        items.reverse();
+    }
+    let lens: Vec<_> = row.deref_items().map(<[u32]>::len).collect();
+    assert_eq!(lens, [2, 0, 1]);
+    assert_eq!(row.get_deref(0), Some(&[20, 1][..]));
+    assert_eq!(row.get_deref(3), None);
+
+    let debug: ThinBox<(), Box<dyn Debug>> =
+        ThinBox::new((), vec![Box::new(1) as Box<dyn Debug>, Box::new("a")]);
+    let rendered: Vec<_> = debug
+        .deref_items()
+        .map(|item| format!("{:?}", item))
+        .collect();
+    assert_eq!(rendered, ["1", "\"a\""]);
+}
+
+#[test]
+#[cfg(feature = "bench")]
+fn bench_workloads() {
+    use thin_dst::bench::{BufferShape, TreeShape};
+    let shape = TreeShape {
+        depth: 5,
+        fanout: 4,
+        seed: 7,
+    };
+    let tree = shape.build();
+    let stats = graph::count_unique_allocations(&tree, |child| Some(&child.0));
+    assert_eq!(Some(stats.allocations), shape.nodes());
+    assert_eq!(shape.build(), tree);
+    assert_ne!(TreeShape { seed: 8, ..shape }.build(), tree);
+
+    let chain = TreeShape {
+        depth: 3,
+        fanout: 1,
+        seed: 0,
+    };
+    assert_eq!(chain.build().slice[0].0.slice[0].0.slice.len(), 0);
+    let lone = TreeShape {
+        depth: 3,
+        fanout: 0,
+        seed: 0,
+    };
+    assert_eq!(lone.nodes(), Some(1));
+    assert!(lone.build().slice.is_empty());
+
+    let shape = BufferShape {
+        count: 50,
+        min_len: 3,
+        max_len: 9,
+        seed: 1,
+    };
+    let buffers = shape.build();
+    assert!(buffers.iter().all(|b| (3..=9).contains(&b.slice.len())));
+    assert_eq!(buffers[49].head, 49);
+    assert_eq!(shape.build(), buffers);
+}
+
+#[test]
+fn trivial_head_eq() {
+    #[derive(Debug)]
+    struct Tag;
+    impl TrivialHead for Tag {}
+
+    let arc = ThinArc::new(Tag, vec!["a", "b"]);
+    assert_eq!(arc, *["a", "b"].as_slice());
+    assert_eq!(*arc, *["a", "b"].as_slice());
+    assert!(arc != vec!["a"]);
+    let rc = ThinRc::new(std::marker::PhantomData::<str>, vec![1]);
+    assert_eq!(rc, vec![1]);
+}
+
+#[test]
+fn collect_thin() {
+    use thin_dst::prelude::*;
+    let rc = vec![String::from("a"), String::from("b")]
+        .into_iter()
+        .rev()
+        .collect_thin_rc(2);
+    assert_eq!(rc.slice, ["b", "a"]);
+    let empty = std::iter::empty::<u8>().collect_thin_box(());
+    assert!(empty.slice.is_empty());
+}
+
+#[test]
+fn interner() {
+    use std::collections::hash_map::DefaultHasher;
+    let mut interner = ThinInterner::<u32, String, DefaultHasher>::new();
+    let nodes: Vec<_> = (0..100)
+        .map(|i| interner.intern(i % 30, vec![(i % 3).to_string()]))
+        .collect();
+    assert_eq!(interner.len(), 30);
+    assert!(ThinArc::ptr_eq(&nodes[0], &nodes[30]));
+    assert_eq!(interner.iter().count(), 30);
+
+    let hash = interner
+        .lookup(&7, &[String::from("x")])
+        .unwrap_err()
+        .hash();
+    assert_eq!(interner.len(), 30);
+    match interner.lookup(&7, &[String::from("x")]) {
+        Ok(_) => panic!("dropped pending intern was inserted"),
+        Err(pending) => {
+            assert_eq!(pending.hash(), hash);
+            pending.insert(ThinArc::new(7, vec![String::from("x")]));
+        }
+    }
+    assert_eq!(interner.intern(7, vec![String::from("x")]).slice, ["x"]);
+    assert_eq!(interner.len(), 31);
+}
+
+#[test]
+fn scoped_arena() {
+    let arena = ThinScopedArena::new();
+    let first = arena.alloc(String::from("first"), vec![0]);
+    // Keep every reference alive while the arena's list of nodes grows.
+    let nodes: Vec<_> = (1..100)
+        .map(|i| arena.alloc(i.to_string(), vec![i]))
+        .collect();
+    assert_eq!(first.head, "first");
+    assert_eq!(nodes[41].head, "42");
+    assert_eq!(nodes[41].slice, [42]);
+    assert_eq!(arena.len(), 100);
+
+    let boxes = arena.into_boxes();
+    assert_eq!(boxes[99].slice, [99]);
+
+    let lens = ThinScopedArena::scope(|arena| {
+        let a = arena.alloc((), vec![1, 2, 3]);
+        let b = arena.alloc((), a.slice.iter().map(|x| x * 2));
+        (a.slice.len(), b.slice[2])
+    });
+    assert_eq!(lens, (3, 6));
+}
+
+#[test]
+fn drop_incremental() {
+    let counter = Arc::new(());
+    let boxed = ThinBox::new(counter.clone(), vec![counter.clone(); 10]);
+    let mut dropping = ThinBox::drop_incremental(boxed);
+    assert!(!dropping.step(0));
+    assert_eq!(Arc::strong_count(&counter), 11);
+    assert!(!dropping.step(4));
+    assert_eq!(dropping.remaining(), 6);
+    assert_eq!(Arc::strong_count(&counter), 7);
+    assert!(dropping.step(100));
+    assert!(dropping.step(1));
+    assert_eq!(Arc::strong_count(&counter), 1);
+
+    let empty = ThinBox::new(counter.clone(), Vec::<u8>::new());
+    assert!(ThinBox::drop_incremental(empty).step(0));
+    assert_eq!(Arc::strong_count(&counter), 1);
+}
+
+#[test]
+fn is_unique() {
+    let arc = ThinArc::new((), vec![1]);
+    assert!(ThinArc::is_unique(&arc));
+    let weak = ThinArc::downgrade(&arc);
+    assert!(!ThinArc::is_unique(&arc));
+    drop(weak);
+    let clone = arc.clone();
+    assert!(!ThinArc::is_unique(&clone));
+    drop(arc);
+    assert!(ThinArc::is_unique(&clone));
+
+    let rc = ThinRc::new((), vec![1]);
+    let clone = rc.clone();
+    assert!(!ThinRc::is_unique(&rc));
+    drop(clone);
+    assert!(ThinRc::is_unique(&rc));
+}
+
+#[test]
+#[cfg(all(feature = "trace-alloc", debug_assertions))]
+fn trace_alloc() {
+    // Other tests trace allocations too, and may reuse a freed address,
+    // so entries are told apart by their backtrace rather than their pointer.
+    let is_live = |recorded: &Arc<std::backtrace::Backtrace>| {
+        trace::live()
+            .iter()
+            .any(|(_, backtrace)| Arc::ptr_eq(backtrace, recorded))
+    };
+
+    let node = ThinArc::new(String::from("node"), vec![1]);
+    let recorded = trace::construction_backtrace(&node).unwrap();
+    let clone = node.clone();
+    assert!(Arc::ptr_eq(
+        &recorded,
+        &trace::construction_backtrace(&clone).unwrap()
+    ));
+    drop(node);
+    let erased = ThinArc::erase(clone);
+    assert!(trace::live().iter().any(|&(ptr, _)| ptr == erased));
+
+    unsafe { ThinArc::<String, i32>::decrement_strong_count(erased) };
+    assert!(!is_live(&recorded));
+
+    let node = ThinArc::new(String::from("unwrapped"), vec![2]);
+    let recorded = trace::construction_backtrace(&node).unwrap();
+    let _boxed = ThinArc::try_unwrap(node).unwrap();
+    assert!(!is_live(&recorded));
+
+    // A weak pointer keeps the entry only while it can still upgrade.
+    let node = ThinArc::new(String::from("weak"), vec![3]);
+    let recorded = trace::construction_backtrace(&node).unwrap();
+    let weak = ThinArc::downgrade(&node);
+    let upgraded = weak.upgrade().unwrap();
+    drop(node);
+    assert!(is_live(&recorded));
+    drop(upgraded);
+    assert!(!is_live(&recorded));
+
+    // Whichever clone is dropped last, the entry is removed exactly once.
+    let node = ThinArc::new(String::from("shared"), vec![4]);
+    let recorded = trace::construction_backtrace(&node).unwrap();
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let node = node.clone();
+            std::thread::spawn(move || drop(node))
+        })
+        .collect();
+    drop(node);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert!(!is_live(&recorded));
+}
+
+#[test]
+fn thin_vec() {
+    let mut vec = ThinVec::with_capacity(String::from("words"), 1);
+    for word in "the quick brown fox jumps".split(' ') {
+        vec.push(String::from(word));
+    }
+    assert!(vec.capacity() >= 5);
+    assert_eq!(vec.pop().as_deref(), Some("jumps"));
+    vec[0].make_ascii_uppercase();
+    vec.head_mut().push('!');
+    vec.extend(vec![String::from("over")]);
+    let boxed = vec.into_thin_box();
+    assert_eq!(boxed.head, "words!");
+    assert_eq!(boxed.slice, ["THE", "quick", "brown", "fox", "over"]);
+
+    let mut empty = ThinVec::<u8, u64>::new(7);
+    empty.reserve(10);
+    assert!(empty.is_empty());
+    assert_eq!(empty.into_thin_box().slice.len(), 0);
+
+    let mut units = ThinVec::new(());
+    units.extend(vec![(); 3]);
+    assert_eq!(units.capacity(), ThinData::<(), ()>::MAX_LEN);
+    assert_eq!(units.into_thin_box().slice.len(), 3);
+}